@@ -13,7 +13,11 @@ use friend_tech_app::FRIEND_TECH_APP_ID;
 use abstract_app::objects::namespace::Namespace;
 use abstract_client::{AbstractClient, Publisher};
 use cw_orch::{anyhow, prelude::*, tokio::runtime::Runtime};
-use friend_tech_app::{msg::FriendTechAppInstantiateMsg, Friendtech, FRIEND_TECH_APP_VERSION};
+use cw_utils::Duration;
+use friend_tech_app::{
+    msg::{FriendTechAppInstantiateMsg, UncheckedFeeRecipient},
+    Friendtech, FRIEND_TECH_APP_VERSION,
+};
 use semver::Version;
 
 const LOCAL_MNEMONIC: &str = "clip hire initial neck maid actor venue client foam budget lock catalog sweet steak waste crater broccoli pipe steak sister coyote moment obvious choose";
@@ -44,7 +48,7 @@ fn main() -> anyhow::Result<()> {
     let publisher: Publisher<_> = abstract_client.publisher_builder(app_namespace).build()?;
 
     // Ensure the current sender owns the namespace
-    if publisher.account().owner()? != daemon.sender() {
+    if publisher.account().owner()? != daemon.sender_addr() {
         panic!("The current sender can not publish to this namespace. Please use the wallet that owns the Account that owns the Namespace.")
     }
 
@@ -58,8 +62,23 @@ fn main() -> anyhow::Result<()> {
     let app = account.install_app::<Friendtech<_>>(
         &FriendTechAppInstantiateMsg {
             username: "test".to_string(),
-            issuer_fee_collector: daemon.sender().to_string(),
+            fee_recipients: vec![UncheckedFeeRecipient {
+                address: daemon.sender_addr().to_string(),
+                weight: 100,
+            }],
             fee_denom: "ucosm".to_string(),
+            referral_fee_percentage: 0,
+            staking_fee_percentage: 0,
+            unbonding_period: Duration::Time(0),
+            listing_royalty_percentage: 0,
+            accrue_fees: false,
+            accounts_only: false,
+            opening_auction: None,
+            governance: None,
+            dynamic_fee: None,
+            transfer_royalty: None,
+            airdrop: None,
+            rate_limits: None,
         },
         &[],
     )?;
@@ -0,0 +1,49 @@
+use cosmwasm_std::{Addr, BlockInfo, Uint128};
+use cw_utils::Expiration;
+use sha2::{Digest, Sha256};
+
+use crate::{msg::AirdropParams, FriendTechAppError};
+
+/// Domain of the leaves, so a leaf of this tree never matches a hash signed for another purpose
+const LEAF_DOMAIN: &str = "friend_tech_airdrop";
+
+pub fn assert_airdrop(params: &AirdropParams, block: &BlockInfo) -> Result<(), FriendTechAppError> {
+    // Unclaimed keys can only be reclaimed once the claims expire
+    if params.total_amount.is_zero()
+        || decode_hash(&params.merkle_root).is_none()
+        || params.expires.is_expired(block)
+        || matches!(params.expires, Expiration::Never {})
+    {
+        return Err(FriendTechAppError::InvalidAirdrop {});
+    }
+    Ok(())
+}
+
+fn decode_hash(hash: &str) -> Option<[u8; 32]> {
+    hex::decode(hash).ok()?.try_into().ok()
+}
+
+/// Check that the claim of amount keys by the claimer is a leaf of the merkle tree
+/// Leaves hash `friend_tech_airdrop:<address>:<amount>`, pairs are hashed in ascending order
+pub fn verify_claim(
+    merkle_root: &str,
+    claimer: &Addr,
+    amount: Uint128,
+    proof: &[String],
+) -> Result<(), FriendTechAppError> {
+    let leaf: [u8; 32] =
+        Sha256::digest(format!("{LEAF_DOMAIN}:{claimer}:{amount}").as_bytes()).into();
+    let root = proof.iter().try_fold(leaf, |hash, sibling| {
+        let sibling = decode_hash(sibling).ok_or(FriendTechAppError::InvalidMerkleProof {})?;
+        let (first, second) = if hash <= sibling {
+            (hash, sibling)
+        } else {
+            (sibling, hash)
+        };
+        Ok::<_, FriendTechAppError>(Sha256::digest([first, second].concat()).into())
+    })?;
+    if Some(root) != decode_hash(merkle_root) {
+        return Err(FriendTechAppError::InvalidMerkleProof {});
+    }
+    Ok(())
+}
@@ -3,11 +3,15 @@ use abstract_app::sdk::{
     std::objects::module::ModuleId,
     AbstractSdkResult, AppInterface, ModuleInterface,
 };
-use cosmwasm_std::{Addr, CosmosMsg, Deps, Uint128};
+use cosmwasm_std::{wasm_execute, Addr, Coin, CosmosMsg, Deps, Uint128};
 
 use crate::{
-    msg::{BuyKeyCostResponse, FriendTechAppExecuteMsg, FriendTechAppQueryMsg, IssuerResponse},
-    FRIEND_TECH_APP_ID,
+    msg::{
+        BuyKeyCostResponse, ExecuteMsg, FriendTechAppExecuteMsg, FriendTechAppQueryMsg,
+        HasMinimumKeysResponse, HoldersResponse, HoldingResponse, IssuerResponse, LockResponse,
+        LocksResponse, PendingRewardsResponse, PortfolioResponse,
+    },
+    FriendTechAppError, FRIEND_TECH_APP_ID,
 };
 
 #[derive(Clone)]
@@ -21,7 +25,7 @@ pub struct FriendTech<'a, T: FriendTechInterface> {
 /// Interact with the friend tech app in your module.
 pub trait FriendTechInterface: AccountIdentification + Dependencies + ModuleIdentification {
     /// Construct a new friend_tech interface
-    fn friend_tech<'a>(&'a self, deps: Deps<'a>) -> FriendTech<Self> {
+    fn friend_tech<'a>(&'a self, deps: Deps<'a>) -> FriendTech<'a, Self> {
         FriendTech {
             base: self,
             deps,
@@ -38,18 +42,66 @@ impl<'a, T: FriendTechInterface> FriendTech<'a, T> {
         self.base.modules(self.deps).module_address(self.module_id)
     }
 
-    /// Buy key
-    pub fn buy_key(&self, amount: Uint128) -> AbstractSdkResult<CosmosMsg> {
-        self.base
-            .apps(self.deps)
-            .execute(self.module_id, FriendTechAppExecuteMsg::BuyKey { amount })
+    /// Buy key, credited to the recipient if given
+    pub fn buy_key(
+        &self,
+        amount: Uint128,
+        referrer: Option<String>,
+        recipient: Option<String>,
+    ) -> AbstractSdkResult<CosmosMsg> {
+        self.base.apps(self.deps).execute(
+            self.module_id,
+            FriendTechAppExecuteMsg::BuyKey {
+                amount,
+                referrer,
+                recipient,
+            },
+        )
     }
 
     /// Sell key
-    pub fn sell_key(&self, amount: Uint128) -> AbstractSdkResult<CosmosMsg> {
-        self.base
-            .apps(self.deps)
-            .execute(self.module_id, FriendTechAppExecuteMsg::SellKey { amount })
+    pub fn sell_key(
+        &self,
+        amount: Uint128,
+        referrer: Option<String>,
+    ) -> AbstractSdkResult<CosmosMsg> {
+        self.base.apps(self.deps).execute(
+            self.module_id,
+            FriendTechAppExecuteMsg::SellKey { amount, referrer },
+        )
+    }
+
+    /// Deposit funds as rewards for the key holders
+    /// Unlike other messages it is sent by the calling module, so the funds must be held by it
+    pub fn deposit_rewards(&self, funds: Vec<Coin>) -> AbstractSdkResult<CosmosMsg> {
+        let modules = self.base.modules(self.deps);
+        modules.assert_module_dependency(self.module_id)?;
+        let msg: ExecuteMsg = FriendTechAppExecuteMsg::DepositRewards {}.into();
+        Ok(wasm_execute(self.module_address()?, &msg, funds)?.into())
+    }
+
+    /// Release keys locked in escrow for the calling module back to their holder
+    /// Unlike other messages it is sent by the calling module, which must be the beneficiary of the lock
+    pub fn release_lock(&self, lock_id: u64) -> AbstractSdkResult<CosmosMsg> {
+        let msg: ExecuteMsg = FriendTechAppExecuteMsg::ReleaseLock { lock_id }.into();
+        Ok(wasm_execute(self.module_address()?, &msg, vec![])?.into())
+    }
+
+    /// Slash keys locked in escrow for the calling module, moving them to the recipient or burning them
+    /// Unlike other messages it is sent by the calling module, which must be the beneficiary of the lock
+    pub fn slash_lock(
+        &self,
+        lock_id: u64,
+        amount: Uint128,
+        recipient: Option<String>,
+    ) -> AbstractSdkResult<CosmosMsg> {
+        let msg: ExecuteMsg = FriendTechAppExecuteMsg::SlashLock {
+            lock_id,
+            amount,
+            recipient,
+        }
+        .into();
+        Ok(wasm_execute(self.module_address()?, &msg, vec![])?.into())
     }
 
     /// Query issuer
@@ -59,18 +111,116 @@ impl<'a, T: FriendTechInterface> FriendTech<'a, T> {
             .query(self.module_id, FriendTechAppQueryMsg::Issuer {})
     }
 
-    /// Query the cost of buying key
+    /// Query the cost of buying key, for a new trader
     pub fn query_buy_key_cost(&self, amount: Uint128) -> AbstractSdkResult<BuyKeyCostResponse> {
+        self.base.apps(self.deps).query(
+            self.module_id,
+            FriendTechAppQueryMsg::BuyKeyCost {
+                amount,
+                trader: None,
+            },
+        )
+    }
+
+    /// Query the cost of selling key, for a new trader
+    pub fn query_sell_key_cost(&self, amount: Uint128) -> AbstractSdkResult<BuyKeyCostResponse> {
+        self.base.apps(self.deps).query(
+            self.module_id,
+            FriendTechAppQueryMsg::SellKeyCost {
+                amount,
+                trader: None,
+            },
+        )
+    }
+
+    /// Query the rewards claimable by holder
+    pub fn query_pending_rewards(
+        &self,
+        holder: String,
+    ) -> AbstractSdkResult<PendingRewardsResponse> {
+        self.base.apps(self.deps).query(
+            self.module_id,
+            FriendTechAppQueryMsg::PendingRewards { holder },
+        )
+    }
+
+    /// Query the keys owned by holder
+    pub fn query_holding(&self, holder: String) -> AbstractSdkResult<HoldingResponse> {
         self.base
             .apps(self.deps)
-            .query(self.module_id, FriendTechAppQueryMsg::BuyKeyCost { amount })
+            .query(self.module_id, FriendTechAppQueryMsg::Holding { holder })
     }
 
-    /// Query the cost of selling key
-    pub fn query_sell_key_cost(&self, amount: Uint128) -> AbstractSdkResult<BuyKeyCostResponse> {
+    /// Query a page of key holders, ordered by address
+    pub fn query_holders(
+        &self,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> AbstractSdkResult<HoldersResponse> {
         self.base.apps(self.deps).query(
             self.module_id,
-            FriendTechAppQueryMsg::SellKeyCost { amount },
+            FriendTechAppQueryMsg::Holders { limit, start_after },
         )
     }
+
+    /// Query the value of the keys of holder against what they paid for them
+    pub fn query_portfolio(&self, holder: String) -> AbstractSdkResult<PortfolioResponse> {
+        self.base
+            .apps(self.deps)
+            .query(self.module_id, FriendTechAppQueryMsg::Portfolio { holder })
+    }
+
+    /// Query the keys locked in escrow by lock_id
+    pub fn query_lock(&self, lock_id: u64) -> AbstractSdkResult<LockResponse> {
+        self.base
+            .apps(self.deps)
+            .query(self.module_id, FriendTechAppQueryMsg::KeyLock { lock_id })
+    }
+
+    /// Query a page of the locks of holder, ordered by id
+    pub fn query_locks_by_holder(
+        &self,
+        holder: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> AbstractSdkResult<LocksResponse> {
+        self.base.apps(self.deps).query(
+            self.module_id,
+            FriendTechAppQueryMsg::LocksByHolder {
+                holder,
+                limit,
+                start_after,
+            },
+        )
+    }
+
+    /// Query whether holder owns at least min keys
+    pub fn query_has_minimum_keys(
+        &self,
+        holder: String,
+        min: Uint128,
+    ) -> AbstractSdkResult<HasMinimumKeysResponse> {
+        self.base.apps(self.deps).query(
+            self.module_id,
+            FriendTechAppQueryMsg::HasMinimumKeys { holder, min },
+        )
+    }
+
+    /// Whether holder owns at least min keys
+    pub fn is_holder(&self, holder: String, min: Uint128) -> AbstractSdkResult<bool> {
+        Ok(self.query_has_minimum_keys(holder, min)?.has_minimum_keys)
+    }
+
+    /// Error unless holder owns at least min keys, to gate features by key ownership
+    pub fn assert_holder(&self, holder: String, min: Uint128) -> Result<(), FriendTechAppError> {
+        let resp = self.query_has_minimum_keys(holder.clone(), min)?;
+        if !resp.has_minimum_keys {
+            return Err(FriendTechAppError::InsufficientKeys {
+                holder,
+                required: min,
+                owned: resp.amount,
+            });
+        }
+        Ok(())
+    }
 }
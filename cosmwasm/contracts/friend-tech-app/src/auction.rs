@@ -0,0 +1,21 @@
+use cosmwasm_std::{StdResult, Timestamp, Uint128};
+
+use crate::{state::Auction, utils::calculate_buy_price};
+
+/// Price of a key at the given time, decaying linearly from the start price to the end price
+pub fn auction_price(auction: &Auction, time: Timestamp) -> Uint128 {
+    if time >= auction.end_time {
+        return auction.end_price;
+    }
+    let elapsed = time.seconds().saturating_sub(auction.start_time.seconds());
+    let duration = auction.end_time.seconds() - auction.start_time.seconds();
+    auction.start_price
+        - (auction.start_price - auction.end_price).multiply_ratio(elapsed, duration)
+}
+
+/// Lowest end price at which the auction proceeds cover the curve price of the keys sold
+/// The average curve price grows with the keys sold, so covering the whole batch covers any part of it
+pub fn min_end_price(amount: Uint128) -> StdResult<Uint128> {
+    let curve_price = calculate_buy_price(Uint128::one(), amount)?;
+    Ok((curve_price + amount - Uint128::one()) / amount)
+}
@@ -5,7 +5,7 @@ use crate::{
         FriendTechAppExecuteMsg, FriendTechAppInstantiateMsg, FriendTechAppMigrateMsg,
         FriendTechAppQueryMsg,
     },
-    replies::{self, INSTANTIATE_REPLY_ID},
+    replies::{self, HOOK_REPLY_ID, INSTANTIATE_REPLY_ID},
     FRIEND_TECH_APP_ID, FRIEND_TECH_APP_VERSION,
 };
 
@@ -31,7 +31,10 @@ const FRIEND_TECH_APP: FriendTechApp =
         .with_query(handlers::query_handler)
         .with_migrate(handlers::migrate_handler)
         .with_dependencies(&[])
-        .with_replies(&[(INSTANTIATE_REPLY_ID, replies::instantiate_reply)]);
+        .with_replies(&[
+            (INSTANTIATE_REPLY_ID, replies::instantiate_reply),
+            (HOOK_REPLY_ID, replies::hook_reply),
+        ]);
 
 // Export handlers
 #[cfg(feature = "export")]
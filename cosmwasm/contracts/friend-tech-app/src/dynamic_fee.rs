@@ -0,0 +1,120 @@
+use cosmwasm_std::{Addr, BlockInfo, StdResult, Storage, Timestamp, Uint128};
+
+use crate::{
+    state::{DynamicFeeConfig, DYNAMIC_FEE, ROUND_TRIPS, TRADER_ACTIVITY},
+    utils::{assert_percentage, query_recent_volume},
+    FriendTechAppError,
+};
+
+/// Percentage of the price charged as issuer fee when the dynamic fee is off, and its floor otherwise
+pub const BASE_FEE_PERCENTAGE: u32 = 5;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+pub fn assert_dynamic_fee(dynamic_fee: &DynamicFeeConfig) -> Result<(), FriendTechAppError> {
+    assert_percentage(dynamic_fee.max_fee_percentage)?;
+    if dynamic_fee.volume_step.is_zero()
+        || dynamic_fee.round_trip_window == 0
+        || dynamic_fee.max_fee_percentage < BASE_FEE_PERCENTAGE
+    {
+        return Err(FriendTechAppError::InvalidDynamicFee {});
+    }
+    Ok(())
+}
+
+/// Surcharge of a round trip started by a trade at the given time, decaying linearly to zero over the window
+fn round_trip_surcharge(
+    dynamic_fee: &DynamicFeeConfig,
+    block: &BlockInfo,
+    started: Timestamp,
+) -> u32 {
+    let elapsed = block.time.seconds().saturating_sub(started.seconds());
+    if elapsed >= dynamic_fee.round_trip_window {
+        return 0;
+    }
+    let remaining = dynamic_fee.round_trip_window - elapsed;
+    (u64::from(dynamic_fee.round_trip_fee_percentage) * remaining / dynamic_fee.round_trip_window)
+        as u32
+}
+
+/// Issuer fee percentage of a trade, the base fee raised by the volume of the last 24 hours and,
+/// if the holder is known, by their round trips within the window, the one the trade would make included
+pub fn issuer_fee_percentage(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    holder: Option<&Addr>,
+    side: TradeSide,
+) -> StdResult<u32> {
+    let Some(dynamic_fee) = DYNAMIC_FEE.may_load(storage)? else {
+        return Ok(BASE_FEE_PERCENTAGE);
+    };
+
+    let volume = query_recent_volume(storage, block)?;
+    let volume_surcharge = (volume / dynamic_fee.volume_step).min(Uint128::from(u32::MAX));
+    let mut percentage = BASE_FEE_PERCENTAGE.saturating_add(volume_surcharge.u128() as u32);
+
+    if let Some(holder) = holder {
+        let activity = TRADER_ACTIVITY
+            .may_load(storage, holder)?
+            .unwrap_or_default();
+        let last_opposite_trade = match side {
+            TradeSide::Buy => activity.last_sell,
+            TradeSide::Sell => activity.last_buy,
+        };
+        let round_trips = ROUND_TRIPS.may_load(storage, holder)?.unwrap_or_default();
+        let surcharge = round_trips
+            .iter()
+            .chain(last_opposite_trade.as_ref())
+            .map(|started| round_trip_surcharge(&dynamic_fee, block, *started))
+            .fold(0, u32::saturating_add);
+        percentage = percentage.saturating_add(surcharge);
+    }
+    Ok(percentage.min(dynamic_fee.max_fee_percentage))
+}
+
+/// Remember when keys came into or left the balance of the holder, on the curve or between holders,
+/// and count a round trip when it reverses a trade made within the window
+/// Only tracked while the dynamic fee is on
+pub fn record_trade(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    holder: &Addr,
+    side: TradeSide,
+) -> StdResult<()> {
+    let Some(dynamic_fee) = DYNAMIC_FEE.may_load(storage)? else {
+        return Ok(());
+    };
+    let mut activity = TRADER_ACTIVITY
+        .may_load(storage, holder)?
+        .unwrap_or_default();
+    let (last_trade, last_opposite_trade) = match side {
+        TradeSide::Buy => (&mut activity.last_buy, &mut activity.last_sell),
+        TradeSide::Sell => (&mut activity.last_sell, &mut activity.last_buy),
+    };
+    *last_trade = Some(block.time);
+    // The reversed trade is used up, selling the same keys in several parts is a single round trip
+    let started = last_opposite_trade.take();
+    TRADER_ACTIVITY.save(storage, holder, &activity)?;
+
+    let mut round_trips = ROUND_TRIPS.may_load(storage, holder)?.unwrap_or_default();
+    round_trips.retain(|started| round_trip_surcharge(&dynamic_fee, block, *started) > 0);
+    if let Some(started) =
+        started.filter(|started| round_trip_surcharge(&dynamic_fee, block, *started) > 0)
+    {
+        round_trips.push(started);
+    }
+    // A round trip adds at least a point when counted, the ones past the cap cannot raise the fee
+    if round_trips.len() > dynamic_fee.max_fee_percentage as usize {
+        round_trips.remove(0);
+    }
+    if round_trips.is_empty() {
+        ROUND_TRIPS.remove(storage, holder);
+        Ok(())
+    } else {
+        ROUND_TRIPS.save(storage, holder, &round_trips)
+    }
+}
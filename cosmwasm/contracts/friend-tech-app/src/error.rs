@@ -1,10 +1,10 @@
 use abstract_app::sdk::AbstractSdkError;
 use abstract_app::std::AbstractError;
 use abstract_app::AppError;
-use cosmwasm_std::{StdError, Uint128};
+use cosmwasm_std::{OverflowError, StdError, Uint128};
 use cw_asset::AssetError;
 use cw_controllers::AdminError;
-use cw_utils::PaymentError;
+use cw_utils::{Expiration, PaymentError};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -30,6 +30,9 @@ pub enum FriendTechAppError {
     #[error("{0}")]
     Payment(#[from] PaymentError),
 
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
     #[error("Account owner must be set to issue key")]
     AccountOwnerMustBeSetToIssueKey {},
 
@@ -41,4 +44,239 @@ pub enum FriendTechAppError {
 
     #[error("Issuer cannot sell last key")]
     IssuerCannotSellLastKey {},
+
+    #[error("The issuer's key is already held by the account owner")]
+    OwnerAlreadySynced {},
+
+    #[error("Percentage must be between 0 and 100, got: {percentage}")]
+    InvalidPercentage { percentage: u32 },
+
+    #[error("Trader cannot refer themselves")]
+    CannotReferSelf {},
+
+    #[error("No rewards to claim")]
+    NoRewardsToClaim {},
+
+    #[error("Insufficient unlocked keys, available: {available}, required: {required}")]
+    InsufficientUnlockedKeys {
+        available: Uint128,
+        required: Uint128,
+    },
+
+    #[error("Price curve step must be greater than zero")]
+    InvalidPriceCurveStep {},
+
+    #[error("Price curve range is empty, from supply: {from_supply}, to supply: {to_supply}")]
+    InvalidPriceCurveRange {
+        from_supply: Uint128,
+        to_supply: Uint128,
+    },
+
+    #[error("Unstake amount must be greater than zero")]
+    InvalidUnstakeAmount {},
+
+    #[error("Cannot unstake more than staked, staked: {staked}, to unstake: {to_unstake}")]
+    CannotUnstakeMoreThanStaked {
+        staked: Uint128,
+        to_unstake: Uint128,
+    },
+
+    #[error("Market is closed")]
+    MarketClosed {},
+
+    #[error("Market is not closed")]
+    MarketNotClosed {},
+
+    #[error("No keys to redeem")]
+    NoKeysToRedeem {},
+
+    #[error("Listing amount and price per key must be greater than zero")]
+    InvalidListing {},
+
+    #[error("Listing {listing_id} not found")]
+    ListingNotFound { listing_id: u64 },
+
+    #[error("Only the seller can cancel the listing")]
+    NotListingSeller {},
+
+    #[error("Seller cannot fill their own listing")]
+    CannotFillOwnListing {},
+
+    #[error("Fill amount must be greater than zero")]
+    InvalidFillAmount {},
+
+    #[error("Cannot fill more than listed, listed: {listed}, to fill: {to_fill}")]
+    CannotFillMoreThanListed { listed: Uint128, to_fill: Uint128 },
+
+    #[error("Lock amount must be greater than zero and the lock must expire in the future")]
+    InvalidLock {},
+
+    #[error("App {module_id} is not installed on the account")]
+    AppNotInstalled { module_id: String },
+
+    #[error("Lock {lock_id} not found")]
+    LockNotFound { lock_id: u64 },
+
+    #[error("Only the holder can unlock the keys")]
+    NotLockHolder {},
+
+    #[error("Only the beneficiary app can release or slash the keys")]
+    NotLockBeneficiary {},
+
+    #[error("Lock has not expired yet")]
+    LockNotExpired {},
+
+    #[error("Lock expired, its keys can no longer be slashed")]
+    LockExpired {},
+
+    #[error("Cannot slash more than locked, locked: {locked}, to slash: {to_slash}")]
+    CannotSlashMoreThanLocked { locked: Uint128, to_slash: Uint128 },
+
+    #[error("Auction needs keys, a duration and a start price not below the end price")]
+    InvalidAuction {},
+
+    #[error("Auction end price must cover the curve price of the keys, minimum: {min_end_price}")]
+    AuctionEndPriceTooLow { min_end_price: Uint128 },
+
+    #[error("No auction")]
+    NoAuction {},
+
+    #[error("Auction is in progress")]
+    AuctionInProgress {},
+
+    #[error("Auction has ended")]
+    AuctionEnded {},
+
+    #[error("Auction is already settled")]
+    AuctionSettled {},
+
+    #[error("No bid to claim")]
+    NoBidToClaim {},
+
+    #[error("Airdrop needs a hex encoded 32 byte merkle root, keys to grant and claims expiring in the future")]
+    InvalidAirdrop {},
+
+    #[error("Airdrop cannot be combined with an opening auction")]
+    AirdropWithAuction {},
+
+    #[error("Airdrop must be set before any key is traded")]
+    TradingStarted {},
+
+    #[error("No airdrop")]
+    NoAirdrop {},
+
+    #[error("Airdrop is not funded yet")]
+    AirdropNotFunded {},
+
+    #[error("Airdrop is already funded")]
+    AirdropFunded {},
+
+    #[error("Invalid merkle proof")]
+    InvalidMerkleProof {},
+
+    #[error("Airdrop already claimed")]
+    AirdropAlreadyClaimed {},
+
+    #[error("Claim exceeds the keys left in the airdrop, left: {left}")]
+    AirdropExhausted { left: Uint128 },
+
+    #[error("Airdrop claims expired")]
+    AirdropExpired {},
+
+    #[error("Airdrop claims are open until {expires}")]
+    AirdropNotExpired { expires: Expiration },
+
+    #[error("No unclaimed airdrop keys to reclaim")]
+    NothingToReclaim {},
+
+    #[error("Reward per key overflow")]
+    RewardPerKeyOverflow {},
+
+    #[error("No fees to claim")]
+    NoFeesToClaim {},
+
+    #[error("Insufficient accrued fees, accrued: {accrued}, budget: {budget}")]
+    InsufficientAccruedFees { accrued: Uint128, budget: Uint128 },
+
+    #[error("Budget {budget} does not cover a single key")]
+    BudgetTooLow { budget: Uint128 },
+
+    #[error("Fee recipient weights must add up to 100, got: {total}")]
+    InvalidFeeRecipientWeights { total: Uint128 },
+
+    #[error("Hook {contract} is already registered")]
+    HookAlreadyRegistered { contract: String },
+
+    #[error("Hook {contract} is not registered")]
+    HookNotRegistered { contract: String },
+
+    #[error("Cannot register more than {max} hooks")]
+    TooManyHooks { max: u32 },
+
+    #[error("{address} is not the proxy of an Abstract account")]
+    NotAbstractAccount { address: String },
+
+    #[error(
+        "Dynamic fee needs a volume step, a round trip window and a max fee not below the base fee"
+    )]
+    InvalidDynamicFee {},
+
+    #[error("Rate limits need a window and limits above zero")]
+    InvalidRateLimits {},
+
+    #[error("Too many trades, retry at block {retry_at_height}")]
+    TradeRateLimited { retry_at_height: u64 },
+
+    #[error("Block volume limit reached, retry at block {retry_at_height}")]
+    BlockVolumeLimited { retry_at_height: u64 },
+
+    #[error("Trade of {price} exceeds the block volume limit of {max_block_volume}, split it")]
+    TradeExceedsBlockLimit {
+        price: Uint128,
+        max_block_volume: Uint128,
+    },
+
+    #[error("Transfer needs a positive amount and a recipient other than the sender")]
+    InvalidTransfer {},
+
+    #[error("Governance is disabled")]
+    GovernanceDisabled {},
+
+    #[error(
+        "Quorum and threshold must be above 0 and at most 1, the voting period must not be empty"
+    )]
+    InvalidGovernance {},
+
+    #[error("Proposal {proposal_id} not found")]
+    ProposalNotFound { proposal_id: u64 },
+
+    #[error("Voting on proposal {proposal_id} is closed")]
+    VotingClosed { proposal_id: u64 },
+
+    #[error("Already voted on proposal {proposal_id}")]
+    AlreadyVoted { proposal_id: u64 },
+
+    #[error("No keys to vote with")]
+    NoVotingPower {},
+
+    #[error("Proposal {proposal_id} has not passed")]
+    ProposalNotPassed { proposal_id: u64 },
+
+    #[error(
+        "Proposal {proposal_id} moves funds or calls the account, it needs the owner's approval"
+    )]
+    ProposalNotApproved { proposal_id: u64 },
+
+    #[error("{holder} holds no key, name the holder of the issuer's key in the migrate msg")]
+    IssuerKeyNotHeld { holder: String },
+
+    #[error("Cannot migrate from state version {from}, latest known version is {latest}")]
+    UnknownStateVersion { from: u32, latest: u32 },
+
+    #[error("{holder} holds {owned} keys, at least {required} required")]
+    InsufficientKeys {
+        holder: String,
+        required: Uint128,
+        owned: Uint128,
+    },
 }
@@ -0,0 +1,28 @@
+use cosmwasm_std::{Addr, Order, StdResult, Storage, Uint128};
+
+use crate::state::locks;
+
+/// Keys of the holder locked in escrow for other apps
+pub fn escrowed_keys(storage: &dyn Storage, holder: &Addr) -> StdResult<Uint128> {
+    locks()
+        .idx
+        .holder
+        .prefix(holder.clone())
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, lock)| lock.amount))
+        .sum()
+}
+
+/// Remove every lock of the holder, unlocking the escrowed keys
+pub fn remove_holder_locks(storage: &mut dyn Storage, holder: &Addr) -> StdResult<()> {
+    let lock_ids = locks()
+        .idx
+        .holder
+        .prefix(holder.clone())
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for lock_id in lock_ids {
+        locks().remove(storage, lock_id)?;
+    }
+    Ok(())
+}
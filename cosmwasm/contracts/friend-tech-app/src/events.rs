@@ -0,0 +1,586 @@
+//! Typed events emitted by the app
+//!
+//! Every action emits one `friend_tech` event holding the action, the schema version and the
+//! attributes of its event struct, so indexers can parse it back with [`FriendTechEvent::from_event`]
+
+use cosmwasm_std::{
+    from_json, to_json_string, Addr, Attribute, Decimal, Decimal256, Event, Response, StdError,
+    StdResult, Uint128,
+};
+use cw_utils::{Duration, Expiration};
+
+use crate::{
+    contract::FriendTechApp,
+    msg::AirdropParams,
+    state::{
+        DynamicFeeConfig, FeeRecipient, GovernanceConfig, HookFailure, RateLimits, TransferRoyalty,
+        VoteOption,
+    },
+};
+use abstract_app::traits::AbstractResponse;
+
+/// Type of the events emitted by the app, chains report it prefixed with `wasm-`
+pub const EVENT_TYPE: &str = "friend_tech";
+/// Version of the attribute schema, bump it on every breaking change to an event
+/// 2: `accounts_only` on instantiate, `transfer_royalty` on fill_listing
+/// 3: `expires` on set_airdrop and in the airdrop of instantiate, `royalty` on slash_lock
+pub const EVENT_VERSION: &str = "3";
+
+pub trait FriendTechEvent: Sized {
+    const ACTION: &'static str;
+
+    fn attributes(&self) -> Vec<Attribute>;
+
+    fn from_attributes(attributes: &[Attribute]) -> StdResult<Self>;
+
+    fn to_event(&self) -> Event {
+        Event::new(EVENT_TYPE)
+            .add_attribute("action", Self::ACTION)
+            .add_attribute("version", EVENT_VERSION)
+            .add_attributes(self.attributes())
+    }
+
+    /// Parse the event, it must be of this action and of the current schema version
+    fn from_event(event: &Event) -> StdResult<Self> {
+        if event.ty != EVENT_TYPE && event.ty != format!("wasm-{EVENT_TYPE}") {
+            return Err(StdError::parse_err(Self::ACTION, "not a friend tech event"));
+        }
+        let action = attribute(&event.attributes, "action");
+        if action != Some(Self::ACTION) {
+            return Err(StdError::parse_err(
+                Self::ACTION,
+                format!("unexpected action: {}", action.unwrap_or_default()),
+            ));
+        }
+        let version = attribute(&event.attributes, "version");
+        if version != Some(EVENT_VERSION) {
+            return Err(StdError::parse_err(
+                Self::ACTION,
+                format!("unsupported version: {}", version.unwrap_or_default()),
+            ));
+        }
+        Self::from_attributes(&event.attributes)
+    }
+
+    /// Parse the first event of this action among the events of a transaction
+    fn find(events: &[Event]) -> StdResult<Self> {
+        events
+            .iter()
+            .find(|event| {
+                (event.ty == EVENT_TYPE || event.ty == format!("wasm-{EVENT_TYPE}"))
+                    && attribute(&event.attributes, "action") == Some(Self::ACTION)
+            })
+            .ok_or_else(|| StdError::not_found(Self::ACTION))
+            .and_then(Self::from_event)
+    }
+}
+
+/// Response of the action emitting its event
+pub fn event_response<E: FriendTechEvent>(app: &FriendTechApp, event: E) -> Response {
+    app.response(E::ACTION).add_event(event.to_event())
+}
+
+fn attribute<'a>(attributes: &'a [Attribute], key: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|attribute| attribute.key == key)
+        .map(|attribute| attribute.value.as_str())
+}
+
+/// Value of an event attribute, empty optional values are left out of the event
+pub trait AttributeValue: Sized {
+    fn encode(&self) -> Option<String>;
+
+    fn decode(key: &str, value: Option<&str>) -> StdResult<Self>;
+}
+
+fn required<'a>(key: &str, value: Option<&'a str>) -> StdResult<&'a str> {
+    value.ok_or_else(|| StdError::parse_err(key, "missing attribute"))
+}
+
+macro_rules! display_attribute_value {
+    ($($ty:ty),*) => {
+        $(
+            impl AttributeValue for $ty {
+                fn encode(&self) -> Option<String> {
+                    Some(self.to_string())
+                }
+
+                fn decode(key: &str, value: Option<&str>) -> StdResult<Self> {
+                    required(key, value)?
+                        .parse()
+                        .map_err(|err| StdError::parse_err(key, err))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! json_attribute_value {
+    ($($ty:ty),*) => {
+        $(
+            impl AttributeValue for $ty {
+                fn encode(&self) -> Option<String> {
+                    to_json_string(self).ok()
+                }
+
+                fn decode(key: &str, value: Option<&str>) -> StdResult<Self> {
+                    from_json(required(key, value)?)
+                }
+            }
+        )*
+    };
+}
+
+display_attribute_value!(String, Uint128, Decimal, Decimal256, u32, u64, bool);
+json_attribute_value!(
+    Duration,
+    Expiration,
+    Vec<FeeRecipient>,
+    GovernanceConfig,
+    DynamicFeeConfig,
+    TransferRoyalty,
+    AirdropParams,
+    RateLimits,
+    Vec<Addr>
+);
+
+impl AttributeValue for Addr {
+    fn encode(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+
+    fn decode(key: &str, value: Option<&str>) -> StdResult<Self> {
+        Ok(Addr::unchecked(required(key, value)?))
+    }
+}
+
+impl AttributeValue for HookFailure {
+    fn encode(&self) -> Option<String> {
+        Some(
+            match self {
+                HookFailure::Revert => "revert",
+                HookFailure::Ignore => "ignore",
+            }
+            .to_string(),
+        )
+    }
+
+    fn decode(key: &str, value: Option<&str>) -> StdResult<Self> {
+        match required(key, value)? {
+            "revert" => Ok(HookFailure::Revert),
+            "ignore" => Ok(HookFailure::Ignore),
+            value => Err(StdError::parse_err(
+                key,
+                format!("unknown hook failure: {value}"),
+            )),
+        }
+    }
+}
+
+impl AttributeValue for VoteOption {
+    fn encode(&self) -> Option<String> {
+        Some(
+            match self {
+                VoteOption::Yes => "yes",
+                VoteOption::No => "no",
+                VoteOption::Abstain => "abstain",
+            }
+            .to_string(),
+        )
+    }
+
+    fn decode(key: &str, value: Option<&str>) -> StdResult<Self> {
+        match required(key, value)? {
+            "yes" => Ok(VoteOption::Yes),
+            "no" => Ok(VoteOption::No),
+            "abstain" => Ok(VoteOption::Abstain),
+            value => Err(StdError::parse_err(key, format!("unknown vote: {value}"))),
+        }
+    }
+}
+
+impl<T: AttributeValue> AttributeValue for Option<T> {
+    fn encode(&self) -> Option<String> {
+        self.as_ref().and_then(T::encode)
+    }
+
+    fn decode(key: &str, value: Option<&str>) -> StdResult<Self> {
+        value.map(|value| T::decode(key, Some(value))).transpose()
+    }
+}
+
+/// Declare the event structs, every field is an attribute named after it
+macro_rules! friend_tech_events {
+    ($(
+        $(#[$meta:meta])*
+        $name:ident($action:literal) {
+            $($(#[$field_meta:meta])* $field:ident: $ty:ty,)*
+        }
+    )*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Clone, Debug, PartialEq)]
+            pub struct $name {
+                $($(#[$field_meta])* pub $field: $ty,)*
+            }
+
+            impl FriendTechEvent for $name {
+                const ACTION: &'static str = $action;
+
+                fn attributes(&self) -> Vec<Attribute> {
+                    let mut attributes = vec![];
+                    $(
+                        if let Some(value) = self.$field.encode() {
+                            attributes.push(Attribute::new(stringify!($field), value));
+                        }
+                    )*
+                    attributes
+                }
+
+                fn from_attributes(attributes: &[Attribute]) -> StdResult<Self> {
+                    Ok(Self {
+                        $(
+                            $field: AttributeValue::decode(
+                                stringify!($field),
+                                attribute(attributes, stringify!($field)),
+                            )?,
+                        )*
+                    })
+                }
+            }
+        )*
+    };
+}
+
+friend_tech_events! {
+    InstantiateEvent("instantiate") {
+        /// Account owner, who holds the first key unless in accounts only mode
+        issuer: Addr,
+        username: String,
+        fee_denom: String,
+        fee_recipients: Vec<FeeRecipient>,
+        referral_fee_percentage: u32,
+        staking_fee_percentage: u32,
+        unbonding_period: Duration,
+        listing_royalty_percentage: u32,
+        accrue_fees: bool,
+        accounts_only: bool,
+        governance: Option<GovernanceConfig>,
+        dynamic_fee: Option<DynamicFeeConfig>,
+        transfer_royalty: Option<TransferRoyalty>,
+        airdrop: Option<AirdropParams>,
+        rate_limits: Option<RateLimits>,
+    }
+
+    MigrateEvent("migrate") {
+        from_state_version: u32,
+        to_state_version: u32,
+        referral_fee_percentage: u32,
+        staking_fee_percentage: u32,
+        unbonding_period: Duration,
+        listing_royalty_percentage: u32,
+        accrue_fees: bool,
+    }
+
+    BuyKeyEvent("buy_key") {
+        issuer: Addr,
+        payer: Addr,
+        recipient: Addr,
+        amount: Uint128,
+        /// Price paid into the curve
+        price: Uint128,
+        /// Part of the issuer fee paid to the fee recipients
+        issuer_fee: Uint128,
+        referrer: Option<Addr>,
+        referral_fee: Uint128,
+        staking_fee: Uint128,
+        /// Supply after the trade
+        supply: Uint128,
+        /// Keys of the recipient after the trade
+        holding: Uint128,
+    }
+
+    SellKeyEvent("sell_key") {
+        issuer: Addr,
+        seller: Addr,
+        amount: Uint128,
+        /// Price paid out of the curve
+        price: Uint128,
+        /// Part of the issuer fee paid to the fee recipients
+        issuer_fee: Uint128,
+        referrer: Option<Addr>,
+        referral_fee: Uint128,
+        staking_fee: Uint128,
+        /// Supply after the trade
+        supply: Uint128,
+        /// Keys of the seller after the trade
+        holding: Uint128,
+    }
+
+    DepositRewardsEvent("deposit_rewards") {
+        depositor: Addr,
+        amount: Uint128,
+        /// Value of the reward per key accumulator after the deposit
+        reward_per_key: Decimal256,
+    }
+
+    ClaimRewardsEvent("claim_rewards") {
+        holder: Addr,
+        amount: Uint128,
+    }
+
+    StakeEvent("stake") {
+        staker: Addr,
+        amount: Uint128,
+        /// Keys staked by the staker after the action
+        staked: Uint128,
+    }
+
+    UnstakeEvent("unstake") {
+        staker: Addr,
+        amount: Uint128,
+        /// Keys staked by the staker after the action
+        staked: Uint128,
+        release_at: Expiration,
+    }
+
+    ClaimStakingRewardsEvent("claim_staking_rewards") {
+        staker: Addr,
+        amount: Uint128,
+    }
+
+    ListKeysEvent("list_keys") {
+        listing_id: u64,
+        seller: Addr,
+        amount: Uint128,
+        price_per_key: Uint128,
+    }
+
+    CancelListingEvent("cancel_listing") {
+        listing_id: u64,
+        seller: Addr,
+        /// Keys left in the listing when cancelled
+        amount: Uint128,
+    }
+
+    LockEvent("lock") {
+        lock_id: u64,
+        holder: Addr,
+        amount: Uint128,
+        until: Expiration,
+        beneficiary_app: String,
+    }
+
+    UnlockEvent("unlock") {
+        lock_id: u64,
+        holder: Addr,
+        amount: Uint128,
+    }
+
+    ReleaseLockEvent("release_lock") {
+        lock_id: u64,
+        holder: Addr,
+        amount: Uint128,
+    }
+
+    SlashLockEvent("slash_lock") {
+        lock_id: u64,
+        holder: Addr,
+        amount: Uint128,
+        /// Receiver of the slashed keys, they are burned when empty
+        recipient: Option<Addr>,
+        /// Transfer royalty paid by the beneficiary app, zero on burns
+        royalty: Uint128,
+    }
+
+    FillListingEvent("fill_listing") {
+        listing_id: u64,
+        buyer: Addr,
+        seller: Addr,
+        amount: Uint128,
+        price: Uint128,
+        /// Part of the price paid to the fee recipients
+        royalty: Uint128,
+        /// Part of the transfer royalty above the royalty on the price, paid by the buyer on top of the price
+        transfer_royalty: Uint128,
+        /// Keys left in the listing after the fill
+        remaining: Uint128,
+    }
+
+    TransferKeysEvent("transfer_keys") {
+        sender: Addr,
+        recipient: Addr,
+        amount: Uint128,
+        /// Paid to the fee recipients, none when the recipient is exempt
+        royalty: Uint128,
+    }
+
+    BidEvent("bid") {
+        bidder: Addr,
+        /// Keys won, bids beyond the keys left are cut down
+        amount: Uint128,
+        price: Uint128,
+        /// Keys sold by the auction after the bid
+        sold: Uint128,
+    }
+
+    SetAirdropEvent("set_airdrop") {
+        merkle_root: String,
+        total_amount: Uint128,
+        expires: Expiration,
+    }
+
+    FundAirdropEvent("fund_airdrop") {
+        /// Keys minted for the claimers
+        amount: Uint128,
+        /// Curve price paid into the reserve
+        price: Uint128,
+        /// Supply after the mint
+        supply: Uint128,
+    }
+
+    ClaimAirdropEvent("claim_airdrop") {
+        claimer: Addr,
+        amount: Uint128,
+        /// Keys of the claimer after the claim
+        holding: Uint128,
+    }
+
+    ReclaimAirdropEvent("reclaim_airdrop") {
+        /// Unclaimed keys burned
+        amount: Uint128,
+        /// Curve price of the keys taken out of the reserve, their pro rata share once the market is closed
+        price: Uint128,
+        /// Rewards accrued on the keys since the funding
+        rewards: Uint128,
+        /// Supply after the burn
+        supply: Uint128,
+    }
+
+    SettleAuctionEvent("settle_auction") {
+        sold: Uint128,
+        clearing_price: Uint128,
+        /// Part of the proceeds added to the reserve
+        curve_price: Uint128,
+        /// Part of the proceeds paid to the fee recipients
+        surplus: Uint128,
+        /// Supply after the settlement
+        supply: Uint128,
+    }
+
+    ClaimAuctionEvent("claim_auction") {
+        bidder: Addr,
+        amount: Uint128,
+        refund: Uint128,
+        /// Keys of the bidder after the claim
+        holding: Uint128,
+    }
+
+    ClaimFeesEvent("claim_fees") {
+        amount: Uint128,
+        /// Destination of the fees, the fee recipients when empty
+        to: Option<Addr>,
+    }
+
+    BuybackAndBurnEvent("buyback_and_burn") {
+        /// Keys bought and burned
+        amount: Uint128,
+        /// Part of the accrued fees distributed to the holders as rewards
+        price: Uint128,
+        /// Supply after the burn, the same as before it
+        supply: Uint128,
+        /// Keys burned since instantiation
+        burned: Uint128,
+    }
+
+    UpdateFeeRecipientsEvent("update_fee_recipients") {
+        fee_recipients: Vec<FeeRecipient>,
+    }
+
+    RegisterHookEvent("register_hook") {
+        contract: Addr,
+        on_failure: HookFailure,
+    }
+
+    UnregisterHookEvent("unregister_hook") {
+        contract: Addr,
+    }
+
+    /// Emitted by the reply of a failed hook whose failures are ignored
+    HookFailedEvent("hook_failed") {
+        error: String,
+    }
+
+    UpdateDynamicFeeEvent("update_dynamic_fee") {
+        /// New dynamic fee model, the issuer fee is flat when empty
+        dynamic_fee: Option<DynamicFeeConfig>,
+    }
+
+    UpdateRateLimitsEvent("update_rate_limits") {
+        /// New rate limits, trading is unlimited when empty
+        rate_limits: Option<RateLimits>,
+    }
+
+    UpdateTransferRoyaltyEvent("update_transfer_royalty") {
+        /// New transfer royalty, transfers are free when empty
+        royalty: Option<TransferRoyalty>,
+    }
+
+    UpdateRoyaltyExemptionsEvent("update_royalty_exemptions") {
+        added: Vec<Addr>,
+        removed: Vec<Addr>,
+    }
+
+    UpdateGovernanceEvent("update_governance") {
+        /// New governance parameters, governance is disabled when empty
+        governance: Option<GovernanceConfig>,
+    }
+
+    ProposeEvent("propose") {
+        proposal_id: u64,
+        proposer: Addr,
+        start_height: u64,
+        expires: Expiration,
+    }
+
+    VoteEvent("vote") {
+        proposal_id: u64,
+        voter: Addr,
+        vote: VoteOption,
+        weight: Uint128,
+    }
+
+    ApproveProposalEvent("approve_proposal") {
+        proposal_id: u64,
+    }
+
+    UpdateApprovalExemptionsEvent("update_approval_exemptions") {
+        added: Vec<Addr>,
+        removed: Vec<Addr>,
+    }
+
+    ExecuteProposalEvent("execute_proposal") {
+        proposal_id: u64,
+    }
+
+    SyncOwnerEvent("sync_owner") {
+        previous_owner: Addr,
+        owner: Addr,
+        /// Keys moved, none when the previous owner no longer held the issuer's key
+        amount: Uint128,
+    }
+
+    CloseMarketEvent("close_market") {
+        supply: Uint128,
+        reserve: Uint128,
+    }
+
+    RedeemEvent("redeem") {
+        holder: Addr,
+        amount: Uint128,
+        payout: Uint128,
+        /// Supply after the redemption
+        supply: Uint128,
+    }
+}
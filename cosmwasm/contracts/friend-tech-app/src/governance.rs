@@ -0,0 +1,101 @@
+use abstract_app::{
+    sdk::features::AccountIdentification,
+    std::{
+        manager::{self, ModuleInfosResponse},
+        version_control::AccountBase,
+    },
+};
+use cosmwasm_std::{Addr, BlockInfo, CosmosMsg, Decimal, Deps, StdResult, Storage, WasmMsg};
+use cw_utils::Duration;
+
+use crate::{
+    contract::{FriendTechApp, FriendTechAppResult},
+    msg::ProposalStatus,
+    state::{GovernanceConfig, Proposal, APPROVAL_EXEMPTIONS},
+    FriendTechAppError,
+};
+
+/// Page size when listing the modules installed on the account
+const MODULE_INFOS_LIMIT: u8 = 10;
+
+pub fn assert_governance(governance: &GovernanceConfig) -> Result<(), FriendTechAppError> {
+    let valid_share = |share: Decimal| !share.is_zero() && share <= Decimal::one();
+    let empty_period = match governance.voting_period {
+        Duration::Height(blocks) => blocks == 0,
+        Duration::Time(seconds) => seconds == 0,
+    };
+    if !valid_share(governance.quorum) || !valid_share(governance.threshold) || empty_period {
+        return Err(FriendTechAppError::InvalidGovernance {});
+    }
+    Ok(())
+}
+
+/// Status of the proposal, the votes are only tallied once the voting period is over
+pub fn proposal_status(proposal: &Proposal, block: &BlockInfo) -> ProposalStatus {
+    if proposal.executed {
+        return ProposalStatus::Executed;
+    }
+    if !proposal.expires.is_expired(block) {
+        return ProposalStatus::Open;
+    }
+    let voted = proposal.yes + proposal.no + proposal.abstain;
+    let quorum_reached = !proposal.total_power.is_zero()
+        && Decimal::from_ratio(voted, proposal.total_power) >= proposal.quorum;
+    let threshold_reached = !proposal.yes.is_zero()
+        && Decimal::from_ratio(proposal.yes, proposal.yes + proposal.no) >= proposal.threshold;
+    if quorum_reached && threshold_reached {
+        ProposalStatus::Passed
+    } else {
+        ProposalStatus::Rejected
+    }
+}
+
+/// Contracts of the account the app is installed on, its manager and proxy and every module installed on it
+pub fn account_contracts(deps: Deps, app: &FriendTechApp) -> FriendTechAppResult<Vec<Addr>> {
+    let AccountBase { manager, proxy } = app.account_base(deps)?;
+    let mut contracts = vec![manager.clone(), proxy];
+    let mut start_after = None;
+    loop {
+        let ModuleInfosResponse { module_infos } = deps.querier.query_wasm_smart(
+            &manager,
+            &manager::QueryMsg::ModuleInfos {
+                start_after,
+                limit: Some(MODULE_INFOS_LIMIT),
+            },
+        )?;
+        let last_page = module_infos.len() < MODULE_INFOS_LIMIT as usize;
+        start_after = module_infos.last().map(|info| info.id.clone());
+        contracts.extend(module_infos.into_iter().map(|info| info.address));
+        if last_page {
+            return Ok(contracts);
+        }
+    }
+}
+
+/// Whether the proposal needs the owner's approval before it runs
+/// Only calls sending no funds to contracts the owner exempted run on the vote of the holders alone, any
+/// other call could move the funds or tokens of the account, the account and its modules are never exempt
+pub fn needs_owner_approval(
+    storage: &dyn Storage,
+    proposal: &Proposal,
+    account_contracts: &[Addr],
+) -> StdResult<bool> {
+    for msg in &proposal.msgs {
+        let CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            funds,
+            ..
+        }) = msg
+        else {
+            return Ok(true);
+        };
+        let contract = Addr::unchecked(contract_addr);
+        if !funds.is_empty()
+            || account_contracts.contains(&contract)
+            || !APPROVAL_EXEMPTIONS.has(storage, &contract)
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
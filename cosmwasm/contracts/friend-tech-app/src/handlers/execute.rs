@@ -1,41 +1,263 @@
-use super::query::{query_buy_key_cost, query_sell_key_cost};
+use super::query::{keys_for_budget, query_buy_key_cost, query_sell_key_cost};
 use crate::{
+    airdrop::{assert_airdrop, verify_claim},
+    auction::auction_price,
     contract::{FriendTechApp, FriendTechAppResult},
-    msg::FriendTechAppExecuteMsg,
-    state::{CONFIG, HOLDERS, SUPPLY},
-    utils::get_account_owner_addr,
+    dynamic_fee::{assert_dynamic_fee, record_trade, TradeSide},
+    escrow::remove_holder_locks,
+    events::{
+        event_response, ApproveProposalEvent, BidEvent, BuyKeyEvent, BuybackAndBurnEvent,
+        CancelListingEvent, ClaimAirdropEvent, ClaimAuctionEvent, ClaimFeesEvent,
+        ClaimRewardsEvent, ClaimStakingRewardsEvent, CloseMarketEvent, DepositRewardsEvent,
+        ExecuteProposalEvent, FillListingEvent, FundAirdropEvent, ListKeysEvent, LockEvent,
+        ProposeEvent, ReclaimAirdropEvent, RedeemEvent, RegisterHookEvent, ReleaseLockEvent,
+        SellKeyEvent, SetAirdropEvent, SettleAuctionEvent, SlashLockEvent, StakeEvent,
+        SyncOwnerEvent, TransferKeysEvent, UnlockEvent, UnregisterHookEvent, UnstakeEvent,
+        UpdateApprovalExemptionsEvent, UpdateDynamicFeeEvent, UpdateFeeRecipientsEvent,
+        UpdateGovernanceEvent, UpdateRateLimitsEvent, UpdateRoyaltyExemptionsEvent,
+        UpdateTransferRoyaltyEvent, VoteEvent,
+    },
+    governance::{account_contracts, assert_governance, needs_owner_approval, proposal_status},
+    hooks::{key_balance_hooks, MAX_HOOKS},
+    listings::remove_seller_listings,
+    msg::{AirdropParams, FriendTechAppExecuteMsg, ProposalStatus, UncheckedFeeRecipient},
+    rate_limit::{assert_rate_limits, record_rate_limited_trade},
+    royalty::{assert_transfer_royalty, gift_royalty, transfer_royalty},
+    staking::{
+        claim_staking_rewards, distribute_staking_fee, query_stake, release_stake, stake,
+        unlocked_keys, unstake,
+    },
+    state::{
+        listings, locks, Airdrop, Ballot, Config, DynamicFeeConfig, GovernanceConfig,
+        HolderRewards, HookFailure, KeyLock, Listing, Proposal, RateLimits, TransferRoyalty,
+        VoteOption, ACCRUED_FEES, AIRDROP, AIRDROP_CLAIMS, APPROVAL_EXEMPTIONS, APPROVED_PROPOSALS,
+        AUCTION, BALLOTS, BIDS, BURNED, CONFIG, DYNAMIC_FEE, GOVERNANCE, HOLDER_REWARDS, HOOKS,
+        ISSUER, MARKET_CLOSED, NEXT_LISTING_ID, NEXT_LOCK_ID, NEXT_PROPOSAL_ID, PROPOSALS,
+        RATE_LIMITS, REFERRERS, RESERVE, REWARD_PER_KEY, ROYALTY_EXEMPTIONS, SUPPLY,
+        TRANSFER_ROYALTY,
+    },
+    utils::{
+        accrued_rewards, assert_account_holder, assert_market_open, assert_trading_not_started,
+        burn_keys, calculate_buy_price, distribute_rewards, fee_messages, get_account_owner_addr,
+        load_holding, may_load_holding, may_load_holding_at_height, multiply_percentage, pay_fees,
+        record_volume, save_holding, settle_rewards, validate_fee_recipients,
+    },
     FriendTechAppError,
 };
 
-use abstract_app::traits::AbstractResponse;
-use cosmwasm_std::{coins, BankMsg, DepsMut, Env, MessageInfo, Uint128};
-use cw_utils::must_pay;
+use abstract_app::sdk::{AccountAction, Execution, ModuleInterface};
+use cosmwasm_std::{
+    coins, Addr, BankMsg, CosmosMsg, DepsMut, Empty, Env, MessageInfo, Order, StdResult, Storage,
+    Uint128,
+};
+use cw_utils::{must_pay, nonpayable, Expiration};
 
 pub fn execute_handler(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     app: FriendTechApp,
     msg: FriendTechAppExecuteMsg,
 ) -> FriendTechAppResult {
-    // TODO: verify caller must be an abstract account?
     match msg {
-        FriendTechAppExecuteMsg::BuyKey { amount } => buy_key(deps, info, amount, app),
-        FriendTechAppExecuteMsg::SellKey { amount } => sell_key(deps, info, amount, app),
+        FriendTechAppExecuteMsg::BuyKey {
+            amount,
+            referrer,
+            recipient,
+        } => buy_key(deps, env, info, amount, referrer, recipient, app),
+        FriendTechAppExecuteMsg::SellKey { amount, referrer } => {
+            sell_key(deps, env, info, amount, referrer, app)
+        }
+        FriendTechAppExecuteMsg::DepositRewards {} => deposit_rewards(deps, info, app),
+        FriendTechAppExecuteMsg::ClaimRewards {} => claim_rewards(deps, info, app),
+        FriendTechAppExecuteMsg::Stake { amount } => stake_keys(deps, env, info, amount, app),
+        FriendTechAppExecuteMsg::Unstake { amount } => unstake_keys(deps, env, info, amount, app),
+        FriendTechAppExecuteMsg::ClaimStakingRewards {} => claim_staking_fee(deps, info, app),
+        FriendTechAppExecuteMsg::ListKeys {
+            amount,
+            price_per_key,
+        } => list_keys(deps, env, info, amount, price_per_key, app),
+        FriendTechAppExecuteMsg::CancelListing { listing_id } => {
+            cancel_listing(deps, info, listing_id, app)
+        }
+        FriendTechAppExecuteMsg::FillListing { listing_id, amount } => {
+            fill_listing(deps, env, info, listing_id, amount, app)
+        }
+        FriendTechAppExecuteMsg::Lock {
+            amount,
+            until,
+            beneficiary_app,
+        } => lock(deps, env, info, amount, until, beneficiary_app, app),
+        FriendTechAppExecuteMsg::Unlock { lock_id } => unlock(deps, env, info, lock_id, app),
+        FriendTechAppExecuteMsg::ReleaseLock { lock_id } => release_lock(deps, info, lock_id, app),
+        FriendTechAppExecuteMsg::SlashLock {
+            lock_id,
+            amount,
+            recipient,
+        } => slash_lock(deps, env, info, lock_id, amount, recipient, app),
+        FriendTechAppExecuteMsg::TransferKeys { recipient, amount } => {
+            transfer_keys(deps, env, info, recipient, amount, app)
+        }
+        FriendTechAppExecuteMsg::Bid { amount } => bid(deps, env, info, amount, app),
+        FriendTechAppExecuteMsg::SetAirdrop { airdrop } => {
+            set_airdrop(deps, env, info, airdrop, app)
+        }
+        FriendTechAppExecuteMsg::FundAirdrop {} => fund_airdrop(deps, env, info, app),
+        FriendTechAppExecuteMsg::ClaimAirdrop { amount, proof } => {
+            claim_airdrop(deps, env, info, amount, proof, app)
+        }
+        FriendTechAppExecuteMsg::ReclaimAirdrop {} => reclaim_airdrop(deps, env, info, app),
+        FriendTechAppExecuteMsg::SettleAuction {} => settle_auction(deps, env, info, app),
+        FriendTechAppExecuteMsg::ClaimAuction {} => claim_auction(deps, env, info, app),
+        FriendTechAppExecuteMsg::ClaimFees { to } => claim_fees(deps, info, to, app),
+        FriendTechAppExecuteMsg::BuybackAndBurn { budget } => {
+            buyback_and_burn(deps, info, budget, app)
+        }
+        FriendTechAppExecuteMsg::RegisterHook {
+            contract,
+            on_failure,
+        } => register_hook(deps, info, contract, on_failure, app),
+        FriendTechAppExecuteMsg::UnregisterHook { contract } => {
+            unregister_hook(deps, info, contract, app)
+        }
+        FriendTechAppExecuteMsg::UpdateDynamicFee { dynamic_fee } => {
+            update_dynamic_fee(deps, info, dynamic_fee, app)
+        }
+        FriendTechAppExecuteMsg::UpdateRateLimits { rate_limits } => {
+            update_rate_limits(deps, info, rate_limits, app)
+        }
+        FriendTechAppExecuteMsg::UpdateTransferRoyalty { royalty } => {
+            update_transfer_royalty(deps, info, royalty, app)
+        }
+        FriendTechAppExecuteMsg::UpdateRoyaltyExemptions { add, remove } => {
+            update_royalty_exemptions(deps, info, add, remove, app)
+        }
+        FriendTechAppExecuteMsg::UpdateGovernance { governance } => {
+            update_governance(deps, info, governance, app)
+        }
+        FriendTechAppExecuteMsg::Propose {
+            title,
+            description,
+            msgs,
+        } => propose(deps, env, info, title, description, msgs, app),
+        FriendTechAppExecuteMsg::Vote { proposal_id, vote } => {
+            vote_on_proposal(deps, env, info, proposal_id, vote, app)
+        }
+        FriendTechAppExecuteMsg::ApproveProposal { proposal_id } => {
+            approve_proposal(deps, info, proposal_id, app)
+        }
+        FriendTechAppExecuteMsg::UpdateApprovalExemptions { add, remove } => {
+            update_approval_exemptions(deps, info, add, remove, app)
+        }
+        FriendTechAppExecuteMsg::ExecuteProposal { proposal_id } => {
+            execute_proposal(deps, env, info, proposal_id, app)
+        }
+        FriendTechAppExecuteMsg::SyncOwner {} => sync_owner(deps, env, info, app),
+        FriendTechAppExecuteMsg::CloseMarket {} => close_market(deps, info, app),
+        FriendTechAppExecuteMsg::Redeem {} => redeem(deps, env, info, app),
+        FriendTechAppExecuteMsg::UpdateFeeRecipients { fee_recipients } => {
+            update_fee_recipients(deps, info, fee_recipients, app)
+        }
+    }
+}
+
+/// Issuer fee of a trade, split between the fee recipients, the referrer and the stakers
+struct FeeSplit {
+    issuer_fee: Uint128,
+    referral_fee: Uint128,
+    referrer: Option<Addr>,
+    staking_fee: Uint128,
+}
+
+impl FeeSplit {
+    /// Bank messages paying out the fees, empty transfers are skipped
+    /// The staking fee stays in the contract until stakers claim it, the issuer fee does too in accrual mode
+    fn messages(&self, storage: &mut dyn Storage, config: &Config) -> StdResult<Vec<BankMsg>> {
+        let mut msgs = pay_fees(storage, self.issuer_fee, config)?;
+        if let Some(referrer) = &self.referrer {
+            if !self.referral_fee.is_zero() {
+                msgs.push(BankMsg::Send {
+                    to_address: referrer.to_string(),
+                    amount: coins(self.referral_fee.u128(), &config.fee_denom),
+                });
+            }
+        }
+        Ok(msgs)
+    }
+}
+
+/// Carve the referral fee and the staking fee out of the issuer fee
+/// The trade is recorded in the referrer stats and the staking fee is distributed to the stakers
+fn split_issuer_fee(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    trader: &Addr,
+    referrer: Option<String>,
+    price: Uint128,
+    issuer_fee: Uint128,
+) -> FriendTechAppResult<FeeSplit> {
+    let staking_fee = distribute_staking_fee(
+        deps.storage,
+        &env.block,
+        issuer_fee,
+        config.staking_fee_percentage,
+    )?;
+
+    let Some(referrer) = referrer else {
+        return Ok(FeeSplit {
+            issuer_fee: issuer_fee - staking_fee,
+            referral_fee: Uint128::zero(),
+            referrer: None,
+            staking_fee,
+        });
+    };
+
+    let referrer = deps.api.addr_validate(&referrer)?;
+    if referrer == trader {
+        return Err(FriendTechAppError::CannotReferSelf {});
     }
+
+    let referral_fee = multiply_percentage(issuer_fee, config.referral_fee_percentage);
+    REFERRERS.update(deps.storage, &referrer, |stats| -> StdResult<_> {
+        let mut stats = stats.unwrap_or_default();
+        stats.volume += price;
+        stats.earnings += referral_fee;
+        Ok(stats)
+    })?;
+
+    Ok(FeeSplit {
+        issuer_fee: issuer_fee - referral_fee - staking_fee,
+        referral_fee,
+        referrer: Some(referrer),
+        staking_fee,
+    })
 }
 
 /// Anyone can call, buy key issued by the module owner
+/// The keys are credited to the recipient, or to the payer if no recipient is given
+/// The rate limits apply to the payer, the round trip surcharge to the recipient who holds the keys
 fn buy_key(
-    deps: DepsMut,
+    mut deps: DepsMut,
+    env: Env,
     msg_info: MessageInfo,
     amount: Uint128,
+    referrer: Option<String>,
+    recipient: Option<String>,
     app: FriendTechApp,
 ) -> FriendTechAppResult {
-    let buyer = &msg_info.sender;
+    let issuer_addr = get_account_owner_addr(deps.as_ref(), &app)?;
+    let payer = &msg_info.sender;
+    let recipient = &match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => payer.clone(),
+    };
+    assert_market_open(deps.storage)?;
     let config = CONFIG.load(deps.storage)?;
+    assert_account_holder(deps.branch(), &app, &config, payer)?;
+    assert_account_holder(deps.branch(), &app, &config, recipient)?;
     let paid = must_pay(&msg_info, &config.fee_denom)?;
-    let cost_resp = query_buy_key_cost(deps.as_ref(), amount)?;
+    let cost_resp = query_buy_key_cost(deps.as_ref(), &env.block, amount, Some(recipient))?;
     if cost_resp.total_cost > paid {
         return Err(crate::FriendTechAppError::InsufficientFunds {
             required: cost_resp.total_cost,
@@ -44,37 +266,74 @@ fn buy_key(
     }
 
     let old_supply = SUPPLY.load(deps.storage)?;
-    SUPPLY.save(deps.storage, &(old_supply + amount))?;
+    SUPPLY.save(deps.storage, &(old_supply + amount), env.block.height)?;
+    RESERVE.update(deps.storage, |reserve| -> StdResult<_> {
+        Ok(reserve + cost_resp.price)
+    })?;
+    record_rate_limited_trade(deps.storage, &env.block, payer, cost_resp.price)?;
+    record_volume(deps.storage, &env.block, cost_resp.price)?;
+    record_trade(deps.storage, &env.block, recipient, TradeSide::Buy)?;
 
-    if HOLDERS.has(deps.storage, buyer) {
-        let old_amount = HOLDERS.load(deps.storage, buyer)?;
-        HOLDERS.save(deps.storage, buyer, &(old_amount + amount))?;
-    } else {
-        HOLDERS.save(deps.storage, buyer, &amount)?;
-    }
+    let old_amount = may_load_holding(deps.storage, recipient)?.unwrap_or_default();
+    save_holding(
+        deps.storage,
+        &env.block,
+        recipient,
+        old_amount + amount,
+        cost_resp.total_cost,
+    )?;
 
-    Ok(app
-        .response("buy_key")
-        .add_message(BankMsg::Send {
-            to_address: config.issuer_fee_collector.to_string(),
-            amount: coins(cost_resp.issuer_fee.u128(), config.fee_denom),
-        })
-        .add_attribute("buyer", buyer)
-        .add_attribute("amount", amount))
+    let fee_split = split_issuer_fee(
+        deps.branch(),
+        &env,
+        &config,
+        payer,
+        referrer,
+        cost_resp.price,
+        cost_resp.issuer_fee,
+    )?;
+
+    let fee_msgs = fee_split.messages(deps.storage, &config)?;
+    let hook_msgs = key_balance_hooks(
+        deps.storage,
+        &[(recipient, old_amount, old_amount + amount)],
+    )?;
+    Ok(event_response(
+        &app,
+        BuyKeyEvent {
+            issuer: issuer_addr,
+            payer: payer.clone(),
+            recipient: recipient.clone(),
+            amount,
+            price: cost_resp.price,
+            issuer_fee: fee_split.issuer_fee,
+            referrer: fee_split.referrer,
+            referral_fee: fee_split.referral_fee,
+            staking_fee: fee_split.staking_fee,
+            supply: old_supply + amount,
+            holding: old_amount + amount,
+        },
+    )
+    .add_messages(fee_msgs)
+    .add_submessages(hook_msgs))
 }
 
 /// Anyone can call, sell key issued by the module owner
 fn sell_key(
-    deps: DepsMut,
+    mut deps: DepsMut,
+    env: Env,
     msg_info: MessageInfo,
     amount: Uint128,
+    referrer: Option<String>,
     app: FriendTechApp,
 ) -> FriendTechAppResult {
     let issuer_addr = &get_account_owner_addr(deps.as_ref(), &app)?;
     let seller = &msg_info.sender;
+    assert_market_open(deps.storage)?;
     let config = CONFIG.load(deps.storage)?;
+    assert_account_holder(deps.branch(), &app, &config, seller)?;
     let paid = must_pay(&msg_info, &config.fee_denom)?;
-    let cost_resp = query_sell_key_cost(deps.as_ref(), amount)?;
+    let cost_resp = query_sell_key_cost(deps.as_ref(), &env.block, amount, Some(seller))?;
     if cost_resp.total_cost > paid {
         return Err(crate::FriendTechAppError::InsufficientFunds {
             required: cost_resp.total_cost,
@@ -82,17 +341,15 @@ fn sell_key(
         });
     }
 
-    if !HOLDERS.has(deps.storage, seller) {
+    let Some(old_amount) = may_load_holding(deps.storage, seller)? else {
         return Err(FriendTechAppError::CannotSellMoreThanOwned {
             to_sell: amount,
             owned: Uint128::zero(),
         });
-    }
-
-    let old_amount = HOLDERS.load(deps.storage, seller)?;
+    };
 
     if amount <= old_amount {
-        if seller == issuer_addr && amount == old_amount {
+        if seller == ISSUER.load(deps.storage)? && amount == old_amount {
             return Err(FriendTechAppError::IssuerCannotSellLastKey {});
         }
     } else {
@@ -102,25 +359,1698 @@ fn sell_key(
         });
     }
 
+    let unlocked = unlocked_keys(deps.storage, &env.block, seller)?;
+    if amount > unlocked {
+        return Err(FriendTechAppError::InsufficientUnlockedKeys {
+            available: unlocked,
+            required: amount,
+        });
+    }
+
     let old_supply = SUPPLY.load(deps.storage)?;
 
-    SUPPLY.save(deps.storage, &(old_supply - amount))?;
-    if old_amount == amount {
-        HOLDERS.remove(deps.storage, seller);
+    SUPPLY.save(deps.storage, &(old_supply - amount), env.block.height)?;
+    RESERVE.update(deps.storage, |reserve| -> StdResult<_> {
+        Ok(reserve - cost_resp.price)
+    })?;
+    record_rate_limited_trade(deps.storage, &env.block, seller, cost_resp.price)?;
+    record_volume(deps.storage, &env.block, cost_resp.price)?;
+    record_trade(deps.storage, &env.block, seller, TradeSide::Sell)?;
+    save_holding(
+        deps.storage,
+        &env.block,
+        seller,
+        old_amount - amount,
+        Uint128::zero(),
+    )?;
+
+    let fee_split = split_issuer_fee(
+        deps.branch(),
+        &env,
+        &config,
+        seller,
+        referrer,
+        cost_resp.price,
+        cost_resp.issuer_fee,
+    )?;
+
+    let fee_msgs = fee_split.messages(deps.storage, &config)?;
+    let hook_msgs = key_balance_hooks(deps.storage, &[(seller, old_amount, old_amount - amount)])?;
+    Ok(event_response(
+        &app,
+        SellKeyEvent {
+            issuer: issuer_addr.clone(),
+            seller: seller.clone(),
+            amount,
+            price: cost_resp.price,
+            issuer_fee: fee_split.issuer_fee,
+            referrer: fee_split.referrer,
+            referral_fee: fee_split.referral_fee,
+            staking_fee: fee_split.staking_fee,
+            supply: old_supply - amount,
+            holding: old_amount - amount,
+        },
+    )
+    .add_messages(fee_msgs)
+    .add_message(BankMsg::Send {
+        to_address: seller.to_string(),
+        amount: coins(cost_resp.price.u128(), &config.fee_denom),
+    })
+    .add_submessages(hook_msgs))
+}
+
+/// Anyone can call, distribute the paid funds pro rata to the current key holders
+fn deposit_rewards(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    assert_market_open(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+    let amount = must_pay(&msg_info, &config.fee_denom)?;
+    let supply = SUPPLY.load(deps.storage)?;
+
+    let reward_per_key = distribute_rewards(deps.storage, amount, supply)?;
+
+    Ok(event_response(
+        &app,
+        DepositRewardsEvent {
+            depositor: msg_info.sender,
+            amount,
+            reward_per_key,
+        },
+    ))
+}
+
+/// Anyone can call, claim the rewards accrued on the keys held by the caller
+fn claim_rewards(deps: DepsMut, msg_info: MessageInfo, app: FriendTechApp) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+
+    let holder = &msg_info.sender;
+    let config = CONFIG.load(deps.storage)?;
+    let rewards = settle_rewards(deps.storage, holder)?;
+    if rewards.pending.is_zero() {
+        return Err(FriendTechAppError::NoRewardsToClaim {});
+    }
+
+    HOLDER_REWARDS.save(
+        deps.storage,
+        holder,
+        &HolderRewards {
+            reward_per_key_paid: rewards.reward_per_key_paid,
+            pending: Uint128::zero(),
+        },
+    )?;
+
+    Ok(event_response(
+        &app,
+        ClaimRewardsEvent {
+            holder: holder.clone(),
+            amount: rewards.pending,
+        },
+    )
+    .add_message(BankMsg::Send {
+        to_address: holder.to_string(),
+        amount: coins(rewards.pending.u128(), &config.fee_denom),
+    }))
+}
+
+/// Anyone can call, stake keys of the caller, staked keys cannot be sold
+fn stake_keys(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    amount: Uint128,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+
+    assert_market_open(deps.storage)?;
+
+    let staker = &msg_info.sender;
+    let unlocked = unlocked_keys(deps.storage, &env.block, staker)?;
+    if amount > unlocked {
+        return Err(FriendTechAppError::InsufficientUnlockedKeys {
+            available: unlocked,
+            required: amount,
+        });
+    }
+
+    let stake = stake(deps.storage, &env.block, staker, amount)?;
+
+    Ok(event_response(
+        &app,
+        StakeEvent {
+            staker: staker.clone(),
+            amount,
+            staked: stake.amount,
+        },
+    ))
+}
+
+/// Anyone can call, unstake keys of the caller, they stay locked for the unbonding period
+fn unstake_keys(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    amount: Uint128,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+
+    assert_market_open(deps.storage)?;
+
+    if amount.is_zero() {
+        return Err(FriendTechAppError::InvalidUnstakeAmount {});
+    }
+
+    let staker = &msg_info.sender;
+    let config = CONFIG.load(deps.storage)?;
+    let staked = query_stake(deps.storage, staker)?.amount;
+    if amount > staked {
+        return Err(FriendTechAppError::CannotUnstakeMoreThanStaked {
+            staked,
+            to_unstake: amount,
+        });
+    }
+
+    let release_at = config.unbonding_period.after(&env.block);
+    let stake = unstake(deps.storage, &env.block, staker, amount, release_at)?;
+
+    Ok(event_response(
+        &app,
+        UnstakeEvent {
+            staker: staker.clone(),
+            amount,
+            staked: stake.amount,
+            release_at,
+        },
+    ))
+}
+
+/// Anyone can call, claim the staking fee earned by the stake of the caller
+fn claim_staking_fee(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+
+    let staker = &msg_info.sender;
+    let config = CONFIG.load(deps.storage)?;
+    let amount = claim_staking_rewards(deps.storage, staker)?;
+    if amount.is_zero() {
+        return Err(FriendTechAppError::NoRewardsToClaim {});
+    }
+
+    Ok(event_response(
+        &app,
+        ClaimStakingRewardsEvent {
+            staker: staker.clone(),
+            amount,
+        },
+    )
+    .add_message(BankMsg::Send {
+        to_address: staker.to_string(),
+        amount: coins(amount.u128(), &config.fee_denom),
+    }))
+}
+
+/// Anyone can call, list keys of the caller at a fixed price, they stay locked until filled or cancelled
+fn list_keys(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    amount: Uint128,
+    price_per_key: Uint128,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    assert_market_open(deps.storage)?;
+    if amount.is_zero() || price_per_key.is_zero() {
+        return Err(FriendTechAppError::InvalidListing {});
+    }
+
+    let seller = &msg_info.sender;
+    let unlocked = unlocked_keys(deps.storage, &env.block, seller)?;
+    if amount > unlocked {
+        return Err(FriendTechAppError::InsufficientUnlockedKeys {
+            available: unlocked,
+            required: amount,
+        });
+    }
+
+    let listing_id = NEXT_LISTING_ID.load(deps.storage)?;
+    NEXT_LISTING_ID.save(deps.storage, &(listing_id + 1))?;
+    listings().save(
+        deps.storage,
+        listing_id,
+        &Listing {
+            seller: seller.clone(),
+            amount,
+            price_per_key,
+        },
+    )?;
+
+    Ok(event_response(
+        &app,
+        ListKeysEvent {
+            listing_id,
+            seller: seller.clone(),
+            amount,
+            price_per_key,
+        },
+    ))
+}
+
+/// Seller only, cancel the listing and unlock its remaining keys
+fn cancel_listing(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    listing_id: u64,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+
+    let listing = listings()
+        .may_load(deps.storage, listing_id)?
+        .ok_or(FriendTechAppError::ListingNotFound { listing_id })?;
+    if listing.seller != msg_info.sender {
+        return Err(FriendTechAppError::NotListingSeller {});
+    }
+    listings().remove(deps.storage, listing_id)?;
+
+    Ok(event_response(
+        &app,
+        CancelListingEvent {
+            listing_id,
+            seller: listing.seller,
+            amount: listing.amount,
+        },
+    ))
+}
+
+/// Anyone can call, lock keys of the caller in escrow for an app installed on this account until the expiration
+fn lock(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    amount: Uint128,
+    until: Expiration,
+    beneficiary_app: String,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    assert_market_open(deps.storage)?;
+    // Keys locked for good could never come back to the holder
+    if amount.is_zero() || until.is_expired(&env.block) || matches!(until, Expiration::Never {}) {
+        return Err(FriendTechAppError::InvalidLock {});
+    }
+    let beneficiary = app
+        .modules(deps.as_ref())
+        .module_address(&beneficiary_app)
+        .map_err(|_| FriendTechAppError::AppNotInstalled {
+            module_id: beneficiary_app.clone(),
+        })?;
+
+    let holder = &msg_info.sender;
+    let unlocked = unlocked_keys(deps.storage, &env.block, holder)?;
+    if amount > unlocked {
+        return Err(FriendTechAppError::InsufficientUnlockedKeys {
+            available: unlocked,
+            required: amount,
+        });
+    }
+
+    let lock_id = NEXT_LOCK_ID.load(deps.storage)?;
+    NEXT_LOCK_ID.save(deps.storage, &(lock_id + 1))?;
+    locks().save(
+        deps.storage,
+        lock_id,
+        &KeyLock {
+            holder: holder.clone(),
+            amount,
+            until,
+            beneficiary_app: beneficiary_app.clone(),
+            beneficiary,
+        },
+    )?;
+
+    Ok(event_response(
+        &app,
+        LockEvent {
+            lock_id,
+            holder: holder.clone(),
+            amount,
+            until,
+            beneficiary_app,
+        },
+    ))
+}
+
+/// Holder only, unlock the keys of an expired lock
+fn unlock(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    lock_id: u64,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+
+    let lock = locks()
+        .may_load(deps.storage, lock_id)?
+        .ok_or(FriendTechAppError::LockNotFound { lock_id })?;
+    if lock.holder != msg_info.sender {
+        return Err(FriendTechAppError::NotLockHolder {});
+    }
+    if !lock.until.is_expired(&env.block) {
+        return Err(FriendTechAppError::LockNotExpired {});
+    }
+    locks().remove(deps.storage, lock_id)?;
+
+    Ok(event_response(
+        &app,
+        UnlockEvent {
+            lock_id,
+            holder: lock.holder,
+            amount: lock.amount,
+        },
+    ))
+}
+
+/// Beneficiary app only, unlock the keys back to the holder
+fn release_lock(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    lock_id: u64,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+
+    let lock = locks()
+        .may_load(deps.storage, lock_id)?
+        .ok_or(FriendTechAppError::LockNotFound { lock_id })?;
+    if lock.beneficiary != msg_info.sender {
+        return Err(FriendTechAppError::NotLockBeneficiary {});
+    }
+    locks().remove(deps.storage, lock_id)?;
+
+    Ok(event_response(
+        &app,
+        ReleaseLockEvent {
+            lock_id,
+            holder: lock.holder,
+            amount: lock.amount,
+        },
+    ))
+}
+
+/// Beneficiary app only, before the lock expires, take amount of the locked keys from the holder
+/// They go to the recipient if given, paying the transfer royalty like a gift, and are burned out of the
+/// supply otherwise
+fn slash_lock(
+    mut deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    lock_id: u64,
+    amount: Uint128,
+    recipient: Option<String>,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    assert_market_open(deps.storage)?;
+
+    let mut lock = locks()
+        .may_load(deps.storage, lock_id)?
+        .ok_or(FriendTechAppError::LockNotFound { lock_id })?;
+    if lock.beneficiary != msg_info.sender {
+        return Err(FriendTechAppError::NotLockBeneficiary {});
+    }
+    if lock.until.is_expired(&env.block) {
+        return Err(FriendTechAppError::LockExpired {});
+    }
+    if amount.is_zero() || amount > lock.amount {
+        return Err(FriendTechAppError::CannotSlashMoreThanLocked {
+            locked: lock.amount,
+            to_slash: amount,
+        });
+    }
+    let recipient = recipient
+        .map(|recipient| deps.api.addr_validate(&recipient))
+        .transpose()?;
+    if recipient.as_ref() == Some(&lock.holder) {
+        return Err(FriendTechAppError::InvalidTransfer {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let royalty = match &recipient {
+        Some(recipient) => gift_royalty(deps.storage, recipient, amount)?,
+        None => Uint128::zero(),
+    };
+    let paid = if royalty.is_zero() {
+        nonpayable(&msg_info)?;
+        Uint128::zero()
+    } else {
+        must_pay(&msg_info, &config.fee_denom)?
+    };
+    if royalty > paid {
+        return Err(FriendTechAppError::InsufficientFunds {
+            required: royalty,
+            paid,
+        });
+    }
+
+    lock.amount -= amount;
+    if lock.amount.is_zero() {
+        locks().remove(deps.storage, lock_id)?;
+    } else {
+        locks().save(deps.storage, lock_id, &lock)?;
+    }
+
+    let holder = &lock.holder;
+    let holder_amount = load_holding(deps.storage, holder)?;
+    save_holding(
+        deps.storage,
+        &env.block,
+        holder,
+        holder_amount - amount,
+        Uint128::zero(),
+    )?;
+    let mut changes = vec![(holder, holder_amount, holder_amount - amount)];
+    match &recipient {
+        Some(recipient) => {
+            assert_account_holder(deps.branch(), &app, &config, recipient)?;
+            let recipient_amount = may_load_holding(deps.storage, recipient)?.unwrap_or_default();
+            save_holding(
+                deps.storage,
+                &env.block,
+                recipient,
+                recipient_amount + amount,
+                Uint128::zero(),
+            )?;
+            changes.push((recipient, recipient_amount, recipient_amount + amount));
+        }
+        // Nobody holds the keys anymore, the reserve paid for them now backs the keys left
+        None => burn_keys(deps.storage, &env.block, amount)?,
+    }
+
+    let mut msgs = pay_fees(deps.storage, royalty, &config)?;
+    if paid > royalty {
+        msgs.push(BankMsg::Send {
+            to_address: msg_info.sender.to_string(),
+            amount: coins((paid - royalty).u128(), &config.fee_denom),
+        });
+    }
+
+    let hook_msgs = key_balance_hooks(deps.storage, &changes)?;
+    Ok(event_response(
+        &app,
+        SlashLockEvent {
+            lock_id,
+            holder: holder.clone(),
+            amount,
+            recipient,
+            royalty,
+        },
+    )
+    .add_messages(msgs)
+    .add_submessages(hook_msgs))
+}
+
+/// Anyone can call, buy amount of the listed keys at the listing price
+/// The fee recipients take a royalty on the price, the buyer pays on top whatever the transfer royalty exceeds it by
+/// Any excess payment is refunded
+fn fill_listing(
+    mut deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    listing_id: u64,
+    amount: Uint128,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    assert_market_open(deps.storage)?;
+    if amount.is_zero() {
+        return Err(FriendTechAppError::InvalidFillAmount {});
+    }
+
+    let buyer = &msg_info.sender;
+    let mut listing = listings()
+        .may_load(deps.storage, listing_id)?
+        .ok_or(FriendTechAppError::ListingNotFound { listing_id })?;
+    if listing.seller == buyer {
+        return Err(FriendTechAppError::CannotFillOwnListing {});
+    }
+    if amount > listing.amount {
+        return Err(FriendTechAppError::CannotFillMoreThanListed {
+            listed: listing.amount,
+            to_fill: amount,
+        });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    assert_account_holder(deps.branch(), &app, &config, buyer)?;
+    let paid = must_pay(&msg_info, &config.fee_denom)?;
+    let price = listing.price_per_key.checked_mul(amount)?;
+    let royalty = multiply_percentage(price, config.listing_royalty_percentage);
+    // A fill pays a single royalty, the greater of the listing and the transfer royalty, so that keys
+    // cannot change hands below the transfer royalty through cheap listings
+    // Fills are sales, the exemptions only cover gifts
+    let transfer_royalty = transfer_royalty(deps.storage, amount)?.saturating_sub(royalty);
+    let required = price.checked_add(transfer_royalty)?;
+    if required > paid {
+        return Err(FriendTechAppError::InsufficientFunds { required, paid });
+    }
+
+    let seller = listing.seller.clone();
+    listing.amount -= amount;
+    if listing.amount.is_zero() {
+        listings().remove(deps.storage, listing_id)?;
     } else {
-        HOLDERS.save(deps.storage, seller, &(old_amount - amount))?;
+        listings().save(deps.storage, listing_id, &listing)?;
     }
 
-    Ok(app
-        .response("sell_key")
-        .add_message(BankMsg::Send {
-            to_address: config.issuer_fee_collector.to_string(),
-            amount: coins(cost_resp.issuer_fee.u128(), &config.fee_denom),
-        })
-        .add_message(BankMsg::Send {
+    record_trade(deps.storage, &env.block, &seller, TradeSide::Sell)?;
+    record_trade(deps.storage, &env.block, buyer, TradeSide::Buy)?;
+    let seller_amount = load_holding(deps.storage, &seller)?;
+    save_holding(
+        deps.storage,
+        &env.block,
+        &seller,
+        seller_amount - amount,
+        Uint128::zero(),
+    )?;
+    let buyer_amount = may_load_holding(deps.storage, buyer)?.unwrap_or_default();
+    save_holding(
+        deps.storage,
+        &env.block,
+        buyer,
+        buyer_amount + amount,
+        required,
+    )?;
+
+    let mut msgs = vec![];
+    if price > royalty {
+        msgs.push(BankMsg::Send {
             to_address: seller.to_string(),
-            amount: coins(cost_resp.price.u128(), &config.fee_denom),
-        })
-        .add_attribute("seller", seller)
-        .add_attribute("amount", amount))
+            amount: coins((price - royalty).u128(), &config.fee_denom),
+        });
+    }
+    msgs.extend(pay_fees(deps.storage, royalty + transfer_royalty, &config)?);
+    if paid > required {
+        msgs.push(BankMsg::Send {
+            to_address: buyer.to_string(),
+            amount: coins((paid - required).u128(), &config.fee_denom),
+        });
+    }
+
+    let hook_msgs = key_balance_hooks(
+        deps.storage,
+        &[
+            (&seller, seller_amount, seller_amount - amount),
+            (buyer, buyer_amount, buyer_amount + amount),
+        ],
+    )?;
+    Ok(event_response(
+        &app,
+        FillListingEvent {
+            listing_id,
+            buyer: buyer.clone(),
+            seller,
+            amount,
+            price,
+            royalty,
+            transfer_royalty,
+            remaining: listing.amount,
+        },
+    )
+    .add_messages(msgs)
+    .add_submessages(hook_msgs))
+}
+
+/// Anyone can call, give unlocked keys of the caller to the recipient
+/// The transfer royalty must be paid unless the recipient is exempt, any excess payment is refunded
+fn transfer_keys(
+    mut deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    assert_market_open(deps.storage)?;
+
+    let sender = &msg_info.sender;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    if amount.is_zero() || recipient == sender {
+        return Err(FriendTechAppError::InvalidTransfer {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    assert_account_holder(deps.branch(), &app, &config, &recipient)?;
+
+    let unlocked = unlocked_keys(deps.storage, &env.block, sender)?;
+    if amount > unlocked {
+        return Err(FriendTechAppError::InsufficientUnlockedKeys {
+            available: unlocked,
+            required: amount,
+        });
+    }
+
+    let royalty = gift_royalty(deps.storage, &recipient, amount)?;
+    let paid = if royalty.is_zero() {
+        nonpayable(&msg_info)?;
+        Uint128::zero()
+    } else {
+        must_pay(&msg_info, &config.fee_denom)?
+    };
+    if royalty > paid {
+        return Err(FriendTechAppError::InsufficientFunds {
+            required: royalty,
+            paid,
+        });
+    }
+
+    // Moving keys to another holder and selling them there is still a round trip
+    record_trade(deps.storage, &env.block, sender, TradeSide::Sell)?;
+    record_trade(deps.storage, &env.block, &recipient, TradeSide::Buy)?;
+    let sender_amount = load_holding(deps.storage, sender)?;
+    save_holding(
+        deps.storage,
+        &env.block,
+        sender,
+        sender_amount - amount,
+        Uint128::zero(),
+    )?;
+    let recipient_amount = may_load_holding(deps.storage, &recipient)?.unwrap_or_default();
+    save_holding(
+        deps.storage,
+        &env.block,
+        &recipient,
+        recipient_amount + amount,
+        Uint128::zero(),
+    )?;
+
+    let mut msgs = pay_fees(deps.storage, royalty, &config)?;
+    if paid > royalty {
+        msgs.push(BankMsg::Send {
+            to_address: sender.to_string(),
+            amount: coins((paid - royalty).u128(), &config.fee_denom),
+        });
+    }
+
+    let hook_msgs = key_balance_hooks(
+        deps.storage,
+        &[
+            (sender, sender_amount, sender_amount - amount),
+            (&recipient, recipient_amount, recipient_amount + amount),
+        ],
+    )?;
+    Ok(event_response(
+        &app,
+        TransferKeysEvent {
+            sender: sender.clone(),
+            recipient,
+            amount,
+            royalty,
+        },
+    )
+    .add_messages(msgs)
+    .add_submessages(hook_msgs))
+}
+
+/// Anyone can call during the opening auction, bid for amount of keys at the current auction price
+/// Bids beyond the keys left are cut down and the excess payment is refunded
+fn bid(
+    mut deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    amount: Uint128,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    let mut auction = AUCTION
+        .may_load(deps.storage)?
+        .ok_or(FriendTechAppError::NoAuction {})?;
+    if auction.clearing_price.is_some() || env.block.time >= auction.end_time {
+        return Err(FriendTechAppError::AuctionEnded {});
+    }
+
+    let bidder = &msg_info.sender;
+    let config = CONFIG.load(deps.storage)?;
+    assert_account_holder(deps.branch(), &app, &config, bidder)?;
+    let paid = must_pay(&msg_info, &config.fee_denom)?;
+    let price = auction_price(&auction, env.block.time);
+    let filled = amount.min(auction.amount - auction.sold);
+    let cost = price * filled;
+    if cost > paid {
+        return Err(FriendTechAppError::InsufficientFunds {
+            required: cost,
+            paid,
+        });
+    }
+
+    BIDS.update(deps.storage, bidder, |bid| -> StdResult<_> {
+        let mut bid = bid.unwrap_or_default();
+        bid.amount += filled;
+        bid.deposit += cost;
+        Ok(bid)
+    })?;
+    auction.sold += filled;
+    if auction.sold == auction.amount {
+        auction.clearing_price = Some(price);
+    }
+    AUCTION.save(deps.storage, &auction)?;
+
+    let mut response = event_response(
+        &app,
+        BidEvent {
+            bidder: bidder.clone(),
+            amount: filled,
+            price,
+            sold: auction.sold,
+        },
+    );
+    if paid > cost {
+        response = response.add_message(BankMsg::Send {
+            to_address: bidder.to_string(),
+            amount: coins((paid - cost).u128(), &config.fee_denom),
+        });
+    }
+    Ok(response)
+}
+
+/// Owner only, before any key is traded, set the airdrop, trading waits until it is funded
+fn set_airdrop(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    params: AirdropParams,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    assert_trading_not_started(deps.storage)?;
+    assert_airdrop(&params, &env.block)?;
+
+    AIRDROP.save(
+        deps.storage,
+        &Airdrop {
+            merkle_root: params.merkle_root.clone(),
+            total_amount: params.total_amount,
+            claimed: Uint128::zero(),
+            reclaimed: Uint128::zero(),
+            expires: params.expires,
+            funded_reward_per_key: None,
+        },
+    )?;
+
+    Ok(event_response(
+        &app,
+        SetAirdropEvent {
+            merkle_root: params.merkle_root,
+            total_amount: params.total_amount,
+            expires: params.expires,
+        },
+    ))
+}
+
+/// Owner only, pay the curve price of the airdropped keys into the reserve, minting them and opening trading
+/// The keys stay with the contract until claimed, like the keys of a settled auction
+fn fund_airdrop(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    let mut airdrop = AIRDROP
+        .may_load(deps.storage)?
+        .ok_or(FriendTechAppError::NoAirdrop {})?;
+    if airdrop.funded_reward_per_key.is_some() {
+        return Err(FriendTechAppError::AirdropFunded {});
+    }
+    assert_trading_not_started(deps.storage)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let paid = must_pay(&msg_info, &config.fee_denom)?;
+    let supply = SUPPLY.load(deps.storage)?;
+    let price = calculate_buy_price(supply, airdrop.total_amount)?;
+    if price > paid {
+        return Err(FriendTechAppError::InsufficientFunds {
+            required: price,
+            paid,
+        });
+    }
+
+    SUPPLY.save(
+        deps.storage,
+        &(supply + airdrop.total_amount),
+        env.block.height,
+    )?;
+    RESERVE.update(deps.storage, |reserve| -> StdResult<_> {
+        Ok(reserve + price)
+    })?;
+    airdrop.funded_reward_per_key = Some(REWARD_PER_KEY.load(deps.storage)?);
+    AIRDROP.save(deps.storage, &airdrop)?;
+
+    let mut response = event_response(
+        &app,
+        FundAirdropEvent {
+            amount: airdrop.total_amount,
+            price,
+            supply: supply + airdrop.total_amount,
+        },
+    );
+    if paid > price {
+        response = response.add_message(BankMsg::Send {
+            to_address: msg_info.sender.to_string(),
+            amount: coins((paid - price).u128(), &config.fee_denom),
+        });
+    }
+    Ok(response)
+}
+
+/// Anyone can call once the airdrop is funded, claim the keys granted to the caller with a merkle proof
+fn claim_airdrop(
+    mut deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    amount: Uint128,
+    proof: Vec<String>,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+
+    let mut airdrop = AIRDROP
+        .may_load(deps.storage)?
+        .ok_or(FriendTechAppError::NoAirdrop {})?;
+    let Some(funded_reward_per_key) = airdrop.funded_reward_per_key else {
+        return Err(FriendTechAppError::AirdropNotFunded {});
+    };
+    if airdrop.expires.is_expired(&env.block) {
+        return Err(FriendTechAppError::AirdropExpired {});
+    }
+    let claimer = &msg_info.sender;
+    if AIRDROP_CLAIMS.has(deps.storage, claimer) {
+        return Err(FriendTechAppError::AirdropAlreadyClaimed {});
+    }
+    verify_claim(&airdrop.merkle_root, claimer, amount, &proof)?;
+    let left = airdrop.total_amount - airdrop.claimed;
+    if amount > left {
+        return Err(FriendTechAppError::AirdropExhausted { left });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    assert_account_holder(deps.branch(), &app, &config, claimer)?;
+
+    airdrop.claimed += amount;
+    AIRDROP.save(deps.storage, &airdrop)?;
+    AIRDROP_CLAIMS.save(deps.storage, claimer, &amount)?;
+
+    let old_amount = may_load_holding(deps.storage, claimer)?.unwrap_or_default();
+    save_holding(
+        deps.storage,
+        &env.block,
+        claimer,
+        old_amount + amount,
+        Uint128::zero(),
+    )?;
+    // The keys were minted at funding, so they earn the rewards deposited since
+    let accrued = accrued_rewards(deps.storage, amount, funded_reward_per_key)?;
+    if !accrued.is_zero() {
+        HOLDER_REWARDS.update(deps.storage, claimer, |rewards| -> StdResult<_> {
+            let mut rewards = rewards.unwrap_or_default();
+            rewards.pending += accrued;
+            Ok(rewards)
+        })?;
+    }
+
+    Ok(event_response(
+        &app,
+        ClaimAirdropEvent {
+            claimer: claimer.clone(),
+            amount,
+            holding: old_amount + amount,
+        },
+    )
+    .add_submessages(key_balance_hooks(
+        deps.storage,
+        &[(claimer, old_amount, old_amount + amount)],
+    )?))
+}
+
+/// Owner only, once the claims expired, burn the unclaimed keys and pay back their curve price
+/// The keys are the last ones on the curve, so the reserve keeps backing the keys left
+/// Once the market is closed they are paid their pro rata share of the reserve, like a redemption
+fn reclaim_airdrop(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    let mut airdrop = AIRDROP
+        .may_load(deps.storage)?
+        .ok_or(FriendTechAppError::NoAirdrop {})?;
+    let Some(funded_reward_per_key) = airdrop.funded_reward_per_key else {
+        return Err(FriendTechAppError::AirdropNotFunded {});
+    };
+    if !airdrop.expires.is_expired(&env.block) {
+        return Err(FriendTechAppError::AirdropNotExpired {
+            expires: airdrop.expires,
+        });
+    }
+    let amount = airdrop.total_amount - airdrop.claimed - airdrop.reclaimed;
+    if amount.is_zero() {
+        return Err(FriendTechAppError::NothingToReclaim {});
+    }
+
+    let supply = SUPPLY.load(deps.storage)?;
+    let reserve = RESERVE.load(deps.storage)?;
+    let price = if MARKET_CLOSED.load(deps.storage)? {
+        reserve.multiply_ratio(amount, supply)
+    } else {
+        calculate_buy_price(supply.checked_sub(amount)?, amount)?
+    };
+    burn_keys(deps.storage, &env.block, amount)?;
+    RESERVE.save(deps.storage, &reserve.checked_sub(price)?)?;
+    // Rewards were deposited for the unclaimed keys too, nobody else can claim them
+    let rewards = accrued_rewards(deps.storage, amount, funded_reward_per_key)?;
+    airdrop.reclaimed += amount;
+    AIRDROP.save(deps.storage, &airdrop)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    Ok(event_response(
+        &app,
+        ReclaimAirdropEvent {
+            amount,
+            price,
+            rewards,
+            supply: supply - amount,
+        },
+    )
+    .add_message(BankMsg::Send {
+        to_address: msg_info.sender.to_string(),
+        amount: coins((price + rewards).u128(), &config.fee_denom),
+    }))
+}
+
+/// Anyone can call once the auction sold out or ended, open trading on the curve
+/// The reserve gets the curve price of the keys sold, the rest of the proceeds goes to the issuer fee collector
+fn settle_auction(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+
+    let mut auction = AUCTION
+        .may_load(deps.storage)?
+        .ok_or(FriendTechAppError::NoAuction {})?;
+    if auction.settled_reward_per_key.is_some() {
+        return Err(FriendTechAppError::AuctionSettled {});
+    }
+    let clearing_price = match auction.clearing_price {
+        Some(clearing_price) => clearing_price,
+        None if env.block.time >= auction.end_time => auction.end_price,
+        None => return Err(FriendTechAppError::AuctionInProgress {}),
+    };
+
+    let config = CONFIG.load(deps.storage)?;
+    let supply = SUPPLY.load(deps.storage)?;
+    let curve_price = calculate_buy_price(supply, auction.sold)?;
+    let proceeds = clearing_price * auction.sold;
+    SUPPLY.save(deps.storage, &(supply + auction.sold), env.block.height)?;
+    RESERVE.update(deps.storage, |reserve| -> StdResult<_> {
+        Ok(reserve + curve_price)
+    })?;
+
+    auction.clearing_price = Some(clearing_price);
+    auction.settled_reward_per_key = Some(REWARD_PER_KEY.load(deps.storage)?);
+    AUCTION.save(deps.storage, &auction)?;
+
+    let surplus = proceeds - curve_price;
+    let fee_msgs = pay_fees(deps.storage, surplus, &config)?;
+    Ok(event_response(
+        &app,
+        SettleAuctionEvent {
+            sold: auction.sold,
+            clearing_price,
+            curve_price,
+            surplus,
+            supply: supply + auction.sold,
+        },
+    )
+    .add_messages(fee_msgs))
+}
+
+/// Bidder only, once the auction is settled, receive the won keys and the refund above the clearing price
+fn claim_auction(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+
+    let auction = AUCTION
+        .may_load(deps.storage)?
+        .ok_or(FriendTechAppError::NoAuction {})?;
+    let (Some(clearing_price), Some(settled_reward_per_key)) =
+        (auction.clearing_price, auction.settled_reward_per_key)
+    else {
+        return Err(FriendTechAppError::AuctionInProgress {});
+    };
+
+    let bidder = &msg_info.sender;
+    let bid = BIDS
+        .may_load(deps.storage, bidder)?
+        .ok_or(FriendTechAppError::NoBidToClaim {})?;
+    BIDS.remove(deps.storage, bidder);
+
+    let old_amount = may_load_holding(deps.storage, bidder)?.unwrap_or_default();
+    save_holding(
+        deps.storage,
+        &env.block,
+        bidder,
+        old_amount + bid.amount,
+        clearing_price * bid.amount,
+    )?;
+    // The keys were issued at settlement, so they earn the rewards deposited since
+    let accrued = accrued_rewards(deps.storage, bid.amount, settled_reward_per_key)?;
+    if !accrued.is_zero() {
+        HOLDER_REWARDS.update(deps.storage, bidder, |rewards| -> StdResult<_> {
+            let mut rewards = rewards.unwrap_or_default();
+            rewards.pending += accrued;
+            Ok(rewards)
+        })?;
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let refund = bid.deposit - clearing_price * bid.amount;
+    let mut response = event_response(
+        &app,
+        ClaimAuctionEvent {
+            bidder: bidder.clone(),
+            amount: bid.amount,
+            refund,
+            holding: old_amount + bid.amount,
+        },
+    );
+    if !refund.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: bidder.to_string(),
+            amount: coins(refund.u128(), &config.fee_denom),
+        });
+    }
+    Ok(response.add_submessages(key_balance_hooks(
+        deps.storage,
+        &[(bidder, old_amount, old_amount + bid.amount)],
+    )?))
+}
+
+/// Owner only, pay out the fees accrued in the contract
+fn claim_fees(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    to: Option<String>,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let amount = ACCRUED_FEES.load(deps.storage)?;
+    if amount.is_zero() {
+        return Err(FriendTechAppError::NoFeesToClaim {});
+    }
+    ACCRUED_FEES.save(deps.storage, &Uint128::zero())?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let to = to.map(|to| deps.api.addr_validate(&to)).transpose()?;
+    let msgs = match &to {
+        Some(to) => vec![BankMsg::Send {
+            to_address: to.to_string(),
+            amount: coins(amount.u128(), &config.fee_denom),
+        }],
+        None => fee_messages(amount, &config),
+    };
+
+    Ok(event_response(&app, ClaimFeesEvent { amount, to }).add_messages(msgs))
+}
+
+/// Owner only, spend up to budget of the accrued fees buying keys on the curve and burn them
+/// The keys never reach the supply, the price paid for them is distributed to every holder as rewards
+fn buyback_and_burn(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    budget: Uint128,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    assert_market_open(deps.storage)?;
+
+    let accrued = ACCRUED_FEES.load(deps.storage)?;
+    if budget > accrued {
+        return Err(FriendTechAppError::InsufficientAccruedFees { accrued, budget });
+    }
+    // The issuer would pay its own fee, so the buyback pays the price alone
+    let supply = SUPPLY.load(deps.storage)?;
+    let (amount, cost) = keys_for_budget(supply, budget, 0);
+    if amount.is_zero() {
+        return Err(FriendTechAppError::BudgetTooLow { budget });
+    }
+
+    // Keys minted and burned at once leave the supply and the curve untouched,
+    // the price they would have added to the reserve goes to the holders instead
+    ACCRUED_FEES.save(deps.storage, &(accrued - cost.price))?;
+    distribute_rewards(deps.storage, cost.price, supply)?;
+    let burned = BURNED.update(deps.storage, |burned| -> StdResult<_> {
+        Ok(burned + amount)
+    })?;
+
+    Ok(event_response(
+        &app,
+        BuybackAndBurnEvent {
+            amount,
+            price: cost.price,
+            supply,
+            burned,
+        },
+    ))
+}
+
+/// Owner only, notify the contract of every key balance change
+fn register_hook(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    contract: String,
+    on_failure: HookFailure,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let contract = deps.api.addr_validate(&contract)?;
+    if HOOKS.has(deps.storage, &contract) {
+        return Err(FriendTechAppError::HookAlreadyRegistered {
+            contract: contract.into_string(),
+        });
+    }
+    let hook_count = HOOKS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .count();
+    if hook_count >= MAX_HOOKS as usize {
+        return Err(FriendTechAppError::TooManyHooks { max: MAX_HOOKS });
+    }
+    HOOKS.save(deps.storage, &contract, &on_failure)?;
+
+    Ok(event_response(
+        &app,
+        RegisterHookEvent {
+            contract,
+            on_failure,
+        },
+    ))
+}
+
+/// Owner only, stop notifying the contract
+fn unregister_hook(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    contract: String,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let contract = deps.api.addr_validate(&contract)?;
+    if !HOOKS.has(deps.storage, &contract) {
+        return Err(FriendTechAppError::HookNotRegistered {
+            contract: contract.into_string(),
+        });
+    }
+    HOOKS.remove(deps.storage, &contract);
+
+    Ok(event_response(&app, UnregisterHookEvent { contract }))
+}
+
+/// Anyone can call, move the issuer's key and the fee share of the previous account owner to the current one
+fn sync_owner(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    let owner = &get_account_owner_addr(deps.as_ref(), &app)?;
+    let previous_owner = &ISSUER.load(deps.storage)?;
+    // The account proxy holds the issuer's key in accounts only mode, whoever owns the account
+    if config.accounts_only || previous_owner == owner {
+        return Err(FriendTechAppError::OwnerAlreadySynced {});
+    }
+
+    // The issuer's key is locked, so the previous owner still holds it
+    let amount = Uint128::one();
+    let previous_amount = load_holding(deps.storage, previous_owner)?;
+    let old_amount = may_load_holding(deps.storage, owner)?.unwrap_or_default();
+    save_holding(
+        deps.storage,
+        &env.block,
+        previous_owner,
+        previous_amount - amount,
+        Uint128::zero(),
+    )?;
+    save_holding(
+        deps.storage,
+        &env.block,
+        owner,
+        old_amount + amount,
+        Uint128::zero(),
+    )?;
+    ISSUER.save(deps.storage, owner)?;
+
+    for fee_recipient in config.fee_recipients.iter_mut() {
+        if fee_recipient.address == previous_owner {
+            fee_recipient.address = owner.clone();
+        }
+    }
+    CONFIG.save(deps.storage, &config)?;
+
+    let hook_msgs = key_balance_hooks(
+        deps.storage,
+        &[
+            (previous_owner, previous_amount, previous_amount - amount),
+            (owner, old_amount, old_amount + amount),
+        ],
+    )?;
+    Ok(event_response(
+        &app,
+        SyncOwnerEvent {
+            previous_owner: previous_owner.clone(),
+            owner: owner.clone(),
+            amount,
+        },
+    )
+    .add_submessages(hook_msgs))
+}
+
+/// Owner only, halt trading for good, holders can then redeem their keys
+fn close_market(deps: DepsMut, msg_info: MessageInfo, app: FriendTechApp) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    assert_market_open(deps.storage)?;
+
+    MARKET_CLOSED.save(deps.storage, &true)?;
+
+    Ok(event_response(
+        &app,
+        CloseMarketEvent {
+            supply: SUPPLY.load(deps.storage)?,
+            reserve: RESERVE.load(deps.storage)?,
+        },
+    ))
+}
+
+/// Owner only, replace the recipients sharing the issuer fees
+fn update_fee_recipients(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    fee_recipients: Vec<UncheckedFeeRecipient>,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    config.fee_recipients = validate_fee_recipients(deps.api, fee_recipients)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(event_response(
+        &app,
+        UpdateFeeRecipientsEvent {
+            fee_recipients: config.fee_recipients,
+        },
+    ))
+}
+
+/// Owner only, replace the dynamic fee model or go back to the flat issuer fee
+fn update_dynamic_fee(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    dynamic_fee: Option<DynamicFeeConfig>,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    match &dynamic_fee {
+        Some(dynamic_fee) => {
+            assert_dynamic_fee(dynamic_fee)?;
+            DYNAMIC_FEE.save(deps.storage, dynamic_fee)?;
+        }
+        None => DYNAMIC_FEE.remove(deps.storage),
+    }
+
+    Ok(event_response(&app, UpdateDynamicFeeEvent { dynamic_fee }))
+}
+
+/// Owner only, replace the rate limits or lift them, the trade windows already open keep counting
+fn update_rate_limits(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    rate_limits: Option<RateLimits>,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    match &rate_limits {
+        Some(rate_limits) => {
+            assert_rate_limits(rate_limits)?;
+            RATE_LIMITS.save(deps.storage, rate_limits)?;
+        }
+        None => RATE_LIMITS.remove(deps.storage),
+    }
+
+    Ok(event_response(&app, UpdateRateLimitsEvent { rate_limits }))
+}
+
+/// Owner only, replace the transfer royalty or remove it
+fn update_transfer_royalty(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    royalty: Option<TransferRoyalty>,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    match &royalty {
+        Some(royalty) => {
+            assert_transfer_royalty(royalty)?;
+            TRANSFER_ROYALTY.save(deps.storage, royalty)?;
+        }
+        None => TRANSFER_ROYALTY.remove(deps.storage),
+    }
+
+    Ok(event_response(&app, UpdateTransferRoyaltyEvent { royalty }))
+}
+
+/// Owner only, add and remove recipients of gifts exempt from the transfer royalty
+fn update_royalty_exemptions(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let added = add
+        .iter()
+        .map(|address| deps.api.addr_validate(address))
+        .collect::<StdResult<Vec<_>>>()?;
+    let removed = remove
+        .iter()
+        .map(|address| deps.api.addr_validate(address))
+        .collect::<StdResult<Vec<_>>>()?;
+    for address in &added {
+        ROYALTY_EXEMPTIONS.save(deps.storage, address, &Empty {})?;
+    }
+    for address in &removed {
+        ROYALTY_EXEMPTIONS.remove(deps.storage, address);
+    }
+
+    Ok(event_response(
+        &app,
+        UpdateRoyaltyExemptionsEvent { added, removed },
+    ))
+}
+
+/// Owner only, replace the governance parameters or disable new proposals
+fn update_governance(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    governance: Option<GovernanceConfig>,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    match &governance {
+        Some(governance) => {
+            assert_governance(governance)?;
+            GOVERNANCE.save(deps.storage, governance)?;
+        }
+        None => GOVERNANCE.remove(deps.storage),
+    }
+
+    Ok(event_response(&app, UpdateGovernanceEvent { governance }))
+}
+
+/// Holder only, propose messages for the account to run
+fn propose(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    title: String,
+    description: String,
+    msgs: Vec<CosmosMsg>,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    let governance = GOVERNANCE
+        .may_load(deps.storage)?
+        .ok_or(FriendTechAppError::GovernanceDisabled {})?;
+
+    let proposer = &msg_info.sender;
+    if may_load_holding(deps.storage, proposer)?.is_none() {
+        return Err(FriendTechAppError::NoVotingPower {});
+    }
+
+    // Changes made earlier in this block are left out of the snapshot, so the supply and the
+    // holdings are read at the start of the block alike
+    let start_height = env.block.height;
+    let total_power = SUPPLY
+        .may_load_at_height(deps.storage, start_height)?
+        .unwrap_or_default();
+    let expires = governance.voting_period.after(&env.block);
+    let proposal_id = NEXT_PROPOSAL_ID.load(deps.storage)?;
+    NEXT_PROPOSAL_ID.save(deps.storage, &(proposal_id + 1))?;
+    PROPOSALS.save(
+        deps.storage,
+        proposal_id,
+        &Proposal {
+            proposer: proposer.clone(),
+            title,
+            description,
+            msgs,
+            start_height,
+            expires,
+            total_power,
+            quorum: governance.quorum,
+            threshold: governance.threshold,
+            yes: Uint128::zero(),
+            no: Uint128::zero(),
+            abstain: Uint128::zero(),
+            executed: false,
+        },
+    )?;
+
+    Ok(event_response(
+        &app,
+        ProposeEvent {
+            proposal_id,
+            proposer: proposer.clone(),
+            start_height,
+            expires,
+        },
+    ))
+}
+
+/// Holder at the start of the proposal only, vote with the keys held then
+fn vote_on_proposal(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    proposal_id: u64,
+    vote: VoteOption,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    let mut proposal = PROPOSALS
+        .may_load(deps.storage, proposal_id)?
+        .ok_or(FriendTechAppError::ProposalNotFound { proposal_id })?;
+    if proposal_status(&proposal, &env.block) != ProposalStatus::Open {
+        return Err(FriendTechAppError::VotingClosed { proposal_id });
+    }
+
+    let voter = &msg_info.sender;
+    if BALLOTS.has(deps.storage, (proposal_id, voter)) {
+        return Err(FriendTechAppError::AlreadyVoted { proposal_id });
+    }
+    let weight =
+        may_load_holding_at_height(deps.storage, voter, proposal.start_height)?.unwrap_or_default();
+    if weight.is_zero() {
+        return Err(FriendTechAppError::NoVotingPower {});
+    }
+
+    match vote {
+        VoteOption::Yes => proposal.yes += weight,
+        VoteOption::No => proposal.no += weight,
+        VoteOption::Abstain => proposal.abstain += weight,
+    }
+    PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+    BALLOTS.save(deps.storage, (proposal_id, voter), &Ballot { vote, weight })?;
+
+    Ok(event_response(
+        &app,
+        VoteEvent {
+            proposal_id,
+            voter: voter.clone(),
+            vote,
+            weight,
+        },
+    ))
+}
+
+/// Anyone can call once the proposal passed, run its messages through the account
+fn execute_proposal(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    proposal_id: u64,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    let mut proposal = PROPOSALS
+        .may_load(deps.storage, proposal_id)?
+        .ok_or(FriendTechAppError::ProposalNotFound { proposal_id })?;
+    if proposal_status(&proposal, &env.block) != ProposalStatus::Passed {
+        return Err(FriendTechAppError::ProposalNotPassed { proposal_id });
+    }
+    let account_contracts = account_contracts(deps.as_ref(), &app)?;
+    if needs_owner_approval(deps.storage, &proposal, &account_contracts)?
+        && !APPROVED_PROPOSALS.has(deps.storage, proposal_id)
+    {
+        return Err(FriendTechAppError::ProposalNotApproved { proposal_id });
+    }
+
+    proposal.executed = true;
+    PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+
+    let executor_msg = app
+        .executor(deps.as_ref())
+        .execute(proposal.msgs.into_iter().map(AccountAction::from).collect())?;
+    Ok(event_response(&app, ExecuteProposalEvent { proposal_id }).add_message(executor_msg))
+}
+
+/// Owner only, let the proposal run messages outside the approval exemptions
+fn approve_proposal(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    proposal_id: u64,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    if !PROPOSALS.has(deps.storage, proposal_id) {
+        return Err(FriendTechAppError::ProposalNotFound { proposal_id });
+    }
+
+    APPROVED_PROPOSALS.save(deps.storage, proposal_id, &Empty {})?;
+
+    Ok(event_response(&app, ApproveProposalEvent { proposal_id }))
+}
+
+/// Owner only, add and remove contracts the proposals may call without the owner's approval
+fn update_approval_exemptions(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let added = add
+        .iter()
+        .map(|address| deps.api.addr_validate(address))
+        .collect::<StdResult<Vec<_>>>()?;
+    let removed = remove
+        .iter()
+        .map(|address| deps.api.addr_validate(address))
+        .collect::<StdResult<Vec<_>>>()?;
+    for address in &added {
+        APPROVAL_EXEMPTIONS.save(deps.storage, address, &Empty {})?;
+    }
+    for address in &removed {
+        APPROVAL_EXEMPTIONS.remove(deps.storage, address);
+    }
+
+    Ok(event_response(
+        &app,
+        UpdateApprovalExemptionsEvent { added, removed },
+    ))
+}
+
+/// Anyone can call once the market is closed, redeem every key of the caller for a pro rata share of the reserve
+fn redeem(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    app: FriendTechApp,
+) -> FriendTechAppResult {
+    nonpayable(&msg_info)?;
+    if !MARKET_CLOSED.load(deps.storage)? {
+        return Err(FriendTechAppError::MarketNotClosed {});
+    }
+
+    let holder = &msg_info.sender;
+    let amount = may_load_holding(deps.storage, holder)?.unwrap_or_default();
+    if amount.is_zero() {
+        return Err(FriendTechAppError::NoKeysToRedeem {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let supply = SUPPLY.load(deps.storage)?;
+    let reserve = RESERVE.load(deps.storage)?;
+    // Rounding down leaves the dust to the last holder, who redeems the whole reserve
+    let payout = reserve.multiply_ratio(amount, supply);
+
+    SUPPLY.save(deps.storage, &(supply - amount), env.block.height)?;
+    RESERVE.save(deps.storage, &(reserve - payout))?;
+    release_stake(deps.storage, holder)?;
+    remove_seller_listings(deps.storage, holder)?;
+    remove_holder_locks(deps.storage, holder)?;
+    save_holding(
+        deps.storage,
+        &env.block,
+        holder,
+        Uint128::zero(),
+        Uint128::zero(),
+    )?;
+
+    let mut response = event_response(
+        &app,
+        RedeemEvent {
+            holder: holder.clone(),
+            amount,
+            payout,
+            supply: supply - amount,
+        },
+    );
+    if !payout.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: holder.to_string(),
+            amount: coins(payout.u128(), &config.fee_denom),
+        });
+    }
+    Ok(response.add_submessages(key_balance_hooks(
+        deps.storage,
+        &[(holder, amount, Uint128::zero())],
+    )?))
 }
@@ -1,36 +1,152 @@
 use crate::{
+    airdrop::assert_airdrop,
+    auction::min_end_price,
     contract::{FriendTechApp, FriendTechAppResult},
+    dynamic_fee::assert_dynamic_fee,
+    events::{event_response, InstantiateEvent},
+    governance::assert_governance,
     msg::FriendTechAppInstantiateMsg,
-    state::{Config, CONFIG, HOLDERS, SUPPLY},
-    utils::get_account_owner_addr,
+    rate_limit::assert_rate_limits,
+    royalty::assert_transfer_royalty,
+    state::{
+        Airdrop, Auction, Config, StakingState, ACCOUNT_IDS, ACCRUED_FEES, AIRDROP, AUCTION,
+        BURNED, CONFIG, CURRENT_STATE_VERSION, DYNAMIC_FEE, GOVERNANCE, HOLDER_COUNT, ISSUER,
+        MARKET_CLOSED, NEXT_LISTING_ID, NEXT_LOCK_ID, NEXT_PROPOSAL_ID, RATE_LIMITS, RESERVE,
+        REWARD_PER_KEY, STAKING, STATE_VERSION, SUPPLY, TRANSFER_ROYALTY,
+    },
+    utils::{
+        assert_fee_shares, assert_percentage, get_account_owner_addr, get_issuer_holder_addr,
+        validate_fee_recipients, write_holding,
+    },
+    FriendTechAppError,
 };
 
-use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, Uint128};
+use abstract_app::sdk::features::AccountIdentification;
+use cosmwasm_std::{Decimal256, DepsMut, Env, MessageInfo, Uint128};
 
 pub fn instantiate_handler(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     _info: MessageInfo,
     app: FriendTechApp,
     msg: FriendTechAppInstantiateMsg,
 ) -> FriendTechAppResult {
     let account_owner_addr = &get_account_owner_addr(deps.as_ref(), &app)?;
 
-    let issuer_fee_collector = deps.api.addr_validate(&msg.issuer_fee_collector)?;
+    let fee_recipients = validate_fee_recipients(deps.api, msg.fee_recipients)?;
+
+    assert_fee_shares(msg.referral_fee_percentage, msg.staking_fee_percentage)?;
+    assert_percentage(msg.listing_royalty_percentage)?;
 
     let config: Config = Config {
         username: msg.username.clone(),
         fee_denom: msg.fee_denom.clone(),
-        issuer_fee_collector,
+        fee_recipients,
+        referral_fee_percentage: msg.referral_fee_percentage,
+        staking_fee_percentage: msg.staking_fee_percentage,
+        unbonding_period: msg.unbonding_period,
+        listing_royalty_percentage: msg.listing_royalty_percentage,
+        accrue_fees: msg.accrue_fees,
+        accounts_only: msg.accounts_only,
     };
+    let issuer_holder_addr =
+        &get_issuer_holder_addr(deps.as_ref(), &app, &config, account_owner_addr)?;
+    if config.accounts_only {
+        let account_id = app.account_id(deps.as_ref())?;
+        ACCOUNT_IDS.save(deps.storage, issuer_holder_addr, &account_id)?;
+    }
+    STATE_VERSION.save(deps.storage, &CURRENT_STATE_VERSION)?;
     CONFIG.save(deps.storage, &config)?;
-    SUPPLY.save(deps.storage, &Uint128::one())?;
-    HOLDERS.save(deps.storage, account_owner_addr, &Uint128::one())?;
+    SUPPLY.save(deps.storage, &Uint128::one(), env.block.height)?;
+    write_holding(deps.storage, &env.block, issuer_holder_addr, Uint128::one())?;
+    ISSUER.save(deps.storage, issuer_holder_addr)?;
+    HOLDER_COUNT.save(deps.storage, &1)?;
+    MARKET_CLOSED.save(deps.storage, &false)?;
+    NEXT_LISTING_ID.save(deps.storage, &0)?;
+
+    if let Some(params) = &msg.opening_auction {
+        if params.amount.is_zero() || params.duration == 0 || params.start_price < params.end_price
+        {
+            return Err(FriendTechAppError::InvalidAuction {});
+        }
+        let min_end_price = min_end_price(params.amount)?;
+        if params.end_price < min_end_price {
+            return Err(FriendTechAppError::AuctionEndPriceTooLow { min_end_price });
+        }
+        AUCTION.save(
+            deps.storage,
+            &Auction {
+                amount: params.amount,
+                start_price: params.start_price,
+                end_price: params.end_price,
+                start_time: env.block.time,
+                end_time: env.block.time.plus_seconds(params.duration),
+                sold: Uint128::zero(),
+                clearing_price: None,
+                settled_reward_per_key: None,
+            },
+        )?;
+    }
+    if let Some(params) = &msg.airdrop {
+        if msg.opening_auction.is_some() {
+            return Err(FriendTechAppError::AirdropWithAuction {});
+        }
+        assert_airdrop(params, &env.block)?;
+        AIRDROP.save(
+            deps.storage,
+            &Airdrop {
+                merkle_root: params.merkle_root.clone(),
+                total_amount: params.total_amount,
+                claimed: Uint128::zero(),
+                reclaimed: Uint128::zero(),
+                expires: params.expires,
+                funded_reward_per_key: None,
+            },
+        )?;
+    }
+    if let Some(rate_limits) = &msg.rate_limits {
+        assert_rate_limits(rate_limits)?;
+        RATE_LIMITS.save(deps.storage, rate_limits)?;
+    }
+    if let Some(dynamic_fee) = &msg.dynamic_fee {
+        assert_dynamic_fee(dynamic_fee)?;
+        DYNAMIC_FEE.save(deps.storage, dynamic_fee)?;
+    }
+    if let Some(governance) = &msg.governance {
+        assert_governance(governance)?;
+        GOVERNANCE.save(deps.storage, governance)?;
+    }
+    NEXT_PROPOSAL_ID.save(deps.storage, &0)?;
+    NEXT_LOCK_ID.save(deps.storage, &0)?;
+    if let Some(royalty) = &msg.transfer_royalty {
+        assert_transfer_royalty(royalty)?;
+        TRANSFER_ROYALTY.save(deps.storage, royalty)?;
+    }
+    // The issuer's key is free so the reserve starts empty
+    RESERVE.save(deps.storage, &Uint128::zero())?;
+    ACCRUED_FEES.save(deps.storage, &Uint128::zero())?;
+    BURNED.save(deps.storage, &Uint128::zero())?;
+    REWARD_PER_KEY.save(deps.storage, &Decimal256::zero())?;
+    STAKING.save(deps.storage, &StakingState::default())?;
 
-    Ok(Response::new()
-        .add_attribute("action", "instantiate")
-        .add_attribute("account_owner", account_owner_addr)
-        .add_attribute("username", msg.username)
-        .add_attribute("fee_denom", msg.fee_denom)
-        .add_attribute("issuer_fee_collector", msg.issuer_fee_collector))
+    Ok(event_response(
+        &app,
+        InstantiateEvent {
+            issuer: account_owner_addr.clone(),
+            username: config.username,
+            fee_denom: config.fee_denom,
+            fee_recipients: config.fee_recipients,
+            referral_fee_percentage: config.referral_fee_percentage,
+            staking_fee_percentage: config.staking_fee_percentage,
+            unbonding_period: config.unbonding_period,
+            listing_royalty_percentage: config.listing_royalty_percentage,
+            accrue_fees: config.accrue_fees,
+            accounts_only: config.accounts_only,
+            governance: msg.governance,
+            dynamic_fee: msg.dynamic_fee,
+            transfer_royalty: msg.transfer_royalty,
+            airdrop: msg.airdrop,
+            rate_limits: msg.rate_limits,
+        },
+    ))
 }
@@ -1,18 +1,456 @@
 use crate::{
     contract::{FriendTechApp, FriendTechAppResult},
+    events::{event_response, MigrateEvent},
     msg::FriendTechAppMigrateMsg,
+    state::{
+        Airdrop, FeeRecipient, StakingState, ACCOUNT_HOLDERS, ACCRUED_FEES, AIRDROP, BURNED,
+        CONFIG, CURRENT_STATE_VERSION, HOLDERS, HOLDER_COUNT, ISSUER, MARKET_CLOSED,
+        NEXT_LISTING_ID, NEXT_LOCK_ID, NEXT_PROPOSAL_ID, RESERVE, REWARD_PER_KEY, STAKING,
+        STATE_VERSION,
+    },
+    utils::{assert_fee_shares, assert_percentage, get_account_owner_addr},
+    FriendTechAppError,
 };
 
-use abstract_app::traits::AbstractResponse;
-use cosmwasm_std::{DepsMut, Env};
+use abstract_app::sdk::features::AccountIdentification;
+use cosmwasm_std::{Addr, Decimal256, DepsMut, Env, Order, StdResult, Uint128};
+use cw_storage_plus::Item;
+use cw_utils::{Duration, Expiration};
+
+/// Storage layout of installs made before versioning (0.0.1)
+const UNVERSIONED_STATE_VERSION: u32 = 1;
+
+/// Upgrades the state from the layout of version `n + 1` to `n + 2`, where `n` is its index
+type MigrationStep =
+    fn(DepsMut, &Env, &FriendTechApp, &FriendTechAppMigrateMsg) -> FriendTechAppResult<()>;
+
+const MIGRATION_STEPS: [MigrationStep;
+    (CURRENT_STATE_VERSION - UNVERSIONED_STATE_VERSION) as usize] = [
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+    migrate_v3_to_v4,
+    migrate_v4_to_v5,
+    migrate_v5_to_v6,
+    migrate_v6_to_v7,
+    migrate_v7_to_v8,
+    migrate_v8_to_v9,
+    migrate_v9_to_v10,
+    migrate_v10_to_v11,
+    migrate_v11_to_v12,
+];
 
 /// Handle the app migrate msg
 /// The top-level Abstract app does version checking and dispatches to this handler
 pub fn migrate_handler(
-    _deps: DepsMut,
-    _env: Env,
+    mut deps: DepsMut,
+    env: Env,
     app: FriendTechApp,
-    _msg: FriendTechAppMigrateMsg,
+    msg: FriendTechAppMigrateMsg,
 ) -> FriendTechAppResult {
-    Ok(app.response("migrate"))
+    let from_version = STATE_VERSION
+        .may_load(deps.storage)?
+        .unwrap_or(UNVERSIONED_STATE_VERSION);
+    if from_version > CURRENT_STATE_VERSION {
+        return Err(FriendTechAppError::UnknownStateVersion {
+            from: from_version,
+            latest: CURRENT_STATE_VERSION,
+        });
+    }
+
+    let pending_steps = (from_version - UNVERSIONED_STATE_VERSION) as usize;
+    for step in &MIGRATION_STEPS[pending_steps..] {
+        step(deps.branch(), &env, &app, &msg)?;
+    }
+    STATE_VERSION.save(deps.storage, &CURRENT_STATE_VERSION)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    if let Some(referral_fee_percentage) = msg.referral_fee_percentage {
+        config.referral_fee_percentage = referral_fee_percentage;
+    }
+    if let Some(staking_fee_percentage) = msg.staking_fee_percentage {
+        config.staking_fee_percentage = staking_fee_percentage;
+    }
+    if let Some(unbonding_period) = msg.unbonding_period {
+        config.unbonding_period = unbonding_period;
+    }
+    if let Some(listing_royalty_percentage) = msg.listing_royalty_percentage {
+        config.listing_royalty_percentage = listing_royalty_percentage;
+    }
+    if let Some(accrue_fees) = msg.accrue_fees {
+        config.accrue_fees = accrue_fees;
+    }
+    assert_fee_shares(
+        config.referral_fee_percentage,
+        config.staking_fee_percentage,
+    )?;
+    assert_percentage(config.listing_royalty_percentage)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(event_response(
+        &app,
+        MigrateEvent {
+            from_state_version: from_version,
+            to_state_version: CURRENT_STATE_VERSION,
+            referral_fee_percentage: config.referral_fee_percentage,
+            staking_fee_percentage: config.staking_fee_percentage,
+            unbonding_period: config.unbonding_period,
+            listing_royalty_percentage: config.listing_royalty_percentage,
+            accrue_fees: config.accrue_fees,
+        },
+    ))
+}
+
+#[cosmwasm_schema::cw_serde]
+struct ConfigV1 {
+    username: String,
+    fee_denom: String,
+    issuer_fee_collector: Addr,
+}
+
+#[cosmwasm_schema::cw_serde]
+struct ConfigV2 {
+    username: String,
+    fee_denom: String,
+    issuer_fee_collector: Addr,
+    referral_fee_percentage: u32,
+    staking_fee_percentage: u32,
+    unbonding_period: Duration,
+}
+
+#[cosmwasm_schema::cw_serde]
+struct ConfigV4 {
+    username: String,
+    fee_denom: String,
+    issuer_fee_collector: Addr,
+    referral_fee_percentage: u32,
+    staking_fee_percentage: u32,
+    unbonding_period: Duration,
+    listing_royalty_percentage: u32,
+}
+
+#[cosmwasm_schema::cw_serde]
+struct ConfigV5 {
+    username: String,
+    fee_denom: String,
+    fee_recipients: Vec<FeeRecipient>,
+    referral_fee_percentage: u32,
+    staking_fee_percentage: u32,
+    unbonding_period: Duration,
+    listing_royalty_percentage: u32,
+}
+
+#[cosmwasm_schema::cw_serde]
+struct ConfigV6 {
+    username: String,
+    fee_denom: String,
+    fee_recipients: Vec<FeeRecipient>,
+    referral_fee_percentage: u32,
+    staking_fee_percentage: u32,
+    unbonding_period: Duration,
+    listing_royalty_percentage: u32,
+    accrue_fees: bool,
+}
+
+#[cosmwasm_schema::cw_serde]
+struct ConfigV7 {
+    username: String,
+    fee_denom: String,
+    fee_recipients: Vec<FeeRecipient>,
+    referral_fee_percentage: u32,
+    staking_fee_percentage: u32,
+    unbonding_period: Duration,
+    listing_royalty_percentage: u32,
+    accrue_fees: bool,
+    accounts_only: bool,
+}
+
+#[cosmwasm_schema::cw_serde]
+struct AirdropV11 {
+    merkle_root: String,
+    total_amount: Uint128,
+    claimed: Uint128,
+    funded_reward_per_key: Option<Decimal256>,
+}
+
+/// Adds the referral, rewards and staking state, fees are off until set by the migrate msg
+fn migrate_v1_to_v2(
+    deps: DepsMut,
+    env: &Env,
+    _app: &FriendTechApp,
+    _msg: &FriendTechAppMigrateMsg,
+) -> FriendTechAppResult<()> {
+    let legacy_config: Item<ConfigV1> = Item::new("CONFIG");
+    let ConfigV1 {
+        username,
+        fee_denom,
+        issuer_fee_collector,
+    } = legacy_config.load(deps.storage)?;
+    // Fees were forwarded on every trade, so the app only holds the prices paid into the curve
+    let reserve = deps
+        .querier
+        .query_balance(&env.contract.address, &fee_denom)?
+        .amount;
+    RESERVE.save(deps.storage, &reserve)?;
+
+    let config: Item<ConfigV2> = Item::new("CONFIG");
+    config.save(
+        deps.storage,
+        &ConfigV2 {
+            username,
+            fee_denom,
+            issuer_fee_collector,
+            referral_fee_percentage: 0,
+            staking_fee_percentage: 0,
+            unbonding_period: Duration::Time(0),
+        },
+    )?;
+
+    let holder_count = HOLDERS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?
+        .len() as u64;
+    HOLDER_COUNT.save(deps.storage, &holder_count)?;
+
+    REWARD_PER_KEY.save(deps.storage, &Decimal256::zero())?;
+    STAKING.save(deps.storage, &StakingState::default())?;
+    Ok(())
+}
+
+/// Adds the market shutdown, installs being migrated are still trading
+fn migrate_v2_to_v3(
+    deps: DepsMut,
+    _env: &Env,
+    _app: &FriendTechApp,
+    _msg: &FriendTechAppMigrateMsg,
+) -> FriendTechAppResult<()> {
+    MARKET_CLOSED.save(deps.storage, &false)?;
+    Ok(())
+}
+
+/// Adds the listings, fills pay no royalty until set by the migrate msg
+fn migrate_v3_to_v4(
+    deps: DepsMut,
+    _env: &Env,
+    _app: &FriendTechApp,
+    _msg: &FriendTechAppMigrateMsg,
+) -> FriendTechAppResult<()> {
+    let legacy_config: Item<ConfigV2> = Item::new("CONFIG");
+    let ConfigV2 {
+        username,
+        fee_denom,
+        issuer_fee_collector,
+        referral_fee_percentage,
+        staking_fee_percentage,
+        unbonding_period,
+    } = legacy_config.load(deps.storage)?;
+    let config: Item<ConfigV4> = Item::new("CONFIG");
+    config.save(
+        deps.storage,
+        &ConfigV4 {
+            username,
+            fee_denom,
+            issuer_fee_collector,
+            referral_fee_percentage,
+            staking_fee_percentage,
+            unbonding_period,
+            listing_royalty_percentage: 0,
+        },
+    )?;
+    NEXT_LISTING_ID.save(deps.storage, &0)?;
+    Ok(())
+}
+
+/// Replaces the issuer fee collector with the fee recipients, the collector keeps every fee
+fn migrate_v4_to_v5(
+    deps: DepsMut,
+    _env: &Env,
+    _app: &FriendTechApp,
+    _msg: &FriendTechAppMigrateMsg,
+) -> FriendTechAppResult<()> {
+    let legacy_config: Item<ConfigV4> = Item::new("CONFIG");
+    let ConfigV4 {
+        username,
+        fee_denom,
+        issuer_fee_collector,
+        referral_fee_percentage,
+        staking_fee_percentage,
+        unbonding_period,
+        listing_royalty_percentage,
+    } = legacy_config.load(deps.storage)?;
+    let config: Item<ConfigV5> = Item::new("CONFIG");
+    config.save(
+        deps.storage,
+        &ConfigV5 {
+            username,
+            fee_denom,
+            fee_recipients: vec![FeeRecipient {
+                address: issuer_fee_collector,
+                weight: 100,
+            }],
+            referral_fee_percentage,
+            staking_fee_percentage,
+            unbonding_period,
+            listing_royalty_percentage,
+        },
+    )?;
+    Ok(())
+}
+
+/// Adds the fee accrual, fees are still sent on every trade until enabled by the migrate msg
+fn migrate_v5_to_v6(
+    deps: DepsMut,
+    _env: &Env,
+    _app: &FriendTechApp,
+    _msg: &FriendTechAppMigrateMsg,
+) -> FriendTechAppResult<()> {
+    let legacy_config: Item<ConfigV5> = Item::new("CONFIG");
+    let ConfigV5 {
+        username,
+        fee_denom,
+        fee_recipients,
+        referral_fee_percentage,
+        staking_fee_percentage,
+        unbonding_period,
+        listing_royalty_percentage,
+    } = legacy_config.load(deps.storage)?;
+    let config: Item<ConfigV6> = Item::new("CONFIG");
+    config.save(
+        deps.storage,
+        &ConfigV6 {
+            username,
+            fee_denom,
+            fee_recipients,
+            referral_fee_percentage,
+            staking_fee_percentage,
+            unbonding_period,
+            listing_royalty_percentage,
+            accrue_fees: false,
+        },
+    )?;
+    ACCRUED_FEES.save(deps.storage, &Uint128::zero())?;
+    Ok(())
+}
+
+/// Adds the accounts only mode, existing holders are plain addresses so it stays off
+fn migrate_v6_to_v7(
+    deps: DepsMut,
+    _env: &Env,
+    _app: &FriendTechApp,
+    _msg: &FriendTechAppMigrateMsg,
+) -> FriendTechAppResult<()> {
+    let legacy_config: Item<ConfigV6> = Item::new("CONFIG");
+    let ConfigV6 {
+        username,
+        fee_denom,
+        fee_recipients,
+        referral_fee_percentage,
+        staking_fee_percentage,
+        unbonding_period,
+        listing_royalty_percentage,
+        accrue_fees,
+    } = legacy_config.load(deps.storage)?;
+    let config: Item<ConfigV7> = Item::new("CONFIG");
+    config.save(
+        deps.storage,
+        &ConfigV7 {
+            username,
+            fee_denom,
+            fee_recipients,
+            referral_fee_percentage,
+            staking_fee_percentage,
+            unbonding_period,
+            listing_royalty_percentage,
+            accrue_fees,
+            accounts_only: false,
+        },
+    )?;
+    Ok(())
+}
+
+/// Records the holder of the issuer's key, the account owner at install time unless named by the migrate msg
+/// Name the previous owner when the account changed hands since the install, they still hold the key
+fn migrate_v7_to_v8(
+    deps: DepsMut,
+    _env: &Env,
+    app: &FriendTechApp,
+    msg: &FriendTechAppMigrateMsg,
+) -> FriendTechAppResult<()> {
+    let config: Item<ConfigV7> = Item::new("CONFIG");
+    let (issuer_holder_addr, holding) = if config.load(deps.storage)?.accounts_only {
+        (
+            app.proxy_address(deps.as_ref())?,
+            ACCOUNT_HOLDERS.may_load(deps.storage, app.account_id(deps.as_ref())?)?,
+        )
+    } else {
+        let holder = match &msg.issuer_key_holder {
+            Some(holder) => deps.api.addr_validate(holder)?,
+            None => get_account_owner_addr(deps.as_ref(), app)?,
+        };
+        let holding = HOLDERS.may_load(deps.storage, &holder)?;
+        (holder, holding)
+    };
+    if holding.is_none() {
+        return Err(FriendTechAppError::IssuerKeyNotHeld {
+            holder: issuer_holder_addr.to_string(),
+        });
+    }
+    ISSUER.save(deps.storage, &issuer_holder_addr)?;
+    Ok(())
+}
+
+/// Adds the proposals, governance stays disabled until the owner sets it
+fn migrate_v8_to_v9(
+    deps: DepsMut,
+    _env: &Env,
+    _app: &FriendTechApp,
+    _msg: &FriendTechAppMigrateMsg,
+) -> FriendTechAppResult<()> {
+    NEXT_PROPOSAL_ID.save(deps.storage, &0)?;
+    Ok(())
+}
+
+/// Adds the buyback stats, no keys were burned before
+fn migrate_v9_to_v10(
+    deps: DepsMut,
+    _env: &Env,
+    _app: &FriendTechApp,
+    _msg: &FriendTechAppMigrateMsg,
+) -> FriendTechAppResult<()> {
+    BURNED.save(deps.storage, &Uint128::zero())?;
+    Ok(())
+}
+
+/// Adds the key locks, no keys were escrowed before
+fn migrate_v10_to_v11(
+    deps: DepsMut,
+    _env: &Env,
+    _app: &FriendTechApp,
+    _msg: &FriendTechAppMigrateMsg,
+) -> FriendTechAppResult<()> {
+    NEXT_LOCK_ID.save(deps.storage, &0)?;
+    Ok(())
+}
+
+/// Adds the airdrop expiry, airdrops set before stay claimable for good
+fn migrate_v11_to_v12(
+    deps: DepsMut,
+    _env: &Env,
+    _app: &FriendTechApp,
+    _msg: &FriendTechAppMigrateMsg,
+) -> FriendTechAppResult<()> {
+    let legacy_airdrop: Item<AirdropV11> = Item::new("AIRDROP");
+    if let Some(airdrop) = legacy_airdrop.may_load(deps.storage)? {
+        AIRDROP.save(
+            deps.storage,
+            &Airdrop {
+                merkle_root: airdrop.merkle_root,
+                total_amount: airdrop.total_amount,
+                claimed: airdrop.claimed,
+                reclaimed: Uint128::zero(),
+                expires: Expiration::Never {},
+                funded_reward_per_key: airdrop.funded_reward_per_key,
+            },
+        )?;
+    }
+    Ok(())
 }
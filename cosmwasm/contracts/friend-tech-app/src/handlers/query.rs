@@ -1,39 +1,159 @@
 use crate::{
+    auction::auction_price,
     contract::{FriendTechApp, FriendTechAppResult},
+    dynamic_fee::{issuer_fee_percentage, TradeSide},
+    governance::{account_contracts, needs_owner_approval, proposal_status},
     msg::{
-        BuyKeyCostResponse, FriendTechAppQueryMsg, HoldersResponse, HoldingResponse,
-        IssuerResponse, SellKeyCostResponse,
+        AccruedFeesResponse, AirdropClaimResponse, AirdropResponse, ApprovalExemptionsResponse,
+        AuctionResponse, BallotResponse, BidResponse, BuyKeyCostResponse, DynamicFeeResponse,
+        FriendTechAppQueryMsg, GovernanceResponse, HasMinimumKeysResponse, HoldersResponse,
+        HoldingResponse, HookResponse, HooksResponse, IssuerResponse, KeysForBudgetResponse,
+        ListingResponse, ListingsResponse, LockResponse, LocksResponse, MarketResponse,
+        PendingRewardsResponse, PortfolioResponse, PriceCurveResponse, PricePoint,
+        ProposalResponse, ProposalsResponse, RateLimitsResponse, ReferrerResponse,
+        RoyaltyExemptionsResponse, SellKeyCostResponse, StakedResponse, StakingInfoResponse,
+        TransferRoyaltyCostResponse, TransferRoyaltyResponse,
     },
-    state::{CONFIG, HOLDERS, SUPPLY},
-    utils::{calculate_buy_price, calculate_sell_price, multiply_percentage},
+    royalty::{gift_royalty, transfer_royalty},
+    staking::{active_unbonding, query_stake, stake_weight, total_weight},
+    state::{
+        listings, locks, KeyLock, Listing, Proposal, ACCOUNT_HOLDERS, ACCRUED_FEES, AIRDROP,
+        AIRDROP_CLAIMS, APPROVAL_EXEMPTIONS, APPROVED_PROPOSALS, AUCTION, BALLOTS, BIDS, BURNED,
+        CONFIG, COST_BASIS, DYNAMIC_FEE, GOVERNANCE, HOLDERS, HOLDER_COUNT, HOOKS, ISSUER,
+        MARKET_CLOSED, PROPOSALS, RATE_LIMITS, REFERRERS, RESERVE, ROYALTY_EXEMPTIONS, STAKING,
+        SUPPLY, TRANSFER_ROYALTY,
+    },
+    utils::{
+        calculate_buy_price, calculate_sell_price, may_load_holding, multiply_percentage,
+        query_holder_rewards, query_recent_volume,
+    },
+    FriendTechAppError,
 };
 
-use cosmwasm_std::{to_json_binary, Binary, Deps, Env, Order, StdResult, Uint128};
+use abstract_app::objects::AccountId;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, BlockInfo, Decimal, Deps, Env, Int128, Order, StdResult, Uint128,
+};
 use cw_storage_plus::Bound;
 
 const DEFAULT_QUERY_LIMIT: u32 = 10;
 const MAX_QUERY_LIMIT: u32 = 30;
-
-const ISSUER_FEE_PERCENTAGE: u32 = 5;
+const MAX_PRICE_CURVE_POINTS: usize = 100;
 
 pub fn query_handler(
     deps: Deps,
-    _env: Env,
-    _app: &FriendTechApp,
+    env: Env,
+    app: &FriendTechApp,
     msg: FriendTechAppQueryMsg,
 ) -> FriendTechAppResult<Binary> {
     match msg {
         FriendTechAppQueryMsg::Issuer {} => to_json_binary(&query_issuer(deps)?),
-        FriendTechAppQueryMsg::BuyKeyCost { amount } => {
-            to_json_binary(&query_buy_key_cost(deps, amount)?)
+        FriendTechAppQueryMsg::BuyKeyCost { amount, trader } => {
+            let trader = trader
+                .map(|trader| deps.api.addr_validate(&trader))
+                .transpose()?;
+            to_json_binary(&query_buy_key_cost(
+                deps,
+                &env.block,
+                amount,
+                trader.as_ref(),
+            )?)
+        }
+        FriendTechAppQueryMsg::SellKeyCost { amount, trader } => {
+            let trader = trader
+                .map(|trader| deps.api.addr_validate(&trader))
+                .transpose()?;
+            to_json_binary(&query_sell_key_cost(
+                deps,
+                &env.block,
+                amount,
+                trader.as_ref(),
+            )?)
         }
-        FriendTechAppQueryMsg::SellKeyCost { amount } => {
-            to_json_binary(&query_sell_key_cost(deps, amount)?)
+        FriendTechAppQueryMsg::KeysForBudget { budget, trader } => {
+            let trader = trader
+                .map(|trader| deps.api.addr_validate(&trader))
+                .transpose()?;
+            to_json_binary(&query_keys_for_budget(
+                deps,
+                &env.block,
+                budget,
+                trader.as_ref(),
+            )?)
         }
+        FriendTechAppQueryMsg::DynamicFee {} => to_json_binary(&query_dynamic_fee(deps)?),
+        FriendTechAppQueryMsg::RateLimits {} => to_json_binary(&query_rate_limits(deps)?),
+        FriendTechAppQueryMsg::PriceCurve {
+            from_supply,
+            to_supply,
+            step,
+        } => to_json_binary(&query_price_curve(from_supply, to_supply, step)?),
+        FriendTechAppQueryMsg::Market {} => to_json_binary(&query_market(deps, env)?),
         FriendTechAppQueryMsg::Holders { limit, start_after } => {
             to_json_binary(&query_holders(deps, limit, start_after)?)
         }
         FriendTechAppQueryMsg::Holding { holder } => to_json_binary(&query_holding(deps, holder)?),
+        FriendTechAppQueryMsg::Portfolio { holder } => {
+            to_json_binary(&query_portfolio(deps, env, holder)?)
+        }
+        FriendTechAppQueryMsg::HasMinimumKeys { holder, min } => {
+            to_json_binary(&query_has_minimum_keys(deps, holder, min)?)
+        }
+        FriendTechAppQueryMsg::Listing { listing_id } => {
+            to_json_binary(&query_listing(deps, listing_id)?)
+        }
+        FriendTechAppQueryMsg::Listings { limit, start_after } => {
+            to_json_binary(&query_listings(deps, limit, start_after)?)
+        }
+        FriendTechAppQueryMsg::ListingsBySeller {
+            seller,
+            limit,
+            start_after,
+        } => to_json_binary(&query_listings_by_seller(deps, seller, limit, start_after)?),
+        FriendTechAppQueryMsg::KeyLock { lock_id } => to_json_binary(&query_lock(deps, lock_id)?),
+        FriendTechAppQueryMsg::LocksByHolder {
+            holder,
+            limit,
+            start_after,
+        } => to_json_binary(&query_locks_by_holder(deps, holder, limit, start_after)?),
+        FriendTechAppQueryMsg::Auction {} => to_json_binary(&query_auction(deps, env)?),
+        FriendTechAppQueryMsg::AuctionBid { bidder } => {
+            to_json_binary(&query_auction_bid(deps, bidder)?)
+        }
+        FriendTechAppQueryMsg::Airdrop {} => to_json_binary(&query_airdrop(deps)?),
+        FriendTechAppQueryMsg::AirdropClaim { address } => {
+            to_json_binary(&query_airdrop_claim(deps, address)?)
+        }
+        FriendTechAppQueryMsg::Hooks {} => to_json_binary(&query_hooks(deps)?),
+        FriendTechAppQueryMsg::AccruedFees {} => to_json_binary(&query_accrued_fees(deps)?),
+        FriendTechAppQueryMsg::TransferRoyalty {} => to_json_binary(&query_transfer_royalty(deps)?),
+        FriendTechAppQueryMsg::TransferRoyaltyCost { amount, recipient } => {
+            to_json_binary(&query_transfer_royalty_cost(deps, amount, recipient)?)
+        }
+        FriendTechAppQueryMsg::RoyaltyExemptions { limit, start_after } => {
+            to_json_binary(&query_royalty_exemptions(deps, limit, start_after)?)
+        }
+        FriendTechAppQueryMsg::Governance {} => to_json_binary(&query_governance(deps)?),
+        FriendTechAppQueryMsg::Proposal { proposal_id } => {
+            to_json_binary(&query_proposal(deps, env, app, proposal_id)?)
+        }
+        FriendTechAppQueryMsg::Proposals { limit, start_after } => {
+            to_json_binary(&query_proposals(deps, env, app, limit, start_after)?)
+        }
+        FriendTechAppQueryMsg::ApprovalExemptions { limit, start_after } => {
+            to_json_binary(&query_approval_exemptions(deps, limit, start_after)?)
+        }
+        FriendTechAppQueryMsg::Ballot { proposal_id, voter } => {
+            to_json_binary(&query_ballot(deps, proposal_id, voter)?)
+        }
+        FriendTechAppQueryMsg::Referrer { addr } => to_json_binary(&query_referrer(deps, addr)?),
+        FriendTechAppQueryMsg::PendingRewards { holder } => {
+            to_json_binary(&query_pending_rewards(deps, holder)?)
+        }
+        FriendTechAppQueryMsg::Staked { holder } => {
+            to_json_binary(&query_staked(deps, env, holder)?)
+        }
+        FriendTechAppQueryMsg::StakingInfo {} => to_json_binary(&query_staking_info(deps, env)?),
     }
     .map_err(Into::into)
 }
@@ -44,38 +164,197 @@ fn query_issuer(deps: Deps) -> StdResult<IssuerResponse> {
     Ok(IssuerResponse {
         username: config.username,
         fee_denom: config.fee_denom,
-        issuer_fee_collector: config.issuer_fee_collector,
+        fee_recipients: config.fee_recipients,
+        referral_fee_percentage: config.referral_fee_percentage,
+        listing_royalty_percentage: config.listing_royalty_percentage,
+        accrue_fees: config.accrue_fees,
+        accounts_only: config.accounts_only,
+        issuer_key_holder: ISSUER.load(deps.storage)?,
         supply,
     })
 }
 
-pub fn query_buy_key_cost(deps: Deps, amount: Uint128) -> StdResult<BuyKeyCostResponse> {
-    let old_supply = SUPPLY.load(deps.storage)?;
-    let price = calculate_buy_price(old_supply, amount);
-    let issuer_fee = multiply_percentage(price, ISSUER_FEE_PERCENTAGE);
+fn buy_key_cost(
+    old_supply: Uint128,
+    amount: Uint128,
+    issuer_fee_percentage: u32,
+) -> StdResult<BuyKeyCostResponse> {
+    let price = calculate_buy_price(old_supply, amount)?;
+    let issuer_fee = multiply_percentage(price, issuer_fee_percentage);
     Ok(BuyKeyCostResponse {
         price,
         issuer_fee,
-        total_cost: price + issuer_fee,
+        issuer_fee_percentage,
+        total_cost: price.checked_add(issuer_fee)?,
     })
 }
 
-pub fn query_sell_key_cost(deps: Deps, amount: Uint128) -> StdResult<SellKeyCostResponse> {
+pub fn query_buy_key_cost(
+    deps: Deps,
+    block: &BlockInfo,
+    amount: Uint128,
+    trader: Option<&Addr>,
+) -> StdResult<BuyKeyCostResponse> {
     let old_supply = SUPPLY.load(deps.storage)?;
-    let price = calculate_sell_price(old_supply - amount, amount);
-    let issuer_fee = multiply_percentage(price, ISSUER_FEE_PERCENTAGE);
+    let issuer_fee_percentage = issuer_fee_percentage(deps.storage, block, trader, TradeSide::Buy)?;
+    buy_key_cost(old_supply, amount, issuer_fee_percentage)
+}
+
+pub fn query_sell_key_cost(
+    deps: Deps,
+    block: &BlockInfo,
+    amount: Uint128,
+    trader: Option<&Addr>,
+) -> StdResult<SellKeyCostResponse> {
+    let old_supply = SUPPLY.load(deps.storage)?;
+    let price = calculate_sell_price(old_supply, amount)?;
+    let issuer_fee_percentage =
+        issuer_fee_percentage(deps.storage, block, trader, TradeSide::Sell)?;
+    let issuer_fee = multiply_percentage(price, issuer_fee_percentage);
     Ok(SellKeyCostResponse {
         price,
         issuer_fee,
+        issuer_fee_percentage,
         total_cost: issuer_fee,
     })
 }
 
+fn query_keys_for_budget(
+    deps: Deps,
+    block: &BlockInfo,
+    budget: Uint128,
+    trader: Option<&Addr>,
+) -> StdResult<KeysForBudgetResponse> {
+    let old_supply = SUPPLY.load(deps.storage)?;
+    let issuer_fee_percentage = issuer_fee_percentage(deps.storage, block, trader, TradeSide::Buy)?;
+    let (amount, affordable) = keys_for_budget(old_supply, budget, issuer_fee_percentage);
+    Ok(KeysForBudgetResponse {
+        amount,
+        price: affordable.price,
+        issuer_fee: affordable.issuer_fee,
+        issuer_fee_percentage,
+        total_cost: affordable.total_cost,
+        leftover: budget - affordable.total_cost,
+    })
+}
+
+/// Largest amount of keys the budget covers at the supply, with the cost of buying them
+/// The total cost only grows with the amount bought, so the largest affordable amount is found with
+/// an exponential then binary search over the cost, whatever the curve is
+/// Amounts whose cost overflows are out of any budget, which bounds the search
+pub fn keys_for_budget(
+    old_supply: Uint128,
+    budget: Uint128,
+    issuer_fee_percentage: u32,
+) -> (Uint128, BuyKeyCostResponse) {
+    // Find an amount the budget cannot cover
+    let mut affordable = BuyKeyCostResponse {
+        price: Uint128::zero(),
+        issuer_fee: Uint128::zero(),
+        issuer_fee_percentage,
+        total_cost: Uint128::zero(),
+    };
+    let mut low = Uint128::zero();
+    let mut high = Uint128::one();
+    loop {
+        match buy_key_cost(old_supply, high, issuer_fee_percentage) {
+            Ok(cost) if cost.total_cost <= budget => {
+                affordable = cost;
+                low = high;
+            }
+            _ => break,
+        }
+        // The cost of Uint128::MAX keys overflows, so the search always ends
+        high = high
+            .checked_mul(Uint128::from(2_u64))
+            .unwrap_or(Uint128::MAX);
+    }
+
+    // Narrow down to the largest affordable amount, low is always affordable and high never is
+    while high - low > Uint128::one() {
+        let mid = low + (high - low) / Uint128::from(2_u64);
+        match buy_key_cost(old_supply, mid, issuer_fee_percentage) {
+            Ok(cost) if cost.total_cost <= budget => {
+                affordable = cost;
+                low = mid;
+            }
+            _ => high = mid,
+        }
+    }
+    (low, affordable)
+}
+
+/// At most MAX_PRICE_CURVE_POINTS points are returned, query again from the last supply for more
+/// Supplies whose price overflows are out of the curve and error
+fn query_price_curve(
+    from_supply: Uint128,
+    to_supply: Uint128,
+    step: Uint128,
+) -> FriendTechAppResult<PriceCurveResponse> {
+    if step.is_zero() {
+        return Err(FriendTechAppError::InvalidPriceCurveStep {});
+    }
+    if from_supply > to_supply {
+        return Err(FriendTechAppError::InvalidPriceCurveRange {
+            from_supply,
+            to_supply,
+        });
+    }
+
+    let mut points = vec![];
+    let mut supply = from_supply;
+    while points.len() < MAX_PRICE_CURVE_POINTS {
+        points.push(PricePoint {
+            supply,
+            price: calculate_buy_price(supply, Uint128::one())?,
+        });
+        match supply.checked_add(step) {
+            Ok(next_supply) if next_supply <= to_supply => supply = next_supply,
+            _ => break,
+        }
+    }
+    Ok(PriceCurveResponse { points })
+}
+
+fn query_market(deps: Deps, env: Env) -> StdResult<MarketResponse> {
+    let supply = SUPPLY.load(deps.storage)?;
+    let spot_price = calculate_buy_price(supply, Uint128::one())?;
+    Ok(MarketResponse {
+        supply,
+        spot_price,
+        market_cap: spot_price * supply,
+        reserve: RESERVE.load(deps.storage)?,
+        burned: BURNED.load(deps.storage)?,
+        volume_24h: query_recent_volume(deps.storage, &env.block)?,
+        holder_count: HOLDER_COUNT.load(deps.storage)?,
+        closed: MARKET_CLOSED.load(deps.storage)?,
+    })
+}
+
 fn query_holders(
     deps: Deps,
     limit: Option<u32>,
     start_after: Option<String>,
-) -> StdResult<HoldersResponse> {
+) -> FriendTechAppResult<HoldersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    if CONFIG.load(deps.storage)?.accounts_only {
+        let start_after = start_after
+            .map(|account_id| AccountId::try_from(account_id.as_str()))
+            .transpose()?;
+        let accounts = ACCOUNT_HOLDERS
+            .keys(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
+        return Ok(HoldersResponse {
+            holders: vec![],
+            accounts,
+        });
+    }
     let holders = match start_after {
         Some(start_after) => HOLDERS.range(
             deps.storage,
@@ -85,16 +364,425 @@ fn query_holders(
         ),
         None => HOLDERS.range(deps.storage, None, None, Order::Ascending),
     }
-    .take(limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize)
+    .take(limit)
     .map(|item| item.map(|(addr, _)| addr))
     .collect::<StdResult<Vec<_>>>()?;
-    Ok(HoldersResponse { holders })
+    Ok(HoldersResponse {
+        holders,
+        accounts: vec![],
+    })
 }
 
 fn query_holding(deps: Deps, holder: String) -> StdResult<HoldingResponse> {
     let holder_addr = deps.api.addr_validate(&holder)?;
-    let amount = HOLDERS
-        .may_load(deps.storage, &holder_addr)?
-        .unwrap_or(Uint128::zero());
+    let amount = may_load_holding(deps.storage, &holder_addr)?.unwrap_or(Uint128::zero());
     Ok(HoldingResponse { amount })
 }
+
+fn query_portfolio(deps: Deps, env: Env, holder: String) -> StdResult<PortfolioResponse> {
+    let holder_addr = deps.api.addr_validate(&holder)?;
+    let holding = may_load_holding(deps.storage, &holder_addr)?.unwrap_or_default();
+    let cost_basis = COST_BASIS
+        .may_load(deps.storage, &holder_addr)?
+        .unwrap_or_default();
+    let average_cost = if holding.is_zero() {
+        Decimal::zero()
+    } else {
+        Decimal::from_ratio(cost_basis, holding)
+    };
+
+    // Selling every key takes them off the top of the curve, down to the supply held by the others
+    let cost = query_sell_key_cost(deps, &env.block, holding, Some(&holder_addr))?;
+    let liquidation_value = cost.price - cost.issuer_fee;
+    let unrealized_pnl = Int128::try_from(liquidation_value)? - Int128::try_from(cost_basis)?;
+
+    Ok(PortfolioResponse {
+        holding,
+        liquidation_value,
+        cost_basis,
+        average_cost,
+        unrealized_pnl,
+    })
+}
+
+fn query_has_minimum_keys(
+    deps: Deps,
+    holder: String,
+    min: Uint128,
+) -> StdResult<HasMinimumKeysResponse> {
+    let HoldingResponse { amount } = query_holding(deps, holder)?;
+    Ok(HasMinimumKeysResponse {
+        has_minimum_keys: amount >= min,
+        amount,
+    })
+}
+
+fn listing_response(listing_id: u64, listing: Listing) -> ListingResponse {
+    ListingResponse {
+        listing_id,
+        seller: listing.seller,
+        amount: listing.amount,
+        price_per_key: listing.price_per_key,
+    }
+}
+
+/// Listings are sorted by price then id, so paginating after a listing needs its price
+fn listing_start_bound(
+    deps: Deps,
+    start_after: Option<u64>,
+) -> StdResult<Option<Bound<'static, (u128, u64)>>> {
+    start_after
+        .map(|listing_id| {
+            let listing = listings().load(deps.storage, listing_id)?;
+            Ok(Bound::exclusive((listing.price_per_key.u128(), listing_id)))
+        })
+        .transpose()
+}
+
+fn query_listing(deps: Deps, listing_id: u64) -> StdResult<ListingResponse> {
+    let listing = listings().load(deps.storage, listing_id)?;
+    Ok(listing_response(listing_id, listing))
+}
+
+fn query_listings(
+    deps: Deps,
+    limit: Option<u32>,
+    start_after: Option<u64>,
+) -> StdResult<ListingsResponse> {
+    let start = listing_start_bound(deps, start_after)?;
+    let listings = listings()
+        .idx
+        .price
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize)
+        .map(|item| item.map(|(listing_id, listing)| listing_response(listing_id, listing)))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ListingsResponse { listings })
+}
+
+fn query_listings_by_seller(
+    deps: Deps,
+    seller: String,
+    limit: Option<u32>,
+    start_after: Option<u64>,
+) -> StdResult<ListingsResponse> {
+    let seller_addr = deps.api.addr_validate(&seller)?;
+    let start = listing_start_bound(deps, start_after)?;
+    let listings = listings()
+        .idx
+        .seller
+        .sub_prefix(seller_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize)
+        .map(|item| item.map(|(listing_id, listing)| listing_response(listing_id, listing)))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ListingsResponse { listings })
+}
+
+fn lock_response(lock_id: u64, lock: KeyLock) -> LockResponse {
+    LockResponse {
+        lock_id,
+        holder: lock.holder,
+        amount: lock.amount,
+        until: lock.until,
+        beneficiary_app: lock.beneficiary_app,
+    }
+}
+
+fn query_lock(deps: Deps, lock_id: u64) -> StdResult<LockResponse> {
+    let lock = locks().load(deps.storage, lock_id)?;
+    Ok(lock_response(lock_id, lock))
+}
+
+fn query_locks_by_holder(
+    deps: Deps,
+    holder: String,
+    limit: Option<u32>,
+    start_after: Option<u64>,
+) -> StdResult<LocksResponse> {
+    let holder_addr = deps.api.addr_validate(&holder)?;
+    let locks = locks()
+        .idx
+        .holder
+        .prefix(holder_addr)
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize)
+        .map(|item| item.map(|(lock_id, lock)| lock_response(lock_id, lock)))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(LocksResponse { locks })
+}
+
+fn query_auction(deps: Deps, env: Env) -> StdResult<Option<AuctionResponse>> {
+    let Some(auction) = AUCTION.may_load(deps.storage)? else {
+        return Ok(None);
+    };
+    Ok(Some(AuctionResponse {
+        amount: auction.amount,
+        sold: auction.sold,
+        start_price: auction.start_price,
+        end_price: auction.end_price,
+        start_time: auction.start_time,
+        end_time: auction.end_time,
+        current_price: auction
+            .clearing_price
+            .unwrap_or_else(|| auction_price(&auction, env.block.time)),
+        clearing_price: auction.clearing_price,
+        settled: auction.settled_reward_per_key.is_some(),
+    }))
+}
+
+fn query_airdrop(deps: Deps) -> StdResult<Option<AirdropResponse>> {
+    let Some(airdrop) = AIRDROP.may_load(deps.storage)? else {
+        return Ok(None);
+    };
+    // The airdrop is funded while only the issuer's key exists
+    Ok(Some(AirdropResponse {
+        funding_cost: calculate_buy_price(Uint128::one(), airdrop.total_amount)?,
+        merkle_root: airdrop.merkle_root,
+        total_amount: airdrop.total_amount,
+        claimed: airdrop.claimed,
+        reclaimed: airdrop.reclaimed,
+        expires: airdrop.expires,
+        funded: airdrop.funded_reward_per_key.is_some(),
+    }))
+}
+
+fn query_airdrop_claim(deps: Deps, address: String) -> StdResult<AirdropClaimResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+    Ok(AirdropClaimResponse {
+        claimed: AIRDROP_CLAIMS
+            .may_load(deps.storage, &addr)?
+            .unwrap_or_default(),
+    })
+}
+
+fn query_auction_bid(deps: Deps, bidder: String) -> StdResult<BidResponse> {
+    let bidder_addr = deps.api.addr_validate(&bidder)?;
+    let bid = BIDS
+        .may_load(deps.storage, &bidder_addr)?
+        .unwrap_or_default();
+    Ok(BidResponse {
+        amount: bid.amount,
+        deposit: bid.deposit,
+    })
+}
+
+fn query_hooks(deps: Deps) -> StdResult<HooksResponse> {
+    let hooks = HOOKS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            item.map(|(contract, on_failure)| HookResponse {
+                contract,
+                on_failure,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(HooksResponse { hooks })
+}
+
+fn query_dynamic_fee(deps: Deps) -> StdResult<DynamicFeeResponse> {
+    Ok(DynamicFeeResponse {
+        dynamic_fee: DYNAMIC_FEE.may_load(deps.storage)?,
+    })
+}
+
+fn query_transfer_royalty(deps: Deps) -> StdResult<TransferRoyaltyResponse> {
+    Ok(TransferRoyaltyResponse {
+        royalty: TRANSFER_ROYALTY.may_load(deps.storage)?,
+    })
+}
+
+fn query_transfer_royalty_cost(
+    deps: Deps,
+    amount: Uint128,
+    recipient: Option<String>,
+) -> StdResult<TransferRoyaltyCostResponse> {
+    let royalty = match recipient {
+        Some(recipient) => {
+            gift_royalty(deps.storage, &deps.api.addr_validate(&recipient)?, amount)?
+        }
+        None => transfer_royalty(deps.storage, amount)?,
+    };
+    Ok(TransferRoyaltyCostResponse { royalty })
+}
+
+fn query_royalty_exemptions(
+    deps: Deps,
+    limit: Option<u32>,
+    start_after: Option<String>,
+) -> StdResult<RoyaltyExemptionsResponse> {
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let addresses = ROYALTY_EXEMPTIONS
+        .keys(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(RoyaltyExemptionsResponse { addresses })
+}
+
+fn query_approval_exemptions(
+    deps: Deps,
+    limit: Option<u32>,
+    start_after: Option<String>,
+) -> StdResult<ApprovalExemptionsResponse> {
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let addresses = APPROVAL_EXEMPTIONS
+        .keys(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ApprovalExemptionsResponse { addresses })
+}
+
+fn query_rate_limits(deps: Deps) -> StdResult<RateLimitsResponse> {
+    Ok(RateLimitsResponse {
+        rate_limits: RATE_LIMITS.may_load(deps.storage)?,
+    })
+}
+
+fn query_governance(deps: Deps) -> StdResult<GovernanceResponse> {
+    Ok(GovernanceResponse {
+        governance: GOVERNANCE.may_load(deps.storage)?,
+    })
+}
+
+fn proposal_response(
+    deps: Deps,
+    env: &Env,
+    account_contracts: &[Addr],
+    proposal_id: u64,
+    proposal: Proposal,
+) -> StdResult<ProposalResponse> {
+    let status = proposal_status(&proposal, &env.block);
+    let needs_owner_approval = needs_owner_approval(deps.storage, &proposal, account_contracts)?;
+    Ok(ProposalResponse {
+        proposal_id,
+        proposer: proposal.proposer,
+        title: proposal.title,
+        description: proposal.description,
+        msgs: proposal.msgs,
+        start_height: proposal.start_height,
+        expires: proposal.expires,
+        total_power: proposal.total_power,
+        quorum: proposal.quorum,
+        threshold: proposal.threshold,
+        yes: proposal.yes,
+        no: proposal.no,
+        abstain: proposal.abstain,
+        status,
+        needs_owner_approval,
+        owner_approved: APPROVED_PROPOSALS.has(deps.storage, proposal_id),
+    })
+}
+
+fn query_proposal(
+    deps: Deps,
+    env: Env,
+    app: &FriendTechApp,
+    proposal_id: u64,
+) -> FriendTechAppResult<ProposalResponse> {
+    let proposal = PROPOSALS.load(deps.storage, proposal_id)?;
+    let account_contracts = account_contracts(deps, app)?;
+    Ok(proposal_response(
+        deps,
+        &env,
+        &account_contracts,
+        proposal_id,
+        proposal,
+    )?)
+}
+
+fn query_proposals(
+    deps: Deps,
+    env: Env,
+    app: &FriendTechApp,
+    limit: Option<u32>,
+    start_after: Option<u64>,
+) -> FriendTechAppResult<ProposalsResponse> {
+    let account_contracts = account_contracts(deps, app)?;
+    let proposals = PROPOSALS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize)
+        .map(|item| {
+            let (proposal_id, proposal) = item?;
+            proposal_response(deps, &env, &account_contracts, proposal_id, proposal)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ProposalsResponse { proposals })
+}
+
+fn query_ballot(deps: Deps, proposal_id: u64, voter: String) -> StdResult<BallotResponse> {
+    let voter_addr = deps.api.addr_validate(&voter)?;
+    Ok(BallotResponse {
+        ballot: BALLOTS.may_load(deps.storage, (proposal_id, &voter_addr))?,
+    })
+}
+
+fn query_accrued_fees(deps: Deps) -> StdResult<AccruedFeesResponse> {
+    Ok(AccruedFeesResponse {
+        amount: ACCRUED_FEES.load(deps.storage)?,
+    })
+}
+
+fn query_referrer(deps: Deps, addr: String) -> StdResult<ReferrerResponse> {
+    let referrer_addr = deps.api.addr_validate(&addr)?;
+    let stats = REFERRERS
+        .may_load(deps.storage, &referrer_addr)?
+        .unwrap_or_default();
+    Ok(ReferrerResponse {
+        volume: stats.volume,
+        earnings: stats.earnings,
+    })
+}
+
+fn query_pending_rewards(deps: Deps, holder: String) -> StdResult<PendingRewardsResponse> {
+    let holder_addr = deps.api.addr_validate(&holder)?;
+    let rewards = query_holder_rewards(deps.storage, &holder_addr)?;
+    Ok(PendingRewardsResponse {
+        amount: rewards.pending,
+    })
+}
+
+fn query_staked(deps: Deps, env: Env, holder: String) -> StdResult<StakedResponse> {
+    let holder_addr = deps.api.addr_validate(&holder)?;
+    let stake = query_stake(deps.storage, &holder_addr)?;
+    Ok(StakedResponse {
+        staked: stake.amount,
+        weight: stake_weight(&stake, &env.block),
+        unbonding: active_unbonding(deps.storage, &env.block, &holder_addr)?,
+        pending_rewards: stake.pending,
+    })
+}
+
+fn query_staking_info(deps: Deps, env: Env) -> StdResult<StakingInfoResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let staking = STAKING.load(deps.storage)?;
+    Ok(StakingInfoResponse {
+        total_staked: staking.total_staked,
+        total_weight: total_weight(&staking, &env.block),
+        staking_fee_percentage: config.staking_fee_percentage,
+        unbonding_period: config.unbonding_period,
+    })
+}
@@ -0,0 +1,50 @@
+use cosmwasm_std::{to_json_binary, Addr, Order, StdResult, Storage, SubMsg, Uint128, WasmMsg};
+
+use crate::{
+    msg::FriendTechHookMsg,
+    replies::HOOK_REPLY_ID,
+    state::{HookFailure, HOOKS, SUPPLY},
+};
+
+/// Most hooks the owner can register, every hook costs gas on every trade
+pub const MAX_HOOKS: u32 = 10;
+
+/// Submessages notifying every hook of the key balance changes, given as (holder, old, new)
+/// Call it once the action is done so the hooks get the supply after the action
+pub fn key_balance_hooks(
+    storage: &dyn Storage,
+    changes: &[(&Addr, Uint128, Uint128)],
+) -> StdResult<Vec<SubMsg>> {
+    let hooks = HOOKS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    if hooks.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let supply = SUPPLY.load(storage)?;
+    let mut msgs = vec![];
+    for (holder, old, new) in changes {
+        if old == new {
+            continue;
+        }
+        let msg = to_json_binary(&FriendTechHookMsg::KeyBalanceChanged {
+            holder: (*holder).clone(),
+            old: *old,
+            new: *new,
+            supply,
+        })?;
+        for (contract, on_failure) in &hooks {
+            let execute = WasmMsg::Execute {
+                contract_addr: contract.to_string(),
+                msg: msg.clone(),
+                funds: vec![],
+            };
+            msgs.push(match on_failure {
+                HookFailure::Revert => SubMsg::new(execute),
+                HookFailure::Ignore => SubMsg::reply_on_error(execute, HOOK_REPLY_ID),
+            });
+        }
+    }
+    Ok(msgs)
+}
@@ -1,9 +1,20 @@
+mod airdrop;
 mod api;
+mod auction;
 pub mod contract;
+mod dynamic_fee;
 pub mod error;
+mod escrow;
+pub mod events;
+mod governance;
 mod handlers;
+mod hooks;
+mod listings;
 pub mod msg;
+mod rate_limit;
 mod replies;
+mod royalty;
+mod staking;
 pub mod state;
 mod utils;
 
@@ -14,6 +25,7 @@ pub const FRIEND_TECH_APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub use api::{FriendTech, FriendTechInterface};
 pub use contract::interface::Friendtech;
+pub use utils::split_fee;
 
 pub const MY_NAMESPACE: &str = "bull-market-lab";
 pub const FRIEND_TECH_APP_NAME: &str = "friend-tech-app";
@@ -0,0 +1,28 @@
+use cosmwasm_std::{Addr, Order, StdResult, Storage, Uint128};
+
+use crate::state::listings;
+
+/// Keys of the seller locked in open listings
+pub fn listed_keys(storage: &dyn Storage, seller: &Addr) -> StdResult<Uint128> {
+    listings()
+        .idx
+        .seller
+        .sub_prefix(seller.clone())
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, listing)| listing.amount))
+        .sum()
+}
+
+/// Remove every open listing of the seller, unlocking the listed keys
+pub fn remove_seller_listings(storage: &mut dyn Storage, seller: &Addr) -> StdResult<()> {
+    let listing_ids = listings()
+        .idx
+        .seller
+        .sub_prefix(seller.clone())
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for listing_id in listing_ids {
+        listings().remove(storage, listing_id)?;
+    }
+    Ok(())
+}
@@ -1,6 +1,14 @@
-use crate::contract::FriendTechApp;
+use crate::{
+    contract::FriendTechApp,
+    state::{
+        Ballot, DynamicFeeConfig, FeeRecipient, GovernanceConfig, HookFailure, RateLimits,
+        TransferRoyalty, Unbonding, VoteOption,
+    },
+};
 
-use cosmwasm_std::{Addr, Uint128};
+use abstract_app::objects::AccountId;
+use cosmwasm_std::{Addr, CosmosMsg, Decimal, Int128, Timestamp, Uint128};
+use cw_utils::{Duration, Expiration};
 
 // This is used for type safety and re-exporting the contract endpoint structs.
 abstract_app::app_msg_types!(
@@ -13,8 +21,62 @@ abstract_app::app_msg_types!(
 #[cosmwasm_schema::cw_serde]
 pub struct FriendTechAppInstantiateMsg {
     pub username: String,
-    pub issuer_fee_collector: String,
+    /// Recipients of the issuer fee, royalties and auction surplus, their weights must add up to 100
+    pub fee_recipients: Vec<UncheckedFeeRecipient>,
     pub fee_denom: String,
+    /// Percentage of the issuer fee paid to the referrer of a trade
+    pub referral_fee_percentage: u32,
+    /// Percentage of the issuer fee shared by the stakers
+    pub staking_fee_percentage: u32,
+    /// How long unstaked keys stay locked before they can be sold
+    pub unbonding_period: Duration,
+    /// Percentage of the price of every listing fill paid to the issuer fee collector
+    pub listing_royalty_percentage: u32,
+    /// Accrue the fees in the contract until the owner claims them, instead of sending them on every trade
+    pub accrue_fees: bool,
+    /// Only let the proxies of Abstract accounts buy, sell and hold keys, the issuer's key goes to the account proxy
+    pub accounts_only: bool,
+    /// Sell a first batch of keys in a Dutch auction before trading on the curve starts
+    pub opening_auction: Option<AuctionParams>,
+    /// Let the holders vote on proposals run by the account
+    pub governance: Option<GovernanceConfig>,
+    /// Raise the issuer fee with trading activity, it is a flat 5% otherwise
+    pub dynamic_fee: Option<DynamicFeeConfig>,
+    /// Charge a royalty on every transfer and listing fill, paid to the fee recipients
+    pub transfer_royalty: Option<TransferRoyalty>,
+    /// Grant keys to the leaves of a merkle tree, trading waits until the owner funds the airdrop
+    pub airdrop: Option<AirdropParams>,
+    /// Limit the trades per address and the volume per block on the curve
+    pub rate_limits: Option<RateLimits>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct UncheckedFeeRecipient {
+    pub address: String,
+    /// Percentage of the fees paid to the recipient
+    pub weight: u32,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct AirdropParams {
+    /// Hex encoded sha256 root of the merkle tree, each leaf hashes `friend_tech_airdrop:<address>:<amount>`
+    pub merkle_root: String,
+    /// Keys granted by the whole tree, they are minted when the airdrop is funded
+    pub total_amount: Uint128,
+    /// End of the claims, the owner can then reclaim the cost of the keys left unclaimed
+    pub expires: Expiration,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct AuctionParams {
+    /// Keys offered
+    pub amount: Uint128,
+    /// Price of a key when the auction starts, it decays linearly to the end price
+    pub start_price: Uint128,
+    /// Price of a key when the auction ends, it must cover the curve price of the batch
+    pub end_price: Uint128,
+    /// Duration of the auction in seconds
+    pub duration: u64,
 }
 
 /// App execute messages
@@ -23,15 +85,177 @@ pub struct FriendTechAppInstantiateMsg {
 #[impl_into(ExecuteMsg)]
 pub enum FriendTechAppExecuteMsg {
     /// Anyone can call, buy key issued by the module owner
+    /// The keys are credited to the recipient, or to the sender if no recipient is given
     #[payable]
-    BuyKey { amount: Uint128 },
+    BuyKey {
+        amount: Uint128,
+        referrer: Option<String>,
+        recipient: Option<String>,
+    },
     /// Anyone can call, sell key issued by the module owner
     #[payable]
-    SellKey { amount: Uint128 },
+    SellKey {
+        amount: Uint128,
+        referrer: Option<String>,
+    },
+    /// Anyone can call, distribute the paid funds pro rata to the current key holders
+    #[payable]
+    DepositRewards {},
+    /// Anyone can call, claim the rewards accrued on the keys held by the caller
+    ClaimRewards {},
+    /// Anyone can call, stake keys of the caller, staked keys cannot be sold
+    Stake { amount: Uint128 },
+    /// Anyone can call, unstake keys of the caller, they stay locked for the unbonding period
+    Unstake { amount: Uint128 },
+    /// Anyone can call, claim the staking fee earned by the stake of the caller
+    ClaimStakingRewards {},
+    /// Anyone can call, list keys of the caller at a fixed price, they stay locked until filled or cancelled
+    ListKeys {
+        amount: Uint128,
+        price_per_key: Uint128,
+    },
+    /// Seller only, cancel the listing and unlock its remaining keys
+    CancelListing { listing_id: u64 },
+    /// Anyone can call, buy amount of the listed keys at the listing price
+    /// The fee recipients take a royalty on the price, the buyer pays on top whatever the transfer royalty exceeds it by
+    /// Any excess payment is refunded
+    #[payable]
+    FillListing { listing_id: u64, amount: Uint128 },
+    /// Anyone can call, lock keys of the caller in escrow for an app installed on this account until the expiration
+    /// The keys still count toward the holding but cannot be sold or transferred
+    Lock {
+        amount: Uint128,
+        until: Expiration,
+        beneficiary_app: String,
+    },
+    /// Holder only, unlock the keys of an expired lock
+    Unlock { lock_id: u64 },
+    /// Beneficiary app only, unlock the keys back to the holder
+    ReleaseLock { lock_id: u64 },
+    /// Beneficiary app only, before the lock expires, take amount of the locked keys from the holder
+    /// They go to the recipient if given and are burned out of the supply otherwise
+    /// Slashing to a recipient pays the transfer royalty unless the recipient is exempt, any excess
+    /// payment is refunded
+    #[payable]
+    SlashLock {
+        lock_id: u64,
+        amount: Uint128,
+        recipient: Option<String>,
+    },
+    /// Anyone can call, give unlocked keys of the caller to the recipient
+    /// The transfer royalty must be paid unless the recipient is exempt, any excess payment is refunded
+    #[payable]
+    TransferKeys { recipient: String, amount: Uint128 },
+    /// Anyone can call during the opening auction, bid for amount of keys at the current auction price
+    /// Bids beyond the keys left are cut down and the excess payment is refunded
+    #[payable]
+    Bid { amount: Uint128 },
+    /// Owner only, before any key is traded, set the airdrop, trading waits until it is funded
+    SetAirdrop { airdrop: AirdropParams },
+    /// Owner only, pay the curve price of the airdropped keys into the reserve, minting them and opening trading
+    /// Any excess payment is refunded
+    #[payable]
+    FundAirdrop {},
+    /// Anyone can call once the airdrop is funded, claim the keys granted to the caller with a merkle proof
+    ClaimAirdrop { amount: Uint128, proof: Vec<String> },
+    /// Owner only, once the claims expired, burn the unclaimed keys and receive their curve price,
+    /// or their share of the reserve once the market is closed, and the rewards they accrued
+    ReclaimAirdrop {},
+    /// Anyone can call once the auction sold out or ended, open trading on the curve
+    SettleAuction {},
+    /// Bidder only, once the auction is settled, receive the won keys and the refund above the clearing price
+    ClaimAuction {},
+    /// Owner only, replace the fee recipients, their weights must add up to 100
+    UpdateFeeRecipients {
+        fee_recipients: Vec<UncheckedFeeRecipient>,
+    },
+    /// Owner only, pay out the accrued fees to the given address, or to the fee recipients if none is given
+    ClaimFees { to: Option<String> },
+    /// Owner only, spend up to budget of the accrued fees buying keys on the curve and burn them
+    /// The supply is unchanged, the price paid is shared by the holders as rewards and the rest of the budget stays accrued
+    BuybackAndBurn { budget: Uint128 },
+    /// Owner only, notify the contract of every key balance change with a `FriendTechHookMsg`
+    RegisterHook {
+        contract: String,
+        on_failure: HookFailure,
+    },
+    /// Owner only, stop notifying the contract
+    UnregisterHook { contract: String },
+    /// Owner only, replace the dynamic fee model or go back to the flat issuer fee
+    UpdateDynamicFee {
+        dynamic_fee: Option<DynamicFeeConfig>,
+    },
+    /// Owner only, replace the rate limits or lift them
+    UpdateRateLimits { rate_limits: Option<RateLimits> },
+    /// Owner only, replace the transfer royalty or remove it
+    UpdateTransferRoyalty { royalty: Option<TransferRoyalty> },
+    /// Owner only, add and remove recipients of gifts exempt from the transfer royalty
+    UpdateRoyaltyExemptions {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Owner only, replace the governance parameters or disable new proposals, open proposals keep theirs
+    UpdateGovernance {
+        governance: Option<GovernanceConfig>,
+    },
+    /// Holder only, propose messages for the account to run, votes weigh the keys held at the current height
+    Propose {
+        title: String,
+        description: String,
+        msgs: Vec<CosmosMsg>,
+    },
+    /// Holder at the start of the proposal only, vote once on an open proposal
+    Vote { proposal_id: u64, vote: VoteOption },
+    /// Owner only, let the proposal run messages outside the approval exemptions
+    ApproveProposal { proposal_id: u64 },
+    /// Owner only, add and remove contracts the proposals may call without the owner's approval
+    /// Calls sending funds, and calls to the account and its modules, always need it
+    UpdateApprovalExemptions {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Anyone can call once the proposal passed and, when it needs it, the owner approved it,
+    /// run its messages through the account
+    ExecuteProposal { proposal_id: u64 },
+    /// Anyone can call, move the issuer's key and fee share of the previous account owner to the current one
+    SyncOwner {},
+    /// Owner only, halt trading for good, holders can then redeem their keys
+    CloseMarket {},
+    /// Anyone can call once the market is closed, redeem every key of the caller for a pro rata share of the reserve
+    /// Staked and unbonding keys are redeemed as well, the issuer's last key included
+    Redeem {},
 }
 
+/// Message sent to the registered hooks, their execute message must accept it
 #[cosmwasm_schema::cw_serde]
-pub struct FriendTechAppMigrateMsg {}
+pub enum FriendTechHookMsg {
+    /// Keys held by the holder changed, the supply is the one after the action
+    KeyBalanceChanged {
+        holder: Addr,
+        old: Uint128,
+        new: Uint128,
+        supply: Uint128,
+    },
+}
+
+/// App migrate message
+/// The state is upgraded to the latest layout first, then the given parameters replace the current ones
+#[cosmwasm_schema::cw_serde]
+#[derive(Default)]
+pub struct FriendTechAppMigrateMsg {
+    /// Percentage of the issuer fee paid to the referrer of a trade
+    pub referral_fee_percentage: Option<u32>,
+    /// Percentage of the issuer fee shared by the stakers
+    pub staking_fee_percentage: Option<u32>,
+    /// How long unstaked keys stay locked before they can be sold
+    pub unbonding_period: Option<Duration>,
+    /// Percentage of the price of every listing fill paid to the issuer fee collector
+    pub listing_royalty_percentage: Option<u32>,
+    /// Accrue the fees in the contract until the owner claims them, instead of sending them on every trade
+    pub accrue_fees: Option<bool>,
+    /// Holder of the issuer's key when migrating from before its holder was recorded, defaults to the account owner
+    pub issuer_key_holder: Option<String>,
+}
 
 /// App query messages
 #[cosmwasm_schema::cw_serde]
@@ -40,10 +264,37 @@ pub struct FriendTechAppMigrateMsg {}
 pub enum FriendTechAppQueryMsg {
     #[returns(IssuerResponse)]
     Issuer {},
+    /// Cost for the trader receiving the keys, who may pay a round trip surcharge, or for a new trader if none is given
     #[returns(BuyKeyCostResponse)]
-    BuyKeyCost { amount: Uint128 },
+    BuyKeyCost {
+        amount: Uint128,
+        trader: Option<String>,
+    },
+    /// Cost for the trader, who may pay a round trip surcharge, or for a new trader if none is given
     #[returns(SellKeyCostResponse)]
-    SellKeyCost { amount: Uint128 },
+    SellKeyCost {
+        amount: Uint128,
+        trader: Option<String>,
+    },
+    #[returns(KeysForBudgetResponse)]
+    KeysForBudget {
+        budget: Uint128,
+        trader: Option<String>,
+    },
+    #[returns(DynamicFeeResponse)]
+    DynamicFee {},
+    #[returns(RateLimitsResponse)]
+    RateLimits {},
+    /// Marginal price of a key at every step between from_supply and to_supply, both included
+    #[returns(PriceCurveResponse)]
+    PriceCurve {
+        from_supply: Uint128,
+        to_supply: Uint128,
+        step: Uint128,
+    },
+    #[returns(MarketResponse)]
+    Market {},
+    /// Holders ordered by address, or by account id in accounts only mode, where start_after is an account id
     #[returns(HoldersResponse)]
     Holders {
         limit: Option<u32>,
@@ -51,13 +302,103 @@ pub enum FriendTechAppQueryMsg {
     },
     #[returns(HoldingResponse)]
     Holding { holder: String },
+    /// Value of the keys of the holder against what they paid for them
+    #[returns(PortfolioResponse)]
+    Portfolio { holder: String },
+    /// Whether the holder owns at least min keys, staked, unbonding and locked keys included
+    #[returns(HasMinimumKeysResponse)]
+    HasMinimumKeys { holder: String, min: Uint128 },
+    #[returns(ListingResponse)]
+    Listing { listing_id: u64 },
+    /// Open listings sorted by price, cheapest first
+    #[returns(ListingsResponse)]
+    Listings {
+        limit: Option<u32>,
+        start_after: Option<u64>,
+    },
+    /// Open listings of the seller sorted by price, cheapest first
+    #[returns(ListingsResponse)]
+    ListingsBySeller {
+        seller: String,
+        limit: Option<u32>,
+        start_after: Option<u64>,
+    },
+    #[returns(LockResponse)]
+    KeyLock { lock_id: u64 },
+    /// Locks of the holder sorted by id
+    #[returns(LocksResponse)]
+    LocksByHolder {
+        holder: String,
+        limit: Option<u32>,
+        start_after: Option<u64>,
+    },
+    #[returns(Option<AuctionResponse>)]
+    Auction {},
+    #[returns(BidResponse)]
+    AuctionBid { bidder: String },
+    #[returns(Option<AirdropResponse>)]
+    Airdrop {},
+    #[returns(AirdropClaimResponse)]
+    AirdropClaim { address: String },
+    /// Fees held by the contract until the owner claims them
+    #[returns(AccruedFeesResponse)]
+    AccruedFees {},
+    #[returns(HooksResponse)]
+    Hooks {},
+    #[returns(TransferRoyaltyResponse)]
+    TransferRoyalty {},
+    /// Royalty due on transferring amount of keys to the recipient, or to a recipient who is not exempt if none is given
+    #[returns(TransferRoyaltyCostResponse)]
+    TransferRoyaltyCost {
+        amount: Uint128,
+        recipient: Option<String>,
+    },
+    /// Recipients exempt from the transfer royalty sorted by address
+    #[returns(RoyaltyExemptionsResponse)]
+    RoyaltyExemptions {
+        limit: Option<u32>,
+        start_after: Option<String>,
+    },
+    #[returns(GovernanceResponse)]
+    Governance {},
+    #[returns(ProposalResponse)]
+    Proposal { proposal_id: u64 },
+    /// Proposals sorted by id, oldest first
+    #[returns(ProposalsResponse)]
+    Proposals {
+        limit: Option<u32>,
+        start_after: Option<u64>,
+    },
+    /// Contracts the proposals may call without the owner's approval sorted by address
+    #[returns(ApprovalExemptionsResponse)]
+    ApprovalExemptions {
+        limit: Option<u32>,
+        start_after: Option<String>,
+    },
+    /// Ballot cast by the voter on the proposal
+    #[returns(BallotResponse)]
+    Ballot { proposal_id: u64, voter: String },
+    #[returns(ReferrerResponse)]
+    Referrer { addr: String },
+    #[returns(PendingRewardsResponse)]
+    PendingRewards { holder: String },
+    #[returns(StakedResponse)]
+    Staked { holder: String },
+    #[returns(StakingInfoResponse)]
+    StakingInfo {},
 }
 
 #[cosmwasm_schema::cw_serde]
 pub struct IssuerResponse {
     pub username: String,
     pub fee_denom: String,
-    pub issuer_fee_collector: Addr,
+    pub fee_recipients: Vec<FeeRecipient>,
+    pub referral_fee_percentage: u32,
+    pub listing_royalty_percentage: u32,
+    pub accrue_fees: bool,
+    pub accounts_only: bool,
+    /// Holder of the issuer's key
+    pub issuer_key_holder: Addr,
     pub supply: Uint128,
 }
 
@@ -67,6 +408,8 @@ pub struct BuyKeyCostResponse {
     pub price: Uint128,
     /// Fee charged by the issuer
     pub issuer_fee: Uint128,
+    /// Percentage of the price charged as issuer fee
+    pub issuer_fee_percentage: u32,
     /// Total cost of the transaction
     pub total_cost: Uint128,
 }
@@ -77,16 +420,283 @@ pub struct SellKeyCostResponse {
     pub price: Uint128,
     /// Fee charged by the issuer
     pub issuer_fee: Uint128,
+    /// Percentage of the price charged as issuer fee
+    pub issuer_fee_percentage: u32,
+    /// Total cost of the transaction
+    pub total_cost: Uint128,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct KeysForBudgetResponse {
+    /// Most keys the budget can buy
+    pub amount: Uint128,
+    /// Price of buying amount of key
+    pub price: Uint128,
+    /// Fee charged by the issuer
+    pub issuer_fee: Uint128,
+    /// Percentage of the price charged as issuer fee
+    pub issuer_fee_percentage: u32,
     /// Total cost of the transaction
     pub total_cost: Uint128,
+    /// Part of the budget left after buying
+    pub leftover: Uint128,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct PricePoint {
+    pub supply: Uint128,
+    /// Price of buying the next key at this supply
+    pub price: Uint128,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct PriceCurveResponse {
+    pub points: Vec<PricePoint>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct MarketResponse {
+    pub supply: Uint128,
+    /// Price of buying the next key
+    pub spot_price: Uint128,
+    /// Spot price multiplied by the supply
+    pub market_cap: Uint128,
+    /// Funds held to pay out sellers
+    pub reserve: Uint128,
+    /// Keys bought back by the issuer and burned
+    pub burned: Uint128,
+    /// Price of keys bought and sold over the last 24 hours
+    pub volume_24h: Uint128,
+    pub holder_count: u64,
+    /// Whether the issuer closed the market, keys can then only be redeemed
+    pub closed: bool,
 }
 
 #[cosmwasm_schema::cw_serde]
 pub struct HoldersResponse {
+    /// Addresses of the holders, empty in accounts only mode
     pub holders: Vec<Addr>,
+    /// Account ids of the holders in accounts only mode, empty otherwise
+    pub accounts: Vec<AccountId>,
 }
 
 #[cosmwasm_schema::cw_serde]
 pub struct HoldingResponse {
     pub amount: Uint128,
 }
+
+#[cosmwasm_schema::cw_serde]
+pub struct PortfolioResponse {
+    /// Keys held, staked and unbonding keys included
+    pub holding: Uint128,
+    /// What selling every key returns after the issuer fee
+    pub liquidation_value: Uint128,
+    /// What the holder paid for the keys held, fees included
+    pub cost_basis: Uint128,
+    /// Cost basis per key
+    pub average_cost: Decimal,
+    /// Liquidation value minus the cost basis
+    pub unrealized_pnl: Int128,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct HasMinimumKeysResponse {
+    pub has_minimum_keys: bool,
+    /// Keys owned by the holder
+    pub amount: Uint128,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ListingResponse {
+    pub listing_id: u64,
+    pub seller: Addr,
+    /// Keys left to fill
+    pub amount: Uint128,
+    pub price_per_key: Uint128,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ListingsResponse {
+    pub listings: Vec<ListingResponse>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct LockResponse {
+    pub lock_id: u64,
+    pub holder: Addr,
+    /// Keys left in escrow
+    pub amount: Uint128,
+    pub until: Expiration,
+    pub beneficiary_app: String,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct LocksResponse {
+    pub locks: Vec<LockResponse>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct AirdropResponse {
+    pub merkle_root: String,
+    pub total_amount: Uint128,
+    pub claimed: Uint128,
+    /// Unclaimed keys burned by the owner after the claims expired
+    pub reclaimed: Uint128,
+    pub expires: Expiration,
+    /// Curve price of the airdropped keys, paid by the owner to fund the airdrop
+    pub funding_cost: Uint128,
+    pub funded: bool,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct AirdropClaimResponse {
+    /// Keys claimed by the address, zero until it claims
+    pub claimed: Uint128,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct AuctionResponse {
+    pub amount: Uint128,
+    pub sold: Uint128,
+    pub start_price: Uint128,
+    pub end_price: Uint128,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+    /// Clearing price once set, the decaying auction price until then
+    pub current_price: Uint128,
+    pub clearing_price: Option<Uint128>,
+    pub settled: bool,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct BidResponse {
+    pub amount: Uint128,
+    pub deposit: Uint128,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct HookResponse {
+    pub contract: Addr,
+    pub on_failure: HookFailure,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct HooksResponse {
+    pub hooks: Vec<HookResponse>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct AccruedFeesResponse {
+    pub amount: Uint128,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct DynamicFeeResponse {
+    pub dynamic_fee: Option<DynamicFeeConfig>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct TransferRoyaltyResponse {
+    pub royalty: Option<TransferRoyalty>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct TransferRoyaltyCostResponse {
+    pub royalty: Uint128,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct RoyaltyExemptionsResponse {
+    pub addresses: Vec<Addr>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ApprovalExemptionsResponse {
+    pub addresses: Vec<Addr>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct RateLimitsResponse {
+    pub rate_limits: Option<RateLimits>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct GovernanceResponse {
+    pub governance: Option<GovernanceConfig>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub enum ProposalStatus {
+    /// Voting period is not over
+    Open,
+    /// Quorum and threshold were reached, the proposal can be executed
+    Passed,
+    Rejected,
+    Executed,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ProposalResponse {
+    pub proposal_id: u64,
+    pub proposer: Addr,
+    pub title: String,
+    pub description: String,
+    pub msgs: Vec<CosmosMsg>,
+    pub start_height: u64,
+    pub expires: Expiration,
+    /// Key supply at the start height
+    pub total_power: Uint128,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub yes: Uint128,
+    pub no: Uint128,
+    pub abstain: Uint128,
+    pub status: ProposalStatus,
+    /// Whether the proposal runs messages that need the approval of the owner
+    pub needs_owner_approval: bool,
+    pub owner_approved: bool,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ProposalsResponse {
+    pub proposals: Vec<ProposalResponse>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct BallotResponse {
+    pub ballot: Option<Ballot>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ReferrerResponse {
+    /// Total price of keys traded with this referrer
+    pub volume: Uint128,
+    /// Total referral fee paid to this referrer
+    pub earnings: Uint128,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct PendingRewardsResponse {
+    /// Rewards the holder can claim
+    pub amount: Uint128,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct StakedResponse {
+    pub staked: Uint128,
+    /// Staked keys multiplied by the seconds they have been staked
+    pub weight: Uint128,
+    /// Unstaked keys that are still locked
+    pub unbonding: Vec<Unbonding>,
+    /// Staking fee the staker can claim
+    pub pending_rewards: Uint128,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct StakingInfoResponse {
+    pub total_staked: Uint128,
+    /// Sum of the weight of every stake
+    pub total_weight: Uint128,
+    pub staking_fee_percentage: u32,
+    pub unbonding_period: Duration,
+}
@@ -0,0 +1,82 @@
+use cosmwasm_std::{Addr, BlockInfo, Storage, Uint128};
+
+use crate::{
+    state::{BlockVolume, RateLimits, TradeWindow, BLOCK_VOLUME, RATE_LIMITS, TRADE_WINDOWS},
+    FriendTechAppError,
+};
+
+pub fn assert_rate_limits(rate_limits: &RateLimits) -> Result<(), FriendTechAppError> {
+    if rate_limits.window_blocks == 0
+        || rate_limits.max_trades == Some(0)
+        || rate_limits
+            .max_block_volume
+            .is_some_and(|volume| volume.is_zero())
+    {
+        return Err(FriendTechAppError::InvalidRateLimits {});
+    }
+    Ok(())
+}
+
+/// Count the trade of the trader against the rate limits, erroring with the height from which they
+/// may trade again once a limit is hit, trades no block can take are refused outright
+pub fn record_rate_limited_trade(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    trader: &Addr,
+    price: Uint128,
+) -> Result<(), FriendTechAppError> {
+    let Some(rate_limits) = RATE_LIMITS.may_load(storage)? else {
+        return Ok(());
+    };
+
+    if let Some(max_trades) = rate_limits.max_trades {
+        // Windows start at the first trade made after the previous one ended
+        let window = TRADE_WINDOWS
+            .may_load(storage, trader)?
+            .filter(|window| block.height < window.start_height + rate_limits.window_blocks)
+            .unwrap_or(TradeWindow {
+                start_height: block.height,
+                trades: 0,
+            });
+        if window.trades >= max_trades {
+            return Err(FriendTechAppError::TradeRateLimited {
+                retry_at_height: window.start_height + rate_limits.window_blocks,
+            });
+        }
+        TRADE_WINDOWS.save(
+            storage,
+            trader,
+            &TradeWindow {
+                trades: window.trades + 1,
+                ..window
+            },
+        )?;
+    }
+
+    if let Some(max_block_volume) = rate_limits.max_block_volume {
+        if price > max_block_volume {
+            return Err(FriendTechAppError::TradeExceedsBlockLimit {
+                price,
+                max_block_volume,
+            });
+        }
+        let volume = BLOCK_VOLUME
+            .may_load(storage)?
+            .filter(|volume| volume.height == block.height)
+            .map(|volume| volume.volume)
+            .unwrap_or_default();
+        if volume + price > max_block_volume {
+            return Err(FriendTechAppError::BlockVolumeLimited {
+                retry_at_height: block.height + 1,
+            });
+        }
+        BLOCK_VOLUME.save(
+            storage,
+            &BlockVolume {
+                height: block.height,
+                volume: volume + price,
+            },
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use crate::{
+    contract::{FriendTechApp, FriendTechAppResult},
+    events::{event_response, HookFailedEvent},
+};
+
+use cosmwasm_std::{DepsMut, Env, Reply, SubMsgResult};
+
+/// Only called for hooks whose failures are ignored, the action goes through
+pub fn hook_reply(
+    _deps: DepsMut,
+    _env: Env,
+    app: FriendTechApp,
+    reply: Reply,
+) -> FriendTechAppResult {
+    let error = match reply.result {
+        SubMsgResult::Err(error) => error,
+        SubMsgResult::Ok(_) => String::new(),
+    };
+    Ok(event_response(&app, HookFailedEvent { error }))
+}
@@ -1,5 +1,8 @@
+mod hook;
 mod instantiate;
 
+pub use hook::hook_reply;
 pub use instantiate::instantiate_reply;
 
 pub const INSTANTIATE_REPLY_ID: u64 = 1u64;
+pub const HOOK_REPLY_ID: u64 = 2u64;
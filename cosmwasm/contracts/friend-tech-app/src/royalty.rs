@@ -0,0 +1,40 @@
+use cosmwasm_std::{Addr, StdResult, Storage, Uint128};
+
+use crate::{
+    state::{TransferRoyalty, ROYALTY_EXEMPTIONS, SUPPLY, TRANSFER_ROYALTY},
+    utils::{assert_percentage, calculate_buy_price, multiply_percentage},
+    FriendTechAppError,
+};
+
+pub fn assert_transfer_royalty(royalty: &TransferRoyalty) -> Result<(), FriendTechAppError> {
+    match royalty {
+        TransferRoyalty::Fixed { .. } => Ok(()),
+        TransferRoyalty::SpotPercentage { percentage } => assert_percentage(*percentage),
+    }
+}
+
+/// Royalty due on moving amount of keys between holders outside the curve
+pub fn transfer_royalty(storage: &dyn Storage, amount: Uint128) -> StdResult<Uint128> {
+    let Some(royalty) = TRANSFER_ROYALTY.may_load(storage)? else {
+        return Ok(Uint128::zero());
+    };
+    Ok(match royalty {
+        TransferRoyalty::Fixed { amount_per_key } => amount_per_key * amount,
+        TransferRoyalty::SpotPercentage { percentage } => {
+            let spot_price = calculate_buy_price(SUPPLY.load(storage)?, Uint128::one())?;
+            multiply_percentage(spot_price * amount, percentage)
+        }
+    })
+}
+
+/// Royalty due on giving amount of keys to the recipient, exempt recipients pay none
+pub fn gift_royalty(
+    storage: &dyn Storage,
+    recipient: &Addr,
+    amount: Uint128,
+) -> StdResult<Uint128> {
+    if ROYALTY_EXEMPTIONS.has(storage, recipient) {
+        return Ok(Uint128::zero());
+    }
+    transfer_royalty(storage, amount)
+}
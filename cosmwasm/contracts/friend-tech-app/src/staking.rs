@@ -0,0 +1,207 @@
+use cosmwasm_std::{Addr, BlockInfo, Decimal256, StdResult, Storage, Uint128, Uint256};
+use cw_utils::Expiration;
+
+use crate::{
+    escrow::escrowed_keys,
+    listings::listed_keys,
+    state::{Stake, StakingState, Unbonding, ISSUER, STAKES, STAKING, UNBONDING},
+    utils::{may_load_holding, multiply_percentage},
+};
+
+// A stake weighs its amount multiplied by the seconds it has been staked, i.e. `amount * now - weighted_since`.
+// A fee distributed at time t gives every stake `fee * weight(t) / total_weight(t)`, which splits into
+// `amount * fee * t / total_weight - weighted_since * fee / total_weight`, so two accumulators are enough
+// to settle any stake without iterating over the stakers.
+
+fn now(block: &BlockInfo) -> Uint128 {
+    Uint128::from(block.time.seconds())
+}
+
+/// Sum of the weight of every stake
+pub fn total_weight(staking: &StakingState, block: &BlockInfo) -> Uint128 {
+    staking.total_staked * now(block) - staking.total_weighted_since
+}
+
+/// Weight of the stake, the staked keys multiplied by the seconds they have been staked
+pub fn stake_weight(stake: &Stake, block: &BlockInfo) -> Uint128 {
+    stake.amount * now(block) - stake.weighted_since
+}
+
+/// Stake of the holder including the rewards accrued since its last settlement
+pub fn query_stake(storage: &dyn Storage, holder: &Addr) -> StdResult<Stake> {
+    let staking = STAKING.load(storage)?;
+    let stake = STAKES.may_load(storage, holder)?.unwrap_or_default();
+    let accrued = (Decimal256::from_ratio(stake.amount, 1u8)
+        * (staking.reward_time_per_weight - stake.reward_time_per_weight_paid))
+        .saturating_sub(
+            Decimal256::from_ratio(stake.weighted_since, 1u8)
+                * (staking.reward_per_weight - stake.reward_per_weight_paid),
+        );
+    Ok(Stake {
+        reward_per_weight_paid: staking.reward_per_weight,
+        reward_time_per_weight_paid: staking.reward_time_per_weight,
+        pending: stake.pending + Uint128::try_from(accrued.to_uint_floor())?,
+        ..stake
+    })
+}
+
+fn save_stake(storage: &mut dyn Storage, holder: &Addr, stake: &Stake) -> StdResult<()> {
+    if stake.amount.is_zero() && stake.pending.is_zero() {
+        STAKES.remove(storage, holder);
+        Ok(())
+    } else {
+        STAKES.save(storage, holder, stake)
+    }
+}
+
+/// Stake keys of the holder, keys already staked keep their weight
+pub fn stake(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    holder: &Addr,
+    amount: Uint128,
+) -> StdResult<Stake> {
+    let mut staking = STAKING.load(storage)?;
+    let mut stake = query_stake(storage, holder)?;
+    let weighted_since = amount * now(block);
+
+    stake.amount += amount;
+    stake.weighted_since += weighted_since;
+    staking.total_staked += amount;
+    staking.total_weighted_since += weighted_since;
+
+    STAKING.save(storage, &staking)?;
+    save_stake(storage, holder, &stake)?;
+    Ok(stake)
+}
+
+/// Unstake keys of the holder, the remaining keys keep their average staking time
+pub fn unstake(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    holder: &Addr,
+    amount: Uint128,
+    release_at: Expiration,
+) -> StdResult<Stake> {
+    let mut staking = STAKING.load(storage)?;
+    let mut stake = query_stake(storage, holder)?;
+    let weighted_since = stake.weighted_since.multiply_ratio(amount, stake.amount);
+
+    stake.amount -= amount;
+    stake.weighted_since -= weighted_since;
+    staking.total_staked -= amount;
+    staking.total_weighted_since -= weighted_since;
+
+    STAKING.save(storage, &staking)?;
+    save_stake(storage, holder, &stake)?;
+
+    let mut unbonding = active_unbonding(storage, block, holder)?;
+    unbonding.push(Unbonding { amount, release_at });
+    UNBONDING.save(storage, holder, &unbonding)?;
+    Ok(stake)
+}
+
+/// Remove the stake and the unbonding keys of the holder at once, its settled rewards can still be claimed
+pub fn release_stake(storage: &mut dyn Storage, holder: &Addr) -> StdResult<()> {
+    let mut staking = STAKING.load(storage)?;
+    let stake = query_stake(storage, holder)?;
+
+    staking.total_staked -= stake.amount;
+    staking.total_weighted_since -= stake.weighted_since;
+
+    STAKING.save(storage, &staking)?;
+    save_stake(
+        storage,
+        holder,
+        &Stake {
+            amount: Uint128::zero(),
+            weighted_since: Uint128::zero(),
+            ..stake
+        },
+    )?;
+    UNBONDING.remove(storage, holder);
+    Ok(())
+}
+
+/// Settle the stake of the holder and take out its rewards
+pub fn claim_staking_rewards(storage: &mut dyn Storage, holder: &Addr) -> StdResult<Uint128> {
+    let stake = query_stake(storage, holder)?;
+    save_stake(
+        storage,
+        holder,
+        &Stake {
+            pending: Uint128::zero(),
+            ..stake.clone()
+        },
+    )?;
+    Ok(stake.pending)
+}
+
+/// Share the staking fee of a trade among the stakers, nothing is taken while no stake has any weight
+pub fn distribute_staking_fee(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    issuer_fee: Uint128,
+    staking_fee_percentage: u32,
+) -> StdResult<Uint128> {
+    let mut staking = STAKING.load(storage)?;
+    let total_weight = total_weight(&staking, block);
+    let staking_fee = multiply_percentage(issuer_fee, staking_fee_percentage);
+    if total_weight.is_zero() || staking_fee.is_zero() {
+        return Ok(Uint128::zero());
+    }
+
+    staking.reward_per_weight += Decimal256::from_ratio(staking_fee, total_weight);
+    staking.reward_time_per_weight += Decimal256::from_ratio(
+        Uint256::from(staking_fee) * Uint256::from(now(block)),
+        total_weight,
+    );
+    STAKING.save(storage, &staking)?;
+    Ok(staking_fee)
+}
+
+/// Unbonding entries of the holder that are not released yet
+pub fn active_unbonding(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    holder: &Addr,
+) -> StdResult<Vec<Unbonding>> {
+    Ok(UNBONDING
+        .may_load(storage, holder)?
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|unbonding| !unbonding.release_at.is_expired(block))
+        .collect())
+}
+
+/// Keys of the holder that cannot be sold or transferred, either staked, still unbonding, listed, escrowed or the issuer's key
+pub fn locked_keys(storage: &dyn Storage, block: &BlockInfo, holder: &Addr) -> StdResult<Uint128> {
+    let issuer_key = if ISSUER.load(storage)? == holder {
+        Uint128::one()
+    } else {
+        Uint128::zero()
+    };
+    let staked = STAKES
+        .may_load(storage, holder)?
+        .map(|stake| stake.amount)
+        .unwrap_or_default();
+    let unbonding: Uint128 = active_unbonding(storage, block, holder)?
+        .iter()
+        .map(|unbonding| unbonding.amount)
+        .sum();
+    Ok(issuer_key
+        + staked
+        + unbonding
+        + listed_keys(storage, holder)?
+        + escrowed_keys(storage, holder)?)
+}
+
+/// Keys of the holder that can be sold, transferred, staked or listed
+pub fn unlocked_keys(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    holder: &Addr,
+) -> StdResult<Uint128> {
+    let holding = may_load_holding(storage, holder)?.unwrap_or_default();
+    Ok(holding.saturating_sub(locked_keys(storage, block, holder)?))
+}
@@ -1,13 +1,407 @@
-use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::{Item, Map};
+use abstract_app::objects::AccountId;
+use cosmwasm_std::{Addr, CosmosMsg, Decimal, Decimal256, Empty, Timestamp, Uint128};
+use cw_storage_plus::{
+    Index, IndexList, IndexedMap, Item, Map, MultiIndex, SnapshotItem, SnapshotMap, Strategy,
+};
+use cw_utils::{Duration, Expiration};
 
 #[cosmwasm_schema::cw_serde]
 pub struct Config {
     pub username: String,
     pub fee_denom: String,
-    pub issuer_fee_collector: Addr,
+    /// Recipients of the issuer fee, royalties and auction surplus, their weights add up to 100
+    pub fee_recipients: Vec<FeeRecipient>,
+    /// Percentage of the issuer fee paid to the referrer of a trade
+    pub referral_fee_percentage: u32,
+    /// Percentage of the issuer fee shared by the stakers
+    pub staking_fee_percentage: u32,
+    /// How long unstaked keys stay locked before they can be sold
+    pub unbonding_period: Duration,
+    /// Percentage of the price of every listing fill paid to the issuer fee collector
+    pub listing_royalty_percentage: u32,
+    /// Whether fees accrue in the contract until the owner claims them, instead of being sent on every trade
+    pub accrue_fees: bool,
+    /// Whether only the proxies of Abstract accounts can buy, sell and hold keys
+    /// Holdings are then keyed by the account id of the proxy, resolved through ACCOUNT_IDS
+    pub accounts_only: bool,
 }
 
+#[cosmwasm_schema::cw_serde]
+pub struct FeeRecipient {
+    pub address: Addr,
+    /// Percentage of the fees paid to the recipient
+    pub weight: u32,
+}
+
+#[cosmwasm_schema::cw_serde]
+#[derive(Default)]
+pub struct ReferrerStats {
+    /// Total price of keys traded with this referrer
+    pub volume: Uint128,
+    /// Total referral fee paid to this referrer
+    pub earnings: Uint128,
+}
+
+#[cosmwasm_schema::cw_serde]
+#[derive(Default)]
+pub struct HolderRewards {
+    /// Value of the reward per key accumulator when the holder was last settled
+    pub reward_per_key_paid: Decimal256,
+    /// Rewards settled but not yet claimed
+    pub pending: Uint128,
+}
+
+#[cosmwasm_schema::cw_serde]
+#[derive(Default)]
+pub struct StakingState {
+    pub total_staked: Uint128,
+    /// Sum of every staked key multiplied by the time in seconds it was staked at
+    pub total_weighted_since: Uint128,
+    /// Accumulated staking fee per unit of stake weight
+    pub reward_per_weight: Decimal256,
+    /// Accumulated staking fee per unit of stake weight, multiplied by the distribution time in seconds
+    pub reward_time_per_weight: Decimal256,
+}
+
+#[cosmwasm_schema::cw_serde]
+#[derive(Default)]
+pub struct Stake {
+    pub amount: Uint128,
+    /// Staked keys multiplied by the time in seconds they were staked at
+    pub weighted_since: Uint128,
+    /// Value of the reward per weight accumulator when the stake was last settled
+    pub reward_per_weight_paid: Decimal256,
+    /// Value of the reward time per weight accumulator when the stake was last settled
+    pub reward_time_per_weight_paid: Decimal256,
+    /// Staking rewards settled but not yet claimed
+    pub pending: Uint128,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct Listing {
+    pub seller: Addr,
+    /// Keys left to fill, they stay locked in the seller's holding until filled or cancelled
+    pub amount: Uint128,
+    pub price_per_key: Uint128,
+}
+
+pub struct ListingIndexes<'a> {
+    pub price: MultiIndex<'a, u128, Listing, u64>,
+    pub seller: MultiIndex<'a, (Addr, u128), Listing, u64>,
+}
+
+impl<'a> IndexList<Listing> for ListingIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Listing>> + '_> {
+        let v: Vec<&dyn Index<Listing>> = vec![&self.price, &self.seller];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Key is the listing id, listings are indexed by price and by seller then price
+pub fn listings<'a>() -> IndexedMap<'a, u64, Listing, ListingIndexes<'a>> {
+    let indexes = ListingIndexes {
+        price: MultiIndex::new(
+            |_, listing| listing.price_per_key.u128(),
+            "LISTINGS",
+            "LISTINGS__PRICE",
+        ),
+        seller: MultiIndex::new(
+            |_, listing| (listing.seller.clone(), listing.price_per_key.u128()),
+            "LISTINGS",
+            "LISTINGS__SELLER",
+        ),
+    };
+    IndexedMap::new("LISTINGS", indexes)
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct Auction {
+    /// Keys offered
+    pub amount: Uint128,
+    pub start_price: Uint128,
+    pub end_price: Uint128,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+    /// Keys bid so far
+    pub sold: Uint128,
+    /// Price paid by every bidder, set once the keys are sold out or the auction is settled
+    pub clearing_price: Option<Uint128>,
+    /// Value of the reward per key accumulator at settlement, set once the auction is settled
+    pub settled_reward_per_key: Option<Decimal256>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct Airdrop {
+    /// Hex encoded root of the merkle tree of the claims
+    pub merkle_root: String,
+    /// Keys granted by the tree
+    pub total_amount: Uint128,
+    /// Keys claimed so far
+    pub claimed: Uint128,
+    /// Unclaimed keys burned once the claims expired
+    pub reclaimed: Uint128,
+    /// End of the claims
+    pub expires: Expiration,
+    /// Value of the reward per key accumulator when the keys were minted, set once funded
+    pub funded_reward_per_key: Option<Decimal256>,
+}
+
+#[cosmwasm_schema::cw_serde]
+#[derive(Default)]
+pub struct Bid {
+    /// Keys won by the bidder
+    pub amount: Uint128,
+    /// Funds paid by the bidder, the excess over the clearing price is refunded on claim
+    pub deposit: Uint128,
+}
+
+/// What happens to an action when one of its hooks fails
+#[cosmwasm_schema::cw_serde]
+pub enum HookFailure {
+    /// The whole action reverts
+    Revert,
+    /// The action goes through and the error is reported in a `hook_failed` event
+    Ignore,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct Unbonding {
+    pub amount: Uint128,
+    pub release_at: Expiration,
+}
+
+/// Keys of a holder committed to another app of the account until they expire
+#[cosmwasm_schema::cw_serde]
+pub struct KeyLock {
+    pub holder: Addr,
+    /// Keys left in escrow, they count toward the holding but cannot be sold or transferred
+    pub amount: Uint128,
+    /// The holder can unlock the keys once expired, the beneficiary can no longer slash them
+    pub until: Expiration,
+    /// Module id of the app that can release or slash the keys
+    pub beneficiary_app: String,
+    /// Address of the beneficiary app when the keys were locked
+    pub beneficiary: Addr,
+}
+
+pub struct KeyLockIndexes<'a> {
+    pub holder: MultiIndex<'a, Addr, KeyLock, u64>,
+}
+
+impl<'a> IndexList<KeyLock> for KeyLockIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<KeyLock>> + '_> {
+        let v: Vec<&dyn Index<KeyLock>> = vec![&self.holder];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Key is the lock id
+pub fn locks<'a>() -> IndexedMap<'a, u64, KeyLock, KeyLockIndexes<'a>> {
+    let indexes = KeyLockIndexes {
+        holder: MultiIndex::new(|_, lock| lock.holder.clone(), "LOCKS", "LOCKS__HOLDER"),
+    };
+    IndexedMap::new("LOCKS", indexes)
+}
+
+/// Royalty paid to the fee recipients when keys move between holders outside the curve
+#[cosmwasm_schema::cw_serde]
+pub enum TransferRoyalty {
+    Fixed {
+        amount_per_key: Uint128,
+    },
+    /// Percentage of the spot price of every key moved
+    SpotPercentage {
+        percentage: u32,
+    },
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct DynamicFeeConfig {
+    /// Price of keys traded over the last 24 hours adding one point to the issuer fee percentage
+    pub volume_step: Uint128,
+    /// Points added to the issuer fee percentage by every round trip of the holder, a trade reversing
+    /// one made within the window, each decaying linearly to zero over the window from its first trade
+    pub round_trip_fee_percentage: u32,
+    /// Round trip window in seconds
+    pub round_trip_window: u64,
+    /// Cap on the issuer fee percentage, not below the base fee
+    pub max_fee_percentage: u32,
+}
+
+/// Limits on the trades on the curve, to keep bots from flooding a new issuer
+#[cosmwasm_schema::cw_serde]
+pub struct RateLimits {
+    /// Trades an address can make within a window, unlimited when empty
+    pub max_trades: Option<u32>,
+    /// Length of the trade window in blocks, it starts at the first trade of the address
+    pub window_blocks: u64,
+    /// Price of keys that can be bought and sold within a block, unlimited when empty
+    /// A single trade above it is refused, it has to be split over several blocks
+    pub max_block_volume: Option<Uint128>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct TradeWindow {
+    pub start_height: u64,
+    /// Trades made since the start of the window
+    pub trades: u32,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct BlockVolume {
+    pub height: u64,
+    /// Price of keys traded at that height
+    pub volume: Uint128,
+}
+
+#[cosmwasm_schema::cw_serde]
+#[derive(Default)]
+pub struct TraderActivity {
+    pub last_buy: Option<Timestamp>,
+    pub last_sell: Option<Timestamp>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct GovernanceConfig {
+    /// Share of the key supply that must vote for a proposal to be valid
+    pub quorum: Decimal,
+    /// Share of the yes and no votes that must be yes for a proposal to pass, abstentions are left out
+    pub threshold: Decimal,
+    pub voting_period: Duration,
+}
+
+#[cosmwasm_schema::cw_serde]
+#[derive(Copy)]
+pub enum VoteOption {
+    Yes,
+    No,
+    Abstain,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct Proposal {
+    pub proposer: Addr,
+    pub title: String,
+    pub description: String,
+    /// Messages run by the account once the proposal passed
+    pub msgs: Vec<CosmosMsg>,
+    /// Votes weigh the keys held at the start of this height
+    pub start_height: u64,
+    pub expires: Expiration,
+    /// Key supply at the start height
+    pub total_power: Uint128,
+    /// Governance parameters when the proposal was created, later updates do not apply to it
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub yes: Uint128,
+    pub no: Uint128,
+    pub abstain: Uint128,
+    pub executed: bool,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct Ballot {
+    pub vote: VoteOption,
+    pub weight: Uint128,
+}
+
+/// Version of the storage layout written by this code, bump it when adding a migration step
+pub const CURRENT_STATE_VERSION: u32 = 12;
+
+/// Version of the storage layout, missing on installs made before versioning (0.0.1)
+pub const STATE_VERSION: Item<u32> = Item::new("STATE_VERSION");
 pub const CONFIG: Item<Config> = Item::new("CONFIG");
-pub const SUPPLY: Item<Uint128> = Item::new("SUPPLY");
-pub const HOLDERS: Map<&Addr, Uint128> = Map::new("HOLDERS");
+/// Snapshotted like HOLDERS to weigh the votes on proposals
+pub const SUPPLY: SnapshotItem<Uint128> = SnapshotItem::new(
+    "SUPPLY",
+    "SUPPLY__checkpoints",
+    "SUPPLY__changelog",
+    Strategy::EveryBlock,
+);
+/// Snapshotted every block, votes on a proposal weigh the keys held when it was created
+pub const HOLDERS: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "HOLDERS",
+    "HOLDERS__checkpoints",
+    "HOLDERS__changelog",
+    Strategy::EveryBlock,
+);
+/// Holder of the issuer's key, which stays locked, the account owner as of the last owner sync or the account proxy
+pub const ISSUER: Item<Addr> = Item::new("ISSUER");
+/// Key is the proxy address of a holder, value is its Abstract account id, only recorded in accounts only mode
+pub const ACCOUNT_IDS: Map<&Addr, AccountId> = Map::new("ACCOUNT_IDS");
+/// Takes the place of HOLDERS in accounts only mode, keyed by the account id of the holder
+pub const ACCOUNT_HOLDERS: SnapshotMap<AccountId, Uint128> = SnapshotMap::new(
+    "ACCOUNT_HOLDERS",
+    "ACCOUNT_HOLDERS__checkpoints",
+    "ACCOUNT_HOLDERS__changelog",
+    Strategy::EveryBlock,
+);
+/// Number of entries in HOLDERS, or in ACCOUNT_HOLDERS in accounts only mode
+pub const HOLDER_COUNT: Item<u64> = Item::new("HOLDER_COUNT");
+/// Set once the issuer closes the market, trading is halted and keys can only be redeemed
+pub const MARKET_CLOSED: Item<bool> = Item::new("MARKET_CLOSED");
+/// Funds paid into the curve by buyers, it pays out sellers
+pub const RESERVE: Item<Uint128> = Item::new("RESERVE");
+/// Fees held by the contract until the owner claims them
+pub const ACCRUED_FEES: Item<Uint128> = Item::new("ACCRUED_FEES");
+/// Keys bought back with the accrued fees and burned
+pub const BURNED: Item<Uint128> = Item::new("BURNED");
+/// Key is the hour since epoch, value is the price of keys bought and sold during that hour
+pub const HOURLY_VOLUME: Map<u64, Uint128> = Map::new("HOURLY_VOLUME");
+/// Key is the holder address, value is what they paid for the keys they hold, fees included
+/// Gifts, airdrops and keys held before it was tracked count at no cost
+pub const COST_BASIS: Map<&Addr, Uint128> = Map::new("COST_BASIS");
+/// Royalty on transfers and listing fills, they are free of it while missing
+pub const TRANSFER_ROYALTY: Item<TransferRoyalty> = Item::new("TRANSFER_ROYALTY");
+/// Recipients of gifts exempt from the transfer royalty
+pub const ROYALTY_EXEMPTIONS: Map<&Addr, Empty> = Map::new("ROYALTY_EXEMPTIONS");
+/// Fee model raising the issuer fee with trading activity, the issuer fee is flat while missing
+pub const DYNAMIC_FEE: Item<DynamicFeeConfig> = Item::new("DYNAMIC_FEE");
+/// Key is the holder address, value is when keys last came into and left their balance,
+/// cleared once reversed by a round trip
+pub const TRADER_ACTIVITY: Map<&Addr, TraderActivity> = Map::new("TRADER_ACTIVITY");
+/// Key is the holder address, value is when their round trips still within the window started, oldest first
+pub const ROUND_TRIPS: Map<&Addr, Vec<Timestamp>> = Map::new("ROUND_TRIPS");
+/// Limits on the trades on the curve, trading is unlimited while missing
+pub const RATE_LIMITS: Item<RateLimits> = Item::new("RATE_LIMITS");
+/// Key is the trader address, value is their current trade window
+pub const TRADE_WINDOWS: Map<&Addr, TradeWindow> = Map::new("TRADE_WINDOWS");
+/// Volume of the last block with a trade
+pub const BLOCK_VOLUME: Item<BlockVolume> = Item::new("BLOCK_VOLUME");
+/// Key is the referrer address, value is the referrer stats
+pub const REFERRERS: Map<&Addr, ReferrerStats> = Map::new("REFERRERS");
+/// Accumulated rewards distributed to every key since the issuer's market opened
+/// 256 bits wide like the staking accumulators, it only ever grows
+pub const REWARD_PER_KEY: Item<Decimal256> = Item::new("REWARD_PER_KEY");
+/// Key is the holder address, value is the holder rewards
+pub const HOLDER_REWARDS: Map<&Addr, HolderRewards> = Map::new("HOLDER_REWARDS");
+pub const STAKING: Item<StakingState> = Item::new("STAKING");
+/// Key is the staker address, value is the stake
+pub const STAKES: Map<&Addr, Stake> = Map::new("STAKES");
+/// Key is the holder address, value is the keys being unbonded
+pub const UNBONDING: Map<&Addr, Vec<Unbonding>> = Map::new("UNBONDING");
+/// Id of the next listing
+pub const NEXT_LISTING_ID: Item<u64> = Item::new("NEXT_LISTING_ID");
+/// Id of the next lock
+pub const NEXT_LOCK_ID: Item<u64> = Item::new("NEXT_LOCK_ID");
+/// Opening Dutch auction, trading on the curve starts once it is settled
+pub const AUCTION: Item<Auction> = Item::new("AUCTION");
+/// Key is the bidder address, value is the bid
+pub const BIDS: Map<&Addr, Bid> = Map::new("BIDS");
+/// Merkle airdrop of keys, trading waits until it is funded
+pub const AIRDROP: Item<Airdrop> = Item::new("AIRDROP");
+/// Key is the claimer address, value is the keys claimed
+pub const AIRDROP_CLAIMS: Map<&Addr, Uint128> = Map::new("AIRDROP_CLAIMS");
+/// Key is the hook contract address, value is how its failures are handled
+pub const HOOKS: Map<&Addr, HookFailure> = Map::new("HOOKS");
+/// Governance of the holders, proposals cannot be created while missing
+pub const GOVERNANCE: Item<GovernanceConfig> = Item::new("GOVERNANCE");
+/// Id of the next proposal
+pub const NEXT_PROPOSAL_ID: Item<u64> = Item::new("NEXT_PROPOSAL_ID");
+pub const PROPOSALS: Map<u64, Proposal> = Map::new("PROPOSALS");
+/// Contracts the holders may call without the owner's approval, see needs_owner_approval
+pub const APPROVAL_EXEMPTIONS: Map<&Addr, Empty> = Map::new("APPROVAL_EXEMPTIONS");
+/// Proposals the owner approved, see needs_owner_approval
+pub const APPROVED_PROPOSALS: Map<u64, Empty> = Map::new("APPROVED_PROPOSALS");
+/// Key is the proposal id and the voter address, value is the ballot cast
+pub const BALLOTS: Map<(u64, &Addr), Ballot> = Map::new("BALLOTS");
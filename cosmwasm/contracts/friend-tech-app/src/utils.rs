@@ -1,46 +1,213 @@
-use cosmwasm_std::{Addr, Deps, Uint128};
+use abstract_app::sdk::{features::AccountIdentification, AccountVerification};
+use cosmwasm_std::{
+    coins, Addr, Api, BankMsg, BlockInfo, Decimal256, Deps, DepsMut, Order, StdError, StdResult,
+    Storage, Uint128,
+};
+use cw_storage_plus::Bound;
 
-use crate::{contract::FriendTechApp, FriendTechAppError};
+use crate::{
+    contract::FriendTechApp,
+    msg::UncheckedFeeRecipient,
+    state::{
+        Config, FeeRecipient, HolderRewards, ACCOUNT_HOLDERS, ACCOUNT_IDS, ACCRUED_FEES, AIRDROP,
+        AUCTION, COST_BASIS, HOLDERS, HOLDER_COUNT, HOLDER_REWARDS, HOURLY_VOLUME, MARKET_CLOSED,
+        REWARD_PER_KEY, SUPPLY,
+    },
+    FriendTechAppError,
+};
 
-fn calculate_price(supply: Uint128, amount: Uint128) -> Uint128 {
-    let sum1 = if supply.is_zero() {
-        Uint128::zero()
-    } else {
-        ((supply - Uint128::one())
-            * supply
-            * (Uint128::from(2u64) * (supply - Uint128::one()) + Uint128::one()))
-            / Uint128::from(6_u64)
-    };
+const SECONDS_PER_HOUR: u64 = 60 * 60;
+/// Number of hourly buckets making up the recent volume
+const VOLUME_WINDOW_HOURS: u64 = 24;
 
-    let sum2 = if supply.is_zero() && amount == Uint128::one() {
-        Uint128::zero()
-    } else {
-        ((supply - Uint128::one() + amount)
-            * (supply + amount)
-            * ((supply - Uint128::one() + amount) * Uint128::from(2_u64) + Uint128::one()))
-            / Uint128::from(6_u64)
-    };
+/// Sum of the squares of 0 to n - 1
+fn sum_of_squares(n: Uint128) -> StdResult<Uint128> {
+    if n.is_zero() {
+        return Ok(Uint128::zero());
+    }
+    let last = n - Uint128::one();
+    Ok(last.checked_mul(n)?.checked_mul(
+        last.checked_mul(Uint128::from(2_u64))?
+            .checked_add(Uint128::one())?,
+    )? / Uint128::from(6_u64))
+}
 
-    let summation = sum2 - sum1;
-    (summation * Uint128::from(1_000_000_u64)) / Uint128::from(1_600_u64)
+/// Price of the keys from supply to supply + amount, errors instead of overflowing on huge amounts
+fn calculate_price(supply: Uint128, amount: Uint128) -> StdResult<Uint128> {
+    let summation = sum_of_squares(supply.checked_add(amount)?)? - sum_of_squares(supply)?;
+    Ok(summation.checked_mul(Uint128::from(1_000_000_u64))? / Uint128::from(1_600_u64))
 }
 
-pub fn calculate_buy_price(supply_before_buy: Uint128, buy_amount: Uint128) -> Uint128 {
+pub fn calculate_buy_price(supply_before_buy: Uint128, buy_amount: Uint128) -> StdResult<Uint128> {
     calculate_price(supply_before_buy, buy_amount)
 }
 
-pub fn calculate_sell_price(supply_before_sell: Uint128, sell_amount: Uint128) -> Uint128 {
+pub fn calculate_sell_price(
+    supply_before_sell: Uint128,
+    sell_amount: Uint128,
+) -> StdResult<Uint128> {
     // We need this to make sure price is the same across buy and sell
     // e.g. old supply is 5, now buy 10 memberships, new supply is 15
     // Now sell 10 memberships, new supply is 5, price to buy 10 memberships should be the same as price to sell 10 memberships
     // Because before supply and after supply is the same
-    calculate_price(supply_before_sell - sell_amount, sell_amount)
+    calculate_price(supply_before_sell.checked_sub(sell_amount)?, sell_amount)
 }
 
 pub fn multiply_percentage(price: Uint128, percentage: u32) -> Uint128 {
     (price * Uint128::from(percentage)) / Uint128::from(100_u64)
 }
 
+pub fn assert_percentage(percentage: u32) -> Result<(), FriendTechAppError> {
+    if percentage > 100 {
+        return Err(FriendTechAppError::InvalidPercentage { percentage });
+    }
+    Ok(())
+}
+
+/// The referral and staking fees are both carved out of the issuer fee
+pub fn assert_fee_shares(
+    referral_fee_percentage: u32,
+    staking_fee_percentage: u32,
+) -> Result<(), FriendTechAppError> {
+    assert_percentage(referral_fee_percentage + staking_fee_percentage)
+}
+
+/// Error while the opening auction is not settled or once the issuer closed the market
+/// Trading and staking are halted in both cases
+pub fn assert_market_open(storage: &dyn Storage) -> Result<(), FriendTechAppError> {
+    if let Some(auction) = AUCTION.may_load(storage)? {
+        if auction.settled_reward_per_key.is_none() {
+            return Err(FriendTechAppError::AuctionInProgress {});
+        }
+    }
+    if let Some(airdrop) = AIRDROP.may_load(storage)? {
+        if airdrop.funded_reward_per_key.is_none() {
+            return Err(FriendTechAppError::AirdropNotFunded {});
+        }
+    }
+    if MARKET_CLOSED.load(storage)? {
+        return Err(FriendTechAppError::MarketClosed {});
+    }
+    Ok(())
+}
+
+/// Only the issuer's key exists, so an airdrop can still be set and funded
+pub fn assert_trading_not_started(storage: &dyn Storage) -> Result<(), FriendTechAppError> {
+    if AUCTION.exists(storage) {
+        return Err(FriendTechAppError::AirdropWithAuction {});
+    }
+    if MARKET_CLOSED.load(storage)? {
+        return Err(FriendTechAppError::MarketClosed {});
+    }
+    if SUPPLY.load(storage)? != Uint128::one() {
+        return Err(FriendTechAppError::TradingStarted {});
+    }
+    Ok(())
+}
+
+/// Validate the fee recipient addresses and check that their weights add up to 100
+pub fn validate_fee_recipients(
+    api: &dyn Api,
+    fee_recipients: Vec<UncheckedFeeRecipient>,
+) -> Result<Vec<FeeRecipient>, FriendTechAppError> {
+    // Summed wider than the weights so that no combination of them wraps around to 100
+    let total: Uint128 = fee_recipients
+        .iter()
+        .map(|recipient| Uint128::from(recipient.weight))
+        .sum();
+    if total != Uint128::new(100) {
+        return Err(FriendTechAppError::InvalidFeeRecipientWeights { total });
+    }
+    fee_recipients
+        .into_iter()
+        .map(|recipient| {
+            Ok(FeeRecipient {
+                address: api.addr_validate(&recipient.address)?,
+                weight: recipient.weight,
+            })
+        })
+        .collect()
+}
+
+/// Share of the amount owed to every fee recipient, the rounding dust goes to the first one
+pub fn split_fee(amount: Uint128, fee_recipients: &[FeeRecipient]) -> Vec<(Addr, Uint128)> {
+    let mut shares: Vec<(Addr, Uint128)> = fee_recipients
+        .iter()
+        .map(|recipient| {
+            (
+                recipient.address.clone(),
+                multiply_percentage(amount, recipient.weight),
+            )
+        })
+        .collect();
+    let distributed: Uint128 = shares.iter().map(|(_, share)| share).sum();
+    if let Some((_, share)) = shares.first_mut() {
+        *share += amount - distributed;
+    }
+    shares
+}
+
+/// Bank messages paying the amount out to the fee recipients, empty transfers are skipped
+pub fn fee_messages(amount: Uint128, config: &Config) -> Vec<BankMsg> {
+    split_fee(amount, &config.fee_recipients)
+        .into_iter()
+        .filter(|(_, share)| !share.is_zero())
+        .map(|(address, share)| BankMsg::Send {
+            to_address: address.to_string(),
+            amount: coins(share.u128(), &config.fee_denom),
+        })
+        .collect()
+}
+
+/// Pay the fees out to the fee recipients, or hold them in the contract in accrual mode
+pub fn pay_fees(
+    storage: &mut dyn Storage,
+    amount: Uint128,
+    config: &Config,
+) -> StdResult<Vec<BankMsg>> {
+    if !config.accrue_fees {
+        return Ok(fee_messages(amount, config));
+    }
+    ACCRUED_FEES.update(storage, |accrued| -> StdResult<_> { Ok(accrued + amount) })?;
+    Ok(vec![])
+}
+
+/// Holder of the issuer's key, the account proxy in accounts only mode and the account owner otherwise
+pub fn get_issuer_holder_addr(
+    deps: Deps,
+    app: &FriendTechApp,
+    config: &Config,
+    account_owner_addr: &Addr,
+) -> Result<Addr, FriendTechAppError> {
+    if config.accounts_only {
+        return Ok(app.proxy_address(deps)?);
+    }
+    Ok(account_owner_addr.clone())
+}
+
+/// In accounts only mode, check that the holder is the proxy of an Abstract account and record its account id
+pub fn assert_account_holder(
+    deps: DepsMut,
+    app: &FriendTechApp,
+    config: &Config,
+    holder: &Addr,
+) -> Result<(), FriendTechAppError> {
+    if !config.accounts_only || ACCOUNT_IDS.has(deps.storage, holder) {
+        return Ok(());
+    }
+    let account_id = {
+        let registry = app.account_registry(deps.as_ref())?;
+        registry
+            .assert_proxy(holder)
+            .and_then(|_| registry.account_id(holder))
+            .map_err(|_| FriendTechAppError::NotAbstractAccount {
+                address: holder.to_string(),
+            })?
+    };
+    ACCOUNT_IDS.save(deps.storage, holder, &account_id)?;
+    Ok(())
+}
+
 pub fn get_account_owner_addr(deps: Deps, app: &FriendTechApp) -> Result<Addr, FriendTechAppError> {
     let issuer = app.admin.query_account_owner(deps)?.admin;
     match issuer {
@@ -48,3 +215,179 @@ pub fn get_account_owner_addr(deps: Deps, app: &FriendTechApp) -> Result<Addr, F
         None => Err(FriendTechAppError::AccountOwnerMustBeSetToIssueKey {}),
     }
 }
+
+/// Distribute amount to every key of the supply, returning the new reward per key
+pub fn distribute_rewards(
+    storage: &mut dyn Storage,
+    amount: Uint128,
+    supply: Uint128,
+) -> Result<Decimal256, FriendTechAppError> {
+    let reward_per_key = Decimal256::checked_from_ratio(amount, supply)
+        .ok()
+        .and_then(|added| REWARD_PER_KEY.load(storage).ok()?.checked_add(added).ok())
+        .ok_or(FriendTechAppError::RewardPerKeyOverflow {})?;
+    REWARD_PER_KEY.save(storage, &reward_per_key)?;
+    Ok(reward_per_key)
+}
+
+/// Rewards accrued on amount keys while the reward per key grew from paid to its current value
+pub fn accrued_rewards(
+    storage: &dyn Storage,
+    amount: Uint128,
+    paid: Decimal256,
+) -> StdResult<Uint128> {
+    let accrued = Decimal256::from_ratio(amount, 1u8)
+        .checked_mul(REWARD_PER_KEY.load(storage)? - paid)?
+        .to_uint_floor();
+    Ok(Uint128::try_from(accrued)?)
+}
+
+/// Keys held by the holder, under its account id in accounts only mode, where every holder has one recorded
+pub fn may_load_holding(storage: &dyn Storage, holder: &Addr) -> StdResult<Option<Uint128>> {
+    match ACCOUNT_IDS.may_load(storage, holder)? {
+        Some(account_id) => ACCOUNT_HOLDERS.may_load(storage, account_id),
+        None => HOLDERS.may_load(storage, holder),
+    }
+}
+
+/// Keys held by the holder, who must hold some
+pub fn load_holding(storage: &dyn Storage, holder: &Addr) -> StdResult<Uint128> {
+    may_load_holding(storage, holder)?.ok_or_else(|| StdError::not_found("holding"))
+}
+
+/// Keys held by the holder at the start of the block at height
+pub fn may_load_holding_at_height(
+    storage: &dyn Storage,
+    holder: &Addr,
+    height: u64,
+) -> StdResult<Option<Uint128>> {
+    match ACCOUNT_IDS.may_load(storage, holder)? {
+        Some(account_id) => ACCOUNT_HOLDERS.may_load_at_height(storage, account_id, height),
+        None => HOLDERS.may_load_at_height(storage, holder, height),
+    }
+}
+
+/// Set the holding of the holder, removed at zero, leaving the holder count and the rewards alone
+pub fn write_holding(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    holder: &Addr,
+    amount: Uint128,
+) -> StdResult<()> {
+    match (ACCOUNT_IDS.may_load(storage, holder)?, amount.is_zero()) {
+        (Some(account_id), true) => ACCOUNT_HOLDERS.remove(storage, account_id, block.height),
+        (Some(account_id), false) => {
+            ACCOUNT_HOLDERS.save(storage, account_id, &amount, block.height)
+        }
+        (None, true) => HOLDERS.remove(storage, holder, block.height),
+        (None, false) => HOLDERS.save(storage, holder, &amount, block.height),
+    }
+}
+
+/// Rewards of the holder including the ones accrued since their last settlement
+pub fn query_holder_rewards(storage: &dyn Storage, holder: &Addr) -> StdResult<HolderRewards> {
+    let reward_per_key = REWARD_PER_KEY.load(storage)?;
+    let holding = may_load_holding(storage, holder)?.unwrap_or_default();
+    let rewards = HOLDER_REWARDS
+        .may_load(storage, holder)?
+        .unwrap_or_default();
+    Ok(HolderRewards {
+        reward_per_key_paid: reward_per_key,
+        pending: rewards.pending + accrued_rewards(storage, holding, rewards.reward_per_key_paid)?,
+    })
+}
+
+/// Settle the rewards accrued on the holder's current balance, must be called before the balance changes
+pub fn settle_rewards(storage: &mut dyn Storage, holder: &Addr) -> StdResult<HolderRewards> {
+    let rewards = query_holder_rewards(storage, holder)?;
+    HOLDER_REWARDS.save(storage, holder, &rewards)?;
+    Ok(rewards)
+}
+
+/// Set the holder's balance to amount after settling their rewards, holders left with no key are removed
+/// Paid is what the holder paid for the keys received, keys leaving take their share of the cost basis
+pub fn save_holding(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    holder: &Addr,
+    amount: Uint128,
+    paid: Uint128,
+) -> StdResult<()> {
+    settle_rewards(storage, holder)?;
+    let old_amount = may_load_holding(storage, holder)?;
+    let was_holder = old_amount.is_some();
+    let old_amount = old_amount.unwrap_or_default();
+    let old_cost = COST_BASIS.may_load(storage, holder)?.unwrap_or_default();
+    let cost = if amount >= old_amount {
+        old_cost + paid
+    } else {
+        old_cost.multiply_ratio(amount, old_amount)
+    };
+    if cost.is_zero() {
+        COST_BASIS.remove(storage, holder);
+    } else {
+        COST_BASIS.save(storage, holder, &cost)?;
+    }
+    write_holding(storage, block, holder, amount)?;
+    if amount.is_zero() {
+        if was_holder {
+            HOLDER_COUNT.update(storage, |count| -> StdResult<_> { Ok(count - 1) })?;
+        }
+    } else {
+        if !was_holder {
+            HOLDER_COUNT.update(storage, |count| -> StdResult<_> { Ok(count + 1) })?;
+        }
+    }
+    Ok(())
+}
+
+fn current_hour(block: &BlockInfo) -> u64 {
+    block.time.seconds() / SECONDS_PER_HOUR
+}
+
+/// Take amount of keys out of the supply, nobody holds them anymore
+pub fn burn_keys(storage: &mut dyn Storage, block: &BlockInfo, amount: Uint128) -> StdResult<()> {
+    let supply = SUPPLY.load(storage)?;
+    SUPPLY.save(storage, &supply.checked_sub(amount)?, block.height)
+}
+
+/// Add the price of a trade to the volume of the current hour, buckets out of the window are dropped
+pub fn record_volume(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    price: Uint128,
+) -> StdResult<()> {
+    let hour = current_hour(block);
+    let expired_hours = HOURLY_VOLUME
+        .keys(
+            storage,
+            None,
+            Some(Bound::exclusive(
+                hour.saturating_sub(VOLUME_WINDOW_HOURS - 1),
+            )),
+            Order::Ascending,
+        )
+        .collect::<StdResult<Vec<_>>>()?;
+    for expired_hour in expired_hours {
+        HOURLY_VOLUME.remove(storage, expired_hour);
+    }
+    HOURLY_VOLUME.update(storage, hour, |volume| -> StdResult<_> {
+        Ok(volume.unwrap_or_default() + price)
+    })?;
+    Ok(())
+}
+
+/// Volume over the last 24 hours, with an hourly granularity
+pub fn query_recent_volume(storage: &dyn Storage, block: &BlockInfo) -> StdResult<Uint128> {
+    HOURLY_VOLUME
+        .range(
+            storage,
+            Some(Bound::inclusive(
+                current_hour(block).saturating_sub(VOLUME_WINDOW_HOURS - 1),
+            )),
+            None,
+            Order::Ascending,
+        )
+        .map(|item| item.map(|(_, volume)| volume))
+        .sum()
+}
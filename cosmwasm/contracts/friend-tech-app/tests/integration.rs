@@ -1,21 +1,59 @@
 use friend_tech_app::{
     contract::interface::Friendtech,
+    events::{
+        BuyKeyEvent, BuybackAndBurnEvent, FillListingEvent, FriendTechEvent, HookFailedEvent,
+        MigrateEvent, ProposeEvent, ReclaimAirdropEvent, SellKeyEvent, SlashLockEvent,
+        SyncOwnerEvent, UnstakeEvent, VoteEvent,
+    },
     msg::{
-        FriendTechAppExecuteMsgFns, FriendTechAppInstantiateMsg, FriendTechAppQueryMsgFns,
-        HoldersResponse, IssuerResponse,
+        AirdropParams, AuctionParams, BuyKeyCostResponse, FriendTechAppExecuteMsgFns,
+        FriendTechAppInstantiateMsg, FriendTechAppMigrateMsg, FriendTechAppQueryMsgFns,
+        FriendTechHookMsg, HasMinimumKeysResponse, HoldersResponse, HookResponse, HooksResponse,
+        IssuerResponse, KeysForBudgetResponse, ListingResponse, LockResponse, MarketResponse,
+        ProposalStatus, ReferrerResponse, SellKeyCostResponse, StakingInfoResponse,
+        UncheckedFeeRecipient,
+    },
+    state::{
+        Ballot, DynamicFeeConfig, FeeRecipient, GovernanceConfig, HookFailure, RateLimits,
+        TransferRoyalty, VoteOption, CURRENT_STATE_VERSION,
     },
-    FriendTechAppError, MY_NAMESPACE,
+    FriendTechAppError, FRIEND_TECH_APP_ID, MY_NAMESPACE,
 };
 
-use abstract_app::objects::namespace::Namespace;
+use abstract_app::{
+    objects::{gov_type::GovernanceDetails, namespace::Namespace},
+    std::{
+        app::{BaseMigrateMsg, MigrateMsg},
+        manager,
+    },
+};
 use abstract_client::{AbstractClient, Application, Environment};
-use cosmwasm_std::{coins, Uint128};
+use cosmwasm_std::{
+    coins, from_json, to_json_binary, to_json_string, BankMsg, Binary, CosmosMsg, Decimal, Deps,
+    DepsMut, Empty, Int128, MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128,
+    WasmMsg,
+};
 // Use prelude to get all the necessary imports
-use cw_orch::{anyhow, prelude::*};
+use cw_controllers::AdminError;
+use cw_orch::{
+    anyhow,
+    mock::cw_multi_test::{ContractWrapper, Executor},
+    prelude::*,
+};
+use cw_storage_plus::Item;
+use cw_utils::{Duration, Expiration};
+use sha2::{Digest, Sha256};
 
 const DENOM: &str = "ucosm";
 
 const USER1: &str = "user1";
+const USER2: &str = "user2";
+const USER3: &str = "user3";
+
+const REFERRAL_FEE_PERCENTAGE: u32 = 20;
+const STAKING_FEE_PERCENTAGE: u32 = 40;
+const UNBONDING_PERIOD_SECONDS: u64 = 60 * 60 * 24;
+const LISTING_ROYALTY_PERCENTAGE: u32 = 10;
 
 struct TestEnv<Env: CwEnv> {
     abs: AbstractClient<Env>,
@@ -25,9 +63,28 @@ struct TestEnv<Env: CwEnv> {
 impl TestEnv<MockBech32> {
     /// Set up the test environment with an Account that has the App installed
     fn setup() -> anyhow::Result<TestEnv<MockBech32>> {
+        Self::setup_with(|_| {})
+    }
+
+    /// Set up the test environment with an App that opens with a Dutch auction
+    fn setup_with_auction(
+        opening_auction: Option<AuctionParams>,
+    ) -> anyhow::Result<TestEnv<MockBech32>> {
+        Self::setup_with(|msg| msg.opening_auction = opening_auction)
+    }
+
+    /// Set up the test environment with an App that accrues its fees until claimed
+    fn setup_with_fee_accrual() -> anyhow::Result<TestEnv<MockBech32>> {
+        Self::setup_with(|msg| msg.accrue_fees = true)
+    }
+
+    /// Set up the test environment with the App instantiate msg adjusted by customize
+    fn setup_with(
+        customize: impl FnOnce(&mut FriendTechAppInstantiateMsg),
+    ) -> anyhow::Result<TestEnv<MockBech32>> {
         // Create a sender and mock env
         let mock = MockBech32::new("mock");
-        let sender = mock.sender();
+        let sender = mock.sender_addr();
         let namespace = Namespace::new(MY_NAMESPACE)?;
 
         // You can set up Abstract with a builder.
@@ -38,14 +95,30 @@ impl TestEnv<MockBech32> {
         let publisher = abs_client.publisher_builder(namespace).build()?;
         publisher.publish_app::<Friendtech<_>>()?;
 
-        let app = publisher.account().install_app::<Friendtech<_>>(
-            &FriendTechAppInstantiateMsg {
-                username: "test".to_string(),
-                fee_denom: DENOM.to_string(),
-                issuer_fee_collector: sender.to_string(),
-            },
-            &[],
-        )?;
+        let mut msg = FriendTechAppInstantiateMsg {
+            username: "test".to_string(),
+            fee_denom: DENOM.to_string(),
+            fee_recipients: vec![UncheckedFeeRecipient {
+                address: sender.to_string(),
+                weight: 100,
+            }],
+            referral_fee_percentage: REFERRAL_FEE_PERCENTAGE,
+            staking_fee_percentage: STAKING_FEE_PERCENTAGE,
+            unbonding_period: Duration::Time(UNBONDING_PERIOD_SECONDS),
+            listing_royalty_percentage: LISTING_ROYALTY_PERCENTAGE,
+            accrue_fees: false,
+            accounts_only: false,
+            opening_auction: None,
+            governance: None,
+            dynamic_fee: None,
+            transfer_royalty: None,
+            airdrop: None,
+            rate_limits: None,
+        };
+        customize(&mut msg);
+        let app = publisher
+            .account()
+            .install_app::<Friendtech<_>>(&msg, &[])?;
 
         Ok(TestEnv {
             abs: abs_client,
@@ -65,7 +138,15 @@ fn successful_install() -> anyhow::Result<()> {
         IssuerResponse {
             username: "test".to_string(),
             fee_denom: DENOM.to_string(),
-            issuer_fee_collector: env.abs.sender(),
+            fee_recipients: vec![FeeRecipient {
+                address: env.abs.sender(),
+                weight: 100,
+            }],
+            referral_fee_percentage: REFERRAL_FEE_PERCENTAGE,
+            listing_royalty_percentage: LISTING_ROYALTY_PERCENTAGE,
+            accrue_fees: false,
+            accounts_only: false,
+            issuer_key_holder: env.abs.sender(),
             supply: Uint128::one(),
         }
     );
@@ -73,12 +154,13 @@ fn successful_install() -> anyhow::Result<()> {
     assert_eq!(
         holders,
         HoldersResponse {
-            holders: vec![env.abs.sender()]
+            holders: vec![env.abs.sender()],
+            accounts: vec![],
         }
     );
     let holding = app.holding(env.abs.sender().to_string())?;
     assert_eq!(holding.amount, Uint128::one());
-    assert_eq!(issuer.issuer_fee_collector, app.account().owner()?);
+    assert_eq!(issuer.fee_recipients[0].address, app.account().owner()?);
 
     Ok(())
 }
@@ -96,7 +178,7 @@ fn failed_buy_key() -> anyhow::Result<()> {
 
     let mock_env = abs.environment();
 
-    let buy_cost_resp = app.buy_key_cost(Uint128::from(buy_amount))?;
+    let buy_cost_resp = app.buy_key_cost(Uint128::from(buy_amount), None)?;
 
     let buyer_addr = &mock_env.addr_make(USER1);
 
@@ -104,7 +186,7 @@ fn failed_buy_key() -> anyhow::Result<()> {
 
     let err: FriendTechAppError = app
         .call_as(buyer_addr)
-        .buy_key(Uint128::from(buy_amount), &coins(1, fee_denom))
+        .buy_key(Uint128::from(buy_amount), None, None, &coins(1, fee_denom))
         .unwrap_err()
         .downcast()
         .unwrap();
@@ -132,7 +214,7 @@ fn successful_buy_key() -> anyhow::Result<()> {
 
     let mock_env = abs.environment();
 
-    let buy_cost_resp = app.buy_key_cost(Uint128::from(buy_amount))?;
+    let buy_cost_resp = app.buy_key_cost(Uint128::from(buy_amount), None)?;
 
     let buyer_addr = &mock_env.addr_make(USER1);
 
@@ -143,6 +225,8 @@ fn successful_buy_key() -> anyhow::Result<()> {
 
     app.call_as(buyer_addr).buy_key(
         Uint128::from(buy_amount),
+        None,
+        None,
         &coins(buy_cost_resp.total_cost.u128(), fee_denom),
     )?;
 
@@ -151,7 +235,7 @@ fn successful_buy_key() -> anyhow::Result<()> {
         Uint128::zero()
     );
     assert_eq!(
-        mock_env.query_balance(&issuer.issuer_fee_collector, fee_denom)?,
+        mock_env.query_balance(&issuer.fee_recipients[0].address, fee_denom)?,
         buy_cost_resp.issuer_fee
     );
     assert_eq!(
@@ -166,7 +250,8 @@ fn successful_buy_key() -> anyhow::Result<()> {
     assert_eq!(
         holders,
         HoldersResponse {
-            holders: vec![abs.sender(), buyer_addr.clone()]
+            holders: vec![abs.sender(), buyer_addr.clone()],
+            accounts: vec![],
         }
     );
 
@@ -190,7 +275,7 @@ fn successful_sell_key() -> anyhow::Result<()> {
 
     let mock_env = abs.environment();
 
-    let buy_cost_resp = app.buy_key_cost(Uint128::from(buy_amount))?;
+    let buy_cost_resp = app.buy_key_cost(Uint128::from(buy_amount), None)?;
 
     let trader_addr = &mock_env.addr_make(USER1);
 
@@ -201,26 +286,44 @@ fn successful_sell_key() -> anyhow::Result<()> {
 
     app.call_as(trader_addr).buy_key(
         Uint128::from(buy_amount),
+        None,
+        None,
         &coins(buy_cost_resp.total_cost.u128(), fee_denom),
     )?;
 
-    let sell_cost_resp = app.sell_key_cost(Uint128::from(sell_amount))?;
+    let sell_cost_resp = app.sell_key_cost(Uint128::from(sell_amount), None)?;
     mock_env.set_balance(
         trader_addr,
         coins(sell_cost_resp.total_cost.u128(), fee_denom),
     )?;
 
-    app.call_as(trader_addr).sell_key(
+    let tx_resp = app.call_as(trader_addr).sell_key(
         Uint128::from(sell_amount),
+        None,
         &coins(sell_cost_resp.total_cost.u128(), fee_denom),
     )?;
 
+    assert_eq!(
+        SellKeyEvent::find(&tx_resp.events)?,
+        SellKeyEvent {
+            issuer: app.account().owner()?,
+            seller: trader_addr.clone(),
+            amount: Uint128::from(sell_amount),
+            price: sell_cost_resp.price,
+            issuer_fee: sell_cost_resp.issuer_fee,
+            referrer: None,
+            referral_fee: Uint128::zero(),
+            staking_fee: Uint128::zero(),
+            supply: Uint128::from(buy_amount - sell_amount + 1),
+            holding: Uint128::from(buy_amount - sell_amount),
+        }
+    );
     assert_eq!(
         mock_env.query_balance(trader_addr, fee_denom)?,
         sell_cost_resp.price
     );
     assert_eq!(
-        mock_env.query_balance(&issuer.issuer_fee_collector, fee_denom)?,
+        mock_env.query_balance(&issuer.fee_recipients[0].address, fee_denom)?,
         buy_cost_resp.issuer_fee + sell_cost_resp.issuer_fee
     );
     assert_eq!(
@@ -235,7 +338,8 @@ fn successful_sell_key() -> anyhow::Result<()> {
     assert_eq!(
         holders,
         HoldersResponse {
-            holders: vec![abs.sender(), trader_addr.clone()]
+            holders: vec![abs.sender(), trader_addr.clone()],
+            accounts: vec![],
         }
     );
 
@@ -244,3 +348,3579 @@ fn successful_sell_key() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn successful_trade_with_referrer() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let issuer = app.issuer()?;
+    let fee_denom = issuer.fee_denom.as_str();
+
+    let buy_amount = 10u128;
+    let sell_amount = 5u128;
+
+    let mock_env = abs.environment();
+
+    let trader_addr = &mock_env.addr_make(USER1);
+    let referrer_addr = &mock_env.addr_make(USER2);
+
+    let buy_cost_resp = app.buy_key_cost(Uint128::from(buy_amount), None)?;
+    mock_env.set_balance(
+        trader_addr,
+        coins(buy_cost_resp.total_cost.u128(), fee_denom),
+    )?;
+    app.call_as(trader_addr).buy_key(
+        Uint128::from(buy_amount),
+        None,
+        Some(referrer_addr.to_string()),
+        &coins(buy_cost_resp.total_cost.u128(), fee_denom),
+    )?;
+
+    let sell_cost_resp = app.sell_key_cost(Uint128::from(sell_amount), None)?;
+    mock_env.set_balance(
+        trader_addr,
+        coins(sell_cost_resp.total_cost.u128(), fee_denom),
+    )?;
+    app.call_as(trader_addr).sell_key(
+        Uint128::from(sell_amount),
+        Some(referrer_addr.to_string()),
+        &coins(sell_cost_resp.total_cost.u128(), fee_denom),
+    )?;
+
+    let buy_referral_fee =
+        buy_cost_resp.issuer_fee * Uint128::from(REFERRAL_FEE_PERCENTAGE) / Uint128::from(100u128);
+    let sell_referral_fee =
+        sell_cost_resp.issuer_fee * Uint128::from(REFERRAL_FEE_PERCENTAGE) / Uint128::from(100u128);
+
+    assert_eq!(
+        mock_env.query_balance(referrer_addr, fee_denom)?,
+        buy_referral_fee + sell_referral_fee
+    );
+    assert_eq!(
+        mock_env.query_balance(&issuer.fee_recipients[0].address, fee_denom)?,
+        buy_cost_resp.issuer_fee + sell_cost_resp.issuer_fee - buy_referral_fee - sell_referral_fee
+    );
+
+    let referrer = app.referrer(referrer_addr.to_string())?;
+    assert_eq!(
+        referrer,
+        ReferrerResponse {
+            volume: buy_cost_resp.price + sell_cost_resp.price,
+            earnings: buy_referral_fee + sell_referral_fee,
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn failed_buy_key_self_referral() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let issuer = app.issuer()?;
+    let fee_denom = issuer.fee_denom.as_str();
+
+    let mock_env = abs.environment();
+
+    let buy_cost_resp = app.buy_key_cost(Uint128::one(), None)?;
+
+    let buyer_addr = &mock_env.addr_make(USER1);
+
+    mock_env.set_balance(
+        buyer_addr,
+        coins(buy_cost_resp.total_cost.u128(), fee_denom),
+    )?;
+
+    let err: FriendTechAppError = app
+        .call_as(buyer_addr)
+        .buy_key(
+            Uint128::one(),
+            None,
+            Some(buyer_addr.to_string()),
+            &coins(buy_cost_resp.total_cost.u128(), fee_denom),
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::CannotReferSelf {});
+
+    Ok(())
+}
+
+#[test]
+fn successful_deposit_and_claim_rewards() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let issuer = app.issuer()?;
+    let fee_denom = issuer.fee_denom.as_str();
+    let issuer_addr = abs.sender();
+
+    let mock_env = abs.environment();
+
+    let holder_addr = &mock_env.addr_make(USER1);
+    let late_buyer_addr = &mock_env.addr_make(USER2);
+
+    // Holder buys 3 keys, supply becomes 4
+    let buy_cost_resp = app.buy_key_cost(Uint128::from(3u128), None)?;
+    mock_env.set_balance(
+        holder_addr,
+        coins(buy_cost_resp.total_cost.u128(), fee_denom),
+    )?;
+    app.call_as(holder_addr).buy_key(
+        Uint128::from(3u128),
+        None,
+        None,
+        &coins(buy_cost_resp.total_cost.u128(), fee_denom),
+    )?;
+
+    // Anyone can deposit rewards
+    mock_env.set_balance(late_buyer_addr, coins(1_000, fee_denom))?;
+    app.call_as(late_buyer_addr)
+        .deposit_rewards(&coins(1_000, fee_denom))?;
+
+    assert_eq!(
+        app.pending_rewards(issuer_addr.to_string())?.amount,
+        Uint128::from(250u128)
+    );
+    assert_eq!(
+        app.pending_rewards(holder_addr.to_string())?.amount,
+        Uint128::from(750u128)
+    );
+
+    // Keys bought after the deposit don't earn past rewards
+    let buy_cost_resp = app.buy_key_cost(Uint128::from(4u128), None)?;
+    mock_env.set_balance(
+        late_buyer_addr,
+        coins(buy_cost_resp.total_cost.u128(), fee_denom),
+    )?;
+    app.call_as(late_buyer_addr).buy_key(
+        Uint128::from(4u128),
+        None,
+        None,
+        &coins(buy_cost_resp.total_cost.u128(), fee_denom),
+    )?;
+    assert_eq!(
+        app.pending_rewards(late_buyer_addr.to_string())?.amount,
+        Uint128::zero()
+    );
+
+    // Selling keys doesn't lose rewards already accrued, supply is now 8 - 2 = 6
+    let sell_cost_resp = app.sell_key_cost(Uint128::from(2u128), None)?;
+    mock_env.set_balance(
+        holder_addr,
+        coins(sell_cost_resp.total_cost.u128(), fee_denom),
+    )?;
+    app.call_as(holder_addr).sell_key(
+        Uint128::from(2u128),
+        None,
+        &coins(sell_cost_resp.total_cost.u128(), fee_denom),
+    )?;
+
+    mock_env.set_balance(late_buyer_addr, coins(600, fee_denom))?;
+    app.call_as(late_buyer_addr)
+        .deposit_rewards(&coins(600, fee_denom))?;
+
+    assert_eq!(
+        app.pending_rewards(issuer_addr.to_string())?.amount,
+        Uint128::from(350u128)
+    );
+    assert_eq!(
+        app.pending_rewards(holder_addr.to_string())?.amount,
+        Uint128::from(850u128)
+    );
+    assert_eq!(
+        app.pending_rewards(late_buyer_addr.to_string())?.amount,
+        Uint128::from(400u128)
+    );
+
+    let holder_balance = mock_env.query_balance(holder_addr, fee_denom)?;
+    app.call_as(holder_addr).claim_rewards()?;
+    assert_eq!(
+        mock_env.query_balance(holder_addr, fee_denom)?,
+        holder_balance + Uint128::from(850u128)
+    );
+    assert_eq!(
+        app.pending_rewards(holder_addr.to_string())?.amount,
+        Uint128::zero()
+    );
+
+    let err: FriendTechAppError = app
+        .call_as(holder_addr)
+        .claim_rewards()
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::NoRewardsToClaim {});
+
+    // The accumulator holds rewards per key well past what a u128 backed decimal fits
+    let large_deposit = 6 * 10u128.pow(24);
+    mock_env.set_balance(late_buyer_addr, coins(large_deposit, fee_denom))?;
+    app.call_as(late_buyer_addr)
+        .deposit_rewards(&coins(large_deposit, fee_denom))?;
+    assert_eq!(
+        app.pending_rewards(holder_addr.to_string())?.amount,
+        Uint128::from(10u128.pow(24))
+    );
+    assert_eq!(
+        app.pending_rewards(late_buyer_addr.to_string())?.amount,
+        Uint128::from(400u128 + 4 * 10u128.pow(24))
+    );
+
+    Ok(())
+}
+
+/// Buy keys at the current cost, funding the buyer with exactly what is needed
+fn buy_keys(
+    mock_env: &MockBech32,
+    app: &Application<MockBech32, Friendtech<MockBech32>>,
+    buyer_addr: &Addr,
+    amount: u128,
+) -> Result<BuyKeyCostResponse, CwOrchError> {
+    let fee_denom = app.issuer()?.fee_denom;
+    let buy_cost_resp = app.buy_key_cost(Uint128::from(amount), None)?;
+    mock_env.set_balance(
+        buyer_addr,
+        coins(buy_cost_resp.total_cost.u128(), &fee_denom),
+    )?;
+    app.call_as(buyer_addr).buy_key(
+        Uint128::from(amount),
+        None,
+        None,
+        &coins(buy_cost_resp.total_cost.u128(), &fee_denom),
+    )?;
+    Ok(buy_cost_resp)
+}
+
+/// Sell keys at the current cost, funding the seller with exactly the fee
+fn sell_keys(
+    mock_env: &MockBech32,
+    app: &Application<MockBech32, Friendtech<MockBech32>>,
+    seller_addr: &Addr,
+    amount: u128,
+) -> Result<SellKeyCostResponse, CwOrchError> {
+    let fee_denom = app.issuer()?.fee_denom;
+    let sell_cost_resp = app.sell_key_cost(Uint128::from(amount), None)?;
+    mock_env.set_balance(
+        seller_addr,
+        coins(sell_cost_resp.total_cost.u128(), &fee_denom),
+    )?;
+    app.call_as(seller_addr).sell_key(
+        Uint128::from(amount),
+        None,
+        &coins(sell_cost_resp.total_cost.u128(), &fee_denom),
+    )?;
+    Ok(sell_cost_resp)
+}
+
+#[test]
+fn successful_stake_and_unstake() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let issuer = app.issuer()?;
+    let fee_denom = issuer.fee_denom.as_str();
+
+    let mock_env = abs.environment();
+
+    let staker1_addr = &mock_env.addr_make(USER1);
+    let staker2_addr = &mock_env.addr_make(USER2);
+    let trader_addr = &mock_env.addr_make(USER3);
+
+    buy_keys(&mock_env, &app, staker1_addr, 10)?;
+    buy_keys(&mock_env, &app, staker2_addr, 10)?;
+
+    app.call_as(staker1_addr).stake(Uint128::from(10u128))?;
+    mock_env.wait_seconds(100)?;
+    app.call_as(staker2_addr).stake(Uint128::from(10u128))?;
+    mock_env.wait_seconds(100)?;
+
+    assert_eq!(
+        app.staking_info()?,
+        StakingInfoResponse {
+            total_staked: Uint128::from(20u128),
+            total_weight: Uint128::from(3_000u128),
+            staking_fee_percentage: STAKING_FEE_PERCENTAGE,
+            unbonding_period: Duration::Time(UNBONDING_PERIOD_SECONDS),
+        }
+    );
+
+    // Staker 1 has been staking twice as long, so they get twice the staking fee
+    let collector_balance = mock_env.query_balance(&issuer.fee_recipients[0].address, fee_denom)?;
+    let buy_cost_resp = buy_keys(&mock_env, &app, trader_addr, 1)?;
+    let staking_fee =
+        buy_cost_resp.issuer_fee * Uint128::from(STAKING_FEE_PERCENTAGE) / Uint128::from(100u128);
+    assert_eq!(
+        mock_env.query_balance(&issuer.fee_recipients[0].address, fee_denom)?,
+        collector_balance + buy_cost_resp.issuer_fee - staking_fee
+    );
+
+    let staked1 = app.staked(staker1_addr.to_string())?;
+    let staked2 = app.staked(staker2_addr.to_string())?;
+    assert_eq!(staked1.weight, Uint128::from(2_000u128));
+    assert_eq!(staked2.weight, Uint128::from(1_000u128));
+    assert_eq!(
+        staked1.pending_rewards,
+        staking_fee.multiply_ratio(2u128, 3u128)
+    );
+    assert_eq!(
+        staked2.pending_rewards,
+        staking_fee.multiply_ratio(1u128, 3u128)
+    );
+
+    // Staked keys cannot be sold
+    let err: FriendTechAppError = sell_keys(&mock_env, &app, staker1_addr, 1)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::InsufficientUnlockedKeys {
+            available: Uint128::zero(),
+            required: Uint128::one(),
+        }
+    );
+
+    // Nothing is unstaked for a zero amount, with or without a stake
+    for addr in [staker1_addr, trader_addr] {
+        let err: FriendTechAppError = app
+            .call_as(addr)
+            .unstake(Uint128::zero())
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err, FriendTechAppError::InvalidUnstakeAmount {});
+    }
+    assert!(app.staked(staker1_addr.to_string())?.unbonding.is_empty());
+
+    // Unstaked keys stay locked during the unbonding period
+    let tx_resp = app.call_as(staker1_addr).unstake(Uint128::from(10u128))?;
+    let staked1 = app.staked(staker1_addr.to_string())?;
+    assert_eq!(staked1.staked, Uint128::zero());
+    assert_eq!(staked1.unbonding.len(), 1);
+    assert_eq!(staked1.unbonding[0].amount, Uint128::from(10u128));
+    assert_eq!(
+        UnstakeEvent::find(&tx_resp.events)?,
+        UnstakeEvent {
+            staker: staker1_addr.clone(),
+            amount: Uint128::from(10u128),
+            staked: Uint128::zero(),
+            release_at: staked1.unbonding[0].release_at,
+        }
+    );
+
+    let err: FriendTechAppError = sell_keys(&mock_env, &app, staker1_addr, 1)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::InsufficientUnlockedKeys {
+            available: Uint128::zero(),
+            required: Uint128::one(),
+        }
+    );
+
+    mock_env.wait_seconds(UNBONDING_PERIOD_SECONDS)?;
+    sell_keys(&mock_env, &app, staker1_addr, 1)?;
+    assert!(app.staked(staker1_addr.to_string())?.unbonding.is_empty());
+
+    let balance = mock_env.query_balance(staker1_addr, fee_denom)?;
+    app.call_as(staker1_addr).claim_staking_rewards()?;
+    assert_eq!(
+        mock_env.query_balance(staker1_addr, fee_denom)?,
+        balance + staking_fee.multiply_ratio(2u128, 3u128)
+    );
+
+    let err: FriendTechAppError = app
+        .call_as(staker1_addr)
+        .claim_staking_rewards()
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::NoRewardsToClaim {});
+
+    Ok(())
+}
+
+#[test]
+fn successful_buy_key_for_recipient() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let issuer = app.issuer()?;
+    let fee_denom = issuer.fee_denom.as_str();
+
+    let buy_amount = 3u128;
+
+    let mock_env = abs.environment();
+
+    let payer_addr = &mock_env.addr_make(USER1);
+    let recipient_addr = &mock_env.addr_make(USER2);
+
+    let buy_cost_resp = app.buy_key_cost(Uint128::from(buy_amount), None)?;
+    mock_env.set_balance(
+        payer_addr,
+        coins(buy_cost_resp.total_cost.u128(), fee_denom),
+    )?;
+
+    let tx_resp = app.call_as(payer_addr).buy_key(
+        Uint128::from(buy_amount),
+        Some(recipient_addr.to_string()),
+        None,
+        &coins(buy_cost_resp.total_cost.u128(), fee_denom),
+    )?;
+
+    // Nobody staked so the fee recipients get the whole issuer fee
+    assert_eq!(
+        BuyKeyEvent::find(&tx_resp.events)?,
+        BuyKeyEvent {
+            issuer: app.account().owner()?,
+            payer: payer_addr.clone(),
+            recipient: recipient_addr.clone(),
+            amount: Uint128::from(buy_amount),
+            price: buy_cost_resp.price,
+            issuer_fee: buy_cost_resp.issuer_fee,
+            referrer: None,
+            referral_fee: Uint128::zero(),
+            staking_fee: Uint128::zero(),
+            supply: Uint128::from(buy_amount + 1),
+            holding: Uint128::from(buy_amount),
+        }
+    );
+    assert!(SellKeyEvent::find(&tx_resp.events).is_err());
+
+    assert_eq!(
+        mock_env.query_balance(payer_addr, fee_denom)?,
+        Uint128::zero()
+    );
+    assert_eq!(
+        app.holding(recipient_addr.to_string())?.amount,
+        Uint128::from(buy_amount)
+    );
+    assert_eq!(app.holding(payer_addr.to_string())?.amount, Uint128::zero());
+
+    Ok(())
+}
+
+#[test]
+fn successful_keys_for_budget() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+
+    let cost_of_7 = app.buy_key_cost(Uint128::from(7u128), None)?;
+    let cost_of_8 = app.buy_key_cost(Uint128::from(8u128), None)?;
+
+    // Exactly enough for 7 keys
+    let quote = app.keys_for_budget(cost_of_7.total_cost, None)?;
+    assert_eq!(
+        quote,
+        KeysForBudgetResponse {
+            amount: Uint128::from(7u128),
+            price: cost_of_7.price,
+            issuer_fee: cost_of_7.issuer_fee,
+            issuer_fee_percentage: 5,
+            total_cost: cost_of_7.total_cost,
+            leftover: Uint128::zero(),
+        }
+    );
+
+    // Just short of 8 keys
+    let budget = cost_of_8.total_cost - Uint128::one();
+    let quote = app.keys_for_budget(budget, None)?;
+    assert_eq!(quote.amount, Uint128::from(7u128));
+    assert_eq!(quote.leftover, budget - cost_of_7.total_cost);
+
+    // Not enough for a single key
+    let cost_of_1 = app.buy_key_cost(Uint128::one(), None)?;
+    let quote = app.keys_for_budget(cost_of_1.total_cost - Uint128::one(), None)?;
+    assert_eq!(quote.amount, Uint128::zero());
+    assert_eq!(quote.total_cost, Uint128::zero());
+    assert_eq!(quote.leftover, cost_of_1.total_cost - Uint128::one());
+
+    // Any budget is quoted, the largest amount is the last one whose cost fits in a Uint128
+    let quote = app.keys_for_budget(Uint128::MAX, None)?;
+    assert!(!quote.amount.is_zero());
+    assert_eq!(quote.total_cost + quote.leftover, Uint128::MAX);
+    assert!(app
+        .buy_key_cost(quote.amount + Uint128::one(), None)
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn successful_price_curve() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+
+    // Arguments are from_supply, step, to_supply
+    let curve = app.price_curve(
+        Uint128::from(1u128),
+        Uint128::from(3u128),
+        Uint128::from(7u128),
+    )?;
+    let supplies: Vec<Uint128> = curve.points.iter().map(|point| point.supply).collect();
+    assert_eq!(
+        supplies,
+        vec![
+            Uint128::from(1u128),
+            Uint128::from(4u128),
+            Uint128::from(7u128)
+        ]
+    );
+
+    // At the current supply the marginal price is the price of buying one key
+    assert_eq!(
+        curve.points[0].price,
+        app.buy_key_cost(Uint128::one(), None)?.price
+    );
+    assert!(curve.points[0].price < curve.points[1].price);
+    assert!(curve.points[1].price < curve.points[2].price);
+
+    // Query errors reach the caller as strings
+    let err = app
+        .price_curve(Uint128::zero(), Uint128::zero(), Uint128::from(7u128))
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains(&FriendTechAppError::InvalidPriceCurveStep {}.to_string()));
+    let err = app
+        .price_curve(Uint128::from(7u128), Uint128::one(), Uint128::one())
+        .unwrap_err();
+    assert!(err.to_string().contains(
+        &FriendTechAppError::InvalidPriceCurveRange {
+            from_supply: Uint128::from(7u128),
+            to_supply: Uint128::one(),
+        }
+        .to_string()
+    ));
+
+    // Stepping past the largest supply ends the curve, the price of a huge supply errors instead of panicking
+    let curve = app.price_curve(Uint128::one(), Uint128::MAX, Uint128::MAX)?;
+    assert_eq!(curve.points.len(), 1);
+    assert!(app
+        .price_curve(Uint128::MAX, Uint128::one(), Uint128::MAX)
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn successful_market() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let trader1_addr = &mock_env.addr_make(USER1);
+    let trader2_addr = &mock_env.addr_make(USER2);
+
+    let buy_cost_resp1 = buy_keys(&mock_env, &app, trader1_addr, 5)?;
+    mock_env.wait_seconds(60 * 60 * 2)?;
+    let buy_cost_resp2 = buy_keys(&mock_env, &app, trader2_addr, 3)?;
+    let sell_cost_resp = sell_keys(&mock_env, &app, trader2_addr, 3)?;
+
+    let spot_price = app.buy_key_cost(Uint128::one(), None)?.price;
+    assert_eq!(
+        app.market()?,
+        MarketResponse {
+            supply: Uint128::from(6u128),
+            spot_price,
+            market_cap: spot_price * Uint128::from(6u128),
+            reserve: buy_cost_resp1.price + buy_cost_resp2.price - sell_cost_resp.price,
+            burned: Uint128::zero(),
+            volume_24h: buy_cost_resp1.price + buy_cost_resp2.price + sell_cost_resp.price,
+            holder_count: 2,
+            closed: false,
+        }
+    );
+
+    // The first buy falls out of the 24 hour window
+    mock_env.wait_seconds(60 * 60 * 23)?;
+    assert_eq!(
+        app.market()?.volume_24h,
+        buy_cost_resp2.price + sell_cost_resp.price
+    );
+
+    Ok(())
+}
+
+/// Key of `key` in the storage of the contract at `contract_addr`, as namespaced by the mock wasm module
+fn contract_storage_key(contract_addr: &Addr, key: &[u8]) -> Vec<u8> {
+    let contract_namespace = [b"contract_data/", contract_addr.as_bytes()].concat();
+    let mut storage_key = vec![];
+    for namespace in [b"wasm".as_slice(), &contract_namespace] {
+        storage_key.extend_from_slice(&(namespace.len() as u16).to_be_bytes());
+        storage_key.extend_from_slice(namespace);
+    }
+    storage_key.extend_from_slice(key);
+    storage_key
+}
+
+/// State version that introduced each storage namespace of the app
+const NAMESPACE_STATE_VERSIONS: [(&str, u32); 52] = [
+    ("CONFIG", 1),
+    ("SUPPLY", 1),
+    ("HOLDERS", 1),
+    ("STATE_VERSION", 2),
+    ("RESERVE", 2),
+    ("HOLDER_COUNT", 2),
+    ("HOURLY_VOLUME", 2),
+    ("REFERRERS", 2),
+    ("REWARD_PER_KEY", 2),
+    ("HOLDER_REWARDS", 2),
+    ("STAKING", 2),
+    ("STAKES", 2),
+    ("UNBONDING", 2),
+    ("MARKET_CLOSED", 3),
+    ("NEXT_LISTING_ID", 4),
+    ("LISTINGS", 4),
+    ("LISTINGS__PRICE", 4),
+    ("LISTINGS__SELLER", 4),
+    ("AUCTION", 4),
+    ("BIDS", 4),
+    ("ACCRUED_FEES", 6),
+    ("HOOKS", 6),
+    ("ACCOUNT_IDS", 7),
+    ("ACCOUNT_HOLDERS", 7),
+    ("ACCOUNT_HOLDERS__checkpoints", 9),
+    ("ACCOUNT_HOLDERS__changelog", 9),
+    ("ISSUER", 8),
+    ("NEXT_PROPOSAL_ID", 9),
+    ("PROPOSALS", 9),
+    ("BALLOTS", 9),
+    ("APPROVED_PROPOSALS", 9),
+    ("APPROVAL_EXEMPTIONS", 12),
+    ("GOVERNANCE", 9),
+    ("SUPPLY__checkpoints", 9),
+    ("SUPPLY__changelog", 9),
+    ("HOLDERS__checkpoints", 9),
+    ("HOLDERS__changelog", 9),
+    ("DYNAMIC_FEE", 9),
+    ("TRADER_ACTIVITY", 9),
+    ("ROUND_TRIPS", 12),
+    ("TRANSFER_ROYALTY", 9),
+    ("ROYALTY_EXEMPTIONS", 9),
+    ("BURNED", 10),
+    ("AIRDROP", 10),
+    ("AIRDROP_CLAIMS", 10),
+    ("COST_BASIS", 10),
+    ("RATE_LIMITS", 10),
+    ("TRADE_WINDOWS", 10),
+    ("BLOCK_VOLUME", 10),
+    ("NEXT_LOCK_ID", 11),
+    ("LOCKS", 11),
+    ("LOCKS__HOLDER", 11),
+];
+
+/// Keys of the Abstract app base state, they exist in every version
+const BASE_STATE_KEYS: [&str; 4] = ["admin", "base_state", "contract_info", "module_data"];
+
+/// Namespace of a key in the contract storage, maps and indexes are prefixed with the length of theirs
+fn storage_namespace(key: &[u8]) -> String {
+    let namespace = match key {
+        [0, len, rest @ ..] => &rest[..*len as usize],
+        _ => key,
+    };
+    String::from_utf8_lossy(namespace).into_owned()
+}
+
+/// Config of the app serialized in the layout of `state_version`
+fn legacy_config_json(
+    issuer: &IssuerResponse,
+    staking_info: &StakingInfoResponse,
+    state_version: u32,
+) -> StdResult<String> {
+    let mut fields = vec![
+        format!(r#""username":{}"#, to_json_string(&issuer.username)?),
+        format!(r#""fee_denom":{}"#, to_json_string(&issuer.fee_denom)?),
+    ];
+    if state_version < 5 {
+        fields.push(format!(
+            r#""issuer_fee_collector":{}"#,
+            to_json_string(&issuer.fee_recipients[0].address)?
+        ));
+    } else {
+        fields.push(format!(
+            r#""fee_recipients":{}"#,
+            to_json_string(&issuer.fee_recipients)?
+        ));
+    }
+    if state_version >= 2 {
+        fields.push(format!(
+            r#""referral_fee_percentage":{}"#,
+            issuer.referral_fee_percentage
+        ));
+        fields.push(format!(
+            r#""staking_fee_percentage":{}"#,
+            staking_info.staking_fee_percentage
+        ));
+        fields.push(format!(
+            r#""unbonding_period":{}"#,
+            to_json_string(&staking_info.unbonding_period)?
+        ));
+    }
+    if state_version >= 4 {
+        fields.push(format!(
+            r#""listing_royalty_percentage":{}"#,
+            issuer.listing_royalty_percentage
+        ));
+    }
+    if state_version >= 6 {
+        fields.push(format!(r#""accrue_fees":{}"#, issuer.accrue_fees));
+    }
+    if state_version >= 7 {
+        fields.push(format!(r#""accounts_only":{}"#, issuer.accounts_only));
+    }
+    Ok(format!("{{{}}}", fields.join(",")))
+}
+
+/// Rewrite the app state into the layout of `state_version`, the state added by later versions is removed
+fn downgrade_to_state_version(
+    mock_env: &MockBech32,
+    app: &Application<MockBech32, Friendtech<MockBech32>>,
+    state_version: u32,
+) -> anyhow::Result<()> {
+    let config = legacy_config_json(&app.issuer()?, &app.staking_info()?, state_version)?;
+    let app_addr = app.address()?;
+    let prefix = contract_storage_key(&app_addr, b"");
+    let mut mock_app = mock_env.app.borrow_mut();
+    let storage = mock_app.storage_mut();
+
+    let keys: Vec<Vec<u8>> = storage
+        .range_keys(Some(&prefix), None, Order::Ascending)
+        .take_while(|key| key.starts_with(&prefix))
+        .collect();
+    for key in keys {
+        let namespace = storage_namespace(&key[prefix.len()..]);
+        if BASE_STATE_KEYS.contains(&namespace.as_str()) {
+            continue;
+        }
+        let (_, introduced_in) = NAMESPACE_STATE_VERSIONS
+            .iter()
+            .find(|(name, _)| *name == namespace)
+            .unwrap_or_else(|| panic!("state version of {namespace} is unknown"));
+        if *introduced_in > state_version {
+            storage.remove(&key);
+        }
+    }
+
+    // Abstract only migrates installs of an older release
+    storage.set(
+        &contract_storage_key(&app_addr, b"contract_info"),
+        format!(r#"{{"contract":"{FRIEND_TECH_APP_ID}","version":"0.0.1"}}"#).as_bytes(),
+    );
+    storage.set(
+        &contract_storage_key(&app_addr, b"CONFIG"),
+        config.as_bytes(),
+    );
+    if state_version >= 2 {
+        storage.set(
+            &contract_storage_key(&app_addr, b"STATE_VERSION"),
+            state_version.to_string().as_bytes(),
+        );
+    }
+    Ok(())
+}
+
+/// Migrate the app to the current code as its admin
+fn migrate_app(
+    mock_env: &MockBech32,
+    app: &Application<MockBech32, Friendtech<MockBech32>>,
+    msg: FriendTechAppMigrateMsg,
+) -> Result<TxResponse<MockBech32>, CwOrchError> {
+    let app_addr = app.address()?;
+    let admin = mock_env
+        .app
+        .borrow()
+        .contract_data(&app_addr)
+        .unwrap()
+        .admin
+        .unwrap();
+    app.call_as(&admin).migrate(
+        &MigrateMsg {
+            base: BaseMigrateMsg {},
+            module: msg,
+        },
+        app.code_id()?,
+    )
+}
+
+#[test]
+fn successful_migrate_from_v0_0_1() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let trader1_addr = &mock_env.addr_make(USER1);
+    let trader2_addr = &mock_env.addr_make(USER2);
+
+    buy_keys(&mock_env, &app, trader1_addr, 4)?;
+    buy_keys(&mock_env, &app, trader2_addr, 2)?;
+    sell_keys(&mock_env, &app, trader1_addr, 1)?;
+    let market = app.market()?;
+
+    downgrade_to_state_version(&mock_env, &app, 1)?;
+    migrate_app(
+        &mock_env,
+        &app,
+        FriendTechAppMigrateMsg {
+            referral_fee_percentage: Some(10),
+            staking_fee_percentage: Some(30),
+            ..Default::default()
+        },
+    )?;
+
+    let issuer = app.issuer()?;
+    assert_eq!(issuer.referral_fee_percentage, 10);
+    assert_eq!(issuer.listing_royalty_percentage, 0);
+    assert!(!issuer.accrue_fees);
+    assert!(app.accrued_fees()?.amount.is_zero());
+    // The issuer fee collector becomes the only fee recipient
+    assert_eq!(
+        issuer.fee_recipients,
+        vec![FeeRecipient {
+            address: abs.sender(),
+            weight: 100,
+        }]
+    );
+    let staking_info = app.staking_info()?;
+    assert_eq!(staking_info.staking_fee_percentage, 30);
+    assert_eq!(staking_info.unbonding_period, Duration::Time(0));
+
+    // The reserve and holder count are rebuilt from the supply and the holders
+    let migrated_market = app.market()?;
+    assert_eq!(migrated_market.reserve, market.reserve);
+    assert_eq!(migrated_market.holder_count, market.holder_count);
+    assert!(!migrated_market.closed);
+
+    // The migrated state supports the features added since 0.0.1
+    app.call_as(trader1_addr).stake(Uint128::from(2u128))?;
+    mock_env.wait_seconds(60)?;
+    buy_keys(&mock_env, &app, trader2_addr, 1)?;
+    assert!(!app
+        .staked(trader1_addr.to_string())?
+        .pending_rewards
+        .is_zero());
+    sell_keys(&mock_env, &app, trader2_addr, 3)?;
+    assert_eq!(app.market()?.holder_count, 2);
+
+    Ok(())
+}
+
+#[test]
+fn failed_migrate_invalid_fee_shares() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    downgrade_to_state_version(&mock_env, &app, 1)?;
+    let err: FriendTechAppError = migrate_app(
+        &mock_env,
+        &app,
+        FriendTechAppMigrateMsg {
+            referral_fee_percentage: Some(60),
+            staking_fee_percentage: Some(50),
+            ..Default::default()
+        },
+    )
+    .unwrap_err()
+    .downcast()
+    .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::InvalidPercentage { percentage: 110 }
+    );
+
+    Ok(())
+}
+
+/// Trade on the app, rewrite its state into the layout of `state_version` and migrate it
+fn migrate_from_state_version(state_version: u32) -> anyhow::Result<TestEnv<MockBech32>> {
+    let env = TestEnv::setup()?;
+    let app = &env.app;
+    let mock_env = env.abs.environment();
+
+    let trader1_addr = &mock_env.addr_make(USER1);
+    let trader2_addr = &mock_env.addr_make(USER2);
+
+    buy_keys(&mock_env, app, trader1_addr, 4)?;
+    buy_keys(&mock_env, app, trader2_addr, 2)?;
+    sell_keys(&mock_env, app, trader1_addr, 1)?;
+    let market = app.market()?;
+
+    downgrade_to_state_version(&mock_env, app, state_version)?;
+    let res = migrate_app(&mock_env, app, FriendTechAppMigrateMsg::default())?;
+    let event = MigrateEvent::find(&res.events)?;
+    assert_eq!(event.from_state_version, state_version);
+    assert_eq!(event.to_state_version, CURRENT_STATE_VERSION);
+
+    // The keys and the curve come through every step untouched
+    let migrated_market = app.market()?;
+    assert_eq!(migrated_market.supply, market.supply);
+    assert_eq!(migrated_market.reserve, market.reserve);
+    assert_eq!(migrated_market.holder_count, market.holder_count);
+    assert_eq!(
+        app.holding(trader1_addr.to_string())?.amount,
+        Uint128::new(3)
+    );
+    sell_keys(&mock_env, app, trader2_addr, 1)?;
+
+    Ok(env)
+}
+
+#[test]
+fn successful_migrate_from_state_v2() -> anyhow::Result<()> {
+    let env = migrate_from_state_version(2)?;
+    let app = env.app;
+
+    assert!(!app.market()?.closed);
+    assert_eq!(
+        app.staking_info()?.staking_fee_percentage,
+        STAKING_FEE_PERCENTAGE
+    );
+    app.close_market()?;
+    assert!(app.market()?.closed);
+
+    Ok(())
+}
+
+#[test]
+fn successful_migrate_from_state_v3() -> anyhow::Result<()> {
+    let env = migrate_from_state_version(3)?;
+    let app = env.app;
+    let mock_env = env.abs.environment();
+
+    let seller_addr = &mock_env.addr_make(USER1);
+    // Fills pay no royalty until set by the migrate msg
+    assert_eq!(app.issuer()?.listing_royalty_percentage, 0);
+    app.call_as(seller_addr)
+        .list_keys(Uint128::one(), Uint128::new(1_000))?;
+    assert_eq!(app.listing(0)?.seller, seller_addr);
+
+    Ok(())
+}
+
+#[test]
+fn successful_migrate_from_state_v4() -> anyhow::Result<()> {
+    let env = migrate_from_state_version(4)?;
+    let app = env.app;
+
+    // The issuer fee collector becomes the only fee recipient
+    let issuer = app.issuer()?;
+    assert_eq!(
+        issuer.fee_recipients,
+        vec![FeeRecipient {
+            address: env.abs.sender(),
+            weight: 100,
+        }]
+    );
+    assert_eq!(
+        issuer.listing_royalty_percentage,
+        LISTING_ROYALTY_PERCENTAGE
+    );
+
+    Ok(())
+}
+
+#[test]
+fn successful_migrate_from_state_v5() -> anyhow::Result<()> {
+    let env = migrate_from_state_version(5)?;
+    let app = env.app;
+    let mock_env = env.abs.environment();
+
+    assert!(!app.issuer()?.accrue_fees);
+    buy_keys(&mock_env, &app, &mock_env.addr_make(USER3), 1)?;
+    assert!(app.accrued_fees()?.amount.is_zero());
+
+    Ok(())
+}
+
+#[test]
+fn successful_migrate_from_state_v6() -> anyhow::Result<()> {
+    let env = migrate_from_state_version(6)?;
+    let app = env.app;
+
+    assert!(!app.issuer()?.accounts_only);
+
+    Ok(())
+}
+
+#[test]
+fn successful_migrate_from_state_v7() -> anyhow::Result<()> {
+    let env = migrate_from_state_version(7)?;
+    let app = env.app;
+
+    assert_eq!(app.issuer()?.issuer_key_holder, env.abs.sender());
+
+    Ok(())
+}
+
+#[test]
+fn successful_migrate_from_state_v7_after_owner_change() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let previous_owner = &abs.sender();
+    let owner = &mock_env.addr_make(USER1);
+
+    downgrade_to_state_version(&mock_env, &app, 7)?;
+    transfer_account(&app, owner)?;
+
+    // The new owner holds no key, the previous one must be named as the holder of the issuer's key
+    let err: FriendTechAppError = migrate_app(&mock_env, &app, FriendTechAppMigrateMsg::default())
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::IssuerKeyNotHeld {
+            holder: owner.to_string()
+        }
+    );
+
+    migrate_app(
+        &mock_env,
+        &app,
+        FriendTechAppMigrateMsg {
+            issuer_key_holder: Some(previous_owner.to_string()),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(&app.issuer()?.issuer_key_holder, previous_owner);
+
+    // The issuer's key then follows the account
+    app.sync_owner()?;
+    assert!(app.holding(previous_owner.to_string())?.amount.is_zero());
+    assert_eq!(app.holding(owner.to_string())?.amount, Uint128::one());
+    assert_eq!(&app.issuer()?.issuer_key_holder, owner);
+
+    Ok(())
+}
+
+#[test]
+fn successful_migrate_from_state_v8() -> anyhow::Result<()> {
+    let env = migrate_from_state_version(8)?;
+    let app = env.app;
+    let mock_env = env.abs.environment();
+
+    let proposer_addr = &mock_env.addr_make(USER1);
+    app.update_governance(Some(GovernanceConfig {
+        quorum: Decimal::percent(50),
+        threshold: Decimal::percent(50),
+        voting_period: Duration::Height(10),
+    }))?;
+    let res =
+        app.call_as(proposer_addr)
+            .propose("Nothing".to_string(), vec![], "Noop".to_string())?;
+    assert_eq!(ProposeEvent::find(&res.events)?.proposal_id, 0);
+
+    Ok(())
+}
+
+#[test]
+fn successful_migrate_from_state_v9() -> anyhow::Result<()> {
+    let env = migrate_from_state_version(9)?;
+    let app = env.app;
+
+    assert!(app.market()?.burned.is_zero());
+
+    Ok(())
+}
+
+#[test]
+fn successful_migrate_from_state_v10() -> anyhow::Result<()> {
+    let env = migrate_from_state_version(10)?;
+    let app = env.app;
+    let mock_env = env.abs.environment();
+
+    let holder_addr = &mock_env.addr_make(USER1);
+    let until = Expiration::AtHeight(mock_env.block_info()?.height + 10);
+    app.call_as(holder_addr)
+        .lock(Uint128::one(), FRIEND_TECH_APP_ID.to_string(), until)?;
+    assert_eq!(app.key_lock(0)?.holder, holder_addr);
+
+    Ok(())
+}
+
+#[test]
+fn successful_migrate_from_state_v11_with_airdrop() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+    let fan1_addr = &mock.addr_make(USER1);
+    let fan2_addr = &mock.addr_make(USER2);
+    let fan1_leaf = airdrop_leaf(fan1_addr, 3);
+    let fan2_leaf = airdrop_leaf(fan2_addr, 2);
+    let merkle_root = airdrop_root(fan1_leaf, fan2_leaf);
+    let airdrop = AirdropParams {
+        merkle_root: merkle_root.clone(),
+        total_amount: Uint128::new(5),
+        expires: Expiration::AtHeight(mock.block_info()?.height + 100),
+    };
+    let env = TestEnv::setup_with(|msg| msg.airdrop = Some(airdrop))?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let funding_cost = app.airdrop()?.unwrap().funding_cost;
+    mock_env.set_balance(&abs.sender(), coins(funding_cost.u128(), DENOM))?;
+    app.fund_airdrop(&coins(funding_cost.u128(), DENOM))?;
+
+    // Airdrops of state v11 had no expiry
+    downgrade_to_state_version(&mock_env, &app, 11)?;
+    mock_env.app.borrow_mut().storage_mut().set(
+        &contract_storage_key(&app.address()?, b"AIRDROP"),
+        format!(
+            r#"{{"merkle_root":"{merkle_root}","total_amount":"5","claimed":"0","funded_reward_per_key":"0"}}"#
+        )
+        .as_bytes(),
+    );
+    migrate_app(&mock_env, &app, FriendTechAppMigrateMsg::default())?;
+
+    // The airdrop stays claimable for good
+    let airdrop = app.airdrop()?.unwrap();
+    assert_eq!(airdrop.expires, Expiration::Never {});
+    assert!(airdrop.reclaimed.is_zero());
+    assert!(airdrop.funded);
+    mock_env.wait_blocks(100)?;
+    app.call_as(fan1_addr)
+        .claim_airdrop(Uint128::new(3), vec![hex::encode(fan2_leaf)])?;
+    assert_eq!(app.holding(fan1_addr.to_string())?.amount, Uint128::new(3));
+
+    Ok(())
+}
+
+#[test]
+fn successful_portfolio() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let trader_addr = &mock_env.addr_make(USER1);
+    let whale_addr = &mock_env.addr_make(USER2);
+    let friend_addr = &mock_env.addr_make(USER3);
+
+    let portfolio = app.portfolio(trader_addr.to_string())?;
+    assert!(portfolio.holding.is_zero());
+    assert!(portfolio.liquidation_value.is_zero());
+    assert!(portfolio.unrealized_pnl.is_zero());
+
+    // The cost basis includes the fees, selling the keys right away returns their price less the fee
+    let buy_cost_resp = buy_keys(&mock_env, &app, trader_addr, 10)?;
+    let portfolio = app.portfolio(trader_addr.to_string())?;
+    assert_eq!(portfolio.holding, Uint128::new(10));
+    assert_eq!(portfolio.cost_basis, buy_cost_resp.total_cost);
+    assert_eq!(
+        portfolio.average_cost,
+        Decimal::from_ratio(buy_cost_resp.total_cost, 10u128)
+    );
+    let sell_cost_resp = app.sell_key_cost(Uint128::new(10), Some(trader_addr.to_string()))?;
+    assert_eq!(sell_cost_resp.price, buy_cost_resp.price);
+    let liquidation_value = sell_cost_resp.price - sell_cost_resp.issuer_fee;
+    assert_eq!(portfolio.liquidation_value, liquidation_value);
+    assert_eq!(
+        portfolio.unrealized_pnl,
+        Int128::try_from(liquidation_value)? - Int128::try_from(buy_cost_resp.total_cost)?
+    );
+    assert!(portfolio.unrealized_pnl < Int128::zero());
+
+    // Keys bought on top raise the price the trader's keys sell for
+    buy_keys(&mock_env, &app, whale_addr, 20)?;
+    let sell_cost_resp = app.sell_key_cost(Uint128::new(10), Some(trader_addr.to_string()))?;
+    assert!(sell_cost_resp.price > buy_cost_resp.price);
+    let liquidation_value = sell_cost_resp.price - sell_cost_resp.issuer_fee;
+    let portfolio = app.portfolio(trader_addr.to_string())?;
+    assert_eq!(portfolio.liquidation_value, liquidation_value);
+    assert!(portfolio.unrealized_pnl > Int128::zero());
+
+    // Keys leaving take their share of the cost basis, gifts come at no cost
+    sell_keys(&mock_env, &app, trader_addr, 4)?;
+    let cost_basis = buy_cost_resp.total_cost.multiply_ratio(6u128, 10u128);
+    assert_eq!(
+        app.portfolio(trader_addr.to_string())?.cost_basis,
+        cost_basis
+    );
+    app.call_as(trader_addr)
+        .transfer_keys(Uint128::new(2), friend_addr.to_string(), &[])?;
+    assert_eq!(
+        app.portfolio(trader_addr.to_string())?.cost_basis,
+        cost_basis.multiply_ratio(4u128, 6u128)
+    );
+    let portfolio = app.portfolio(friend_addr.to_string())?;
+    assert_eq!(portfolio.holding, Uint128::new(2));
+    assert!(portfolio.cost_basis.is_zero());
+    assert!(portfolio.unrealized_pnl > Int128::zero());
+
+    Ok(())
+}
+
+#[test]
+fn successful_has_minimum_keys() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let trader_addr = &mock_env.addr_make(USER1);
+
+    buy_keys(&mock_env, &app, trader_addr, 3)?;
+    // Staked keys still count towards the minimum
+    app.call_as(trader_addr).stake(Uint128::from(2u128))?;
+
+    assert_eq!(
+        app.has_minimum_keys(trader_addr.to_string(), Uint128::from(3u128))?,
+        HasMinimumKeysResponse {
+            has_minimum_keys: true,
+            amount: Uint128::from(3u128),
+        }
+    );
+    assert!(
+        !app.has_minimum_keys(trader_addr.to_string(), Uint128::from(4u128))?
+            .has_minimum_keys
+    );
+    assert!(
+        !app.has_minimum_keys(mock_env.addr_make(USER2).to_string(), Uint128::one())?
+            .has_minimum_keys
+    );
+
+    Ok(())
+}
+
+#[test]
+fn successful_close_market() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let trader_addr = &mock_env.addr_make(USER1);
+
+    buy_keys(&mock_env, &app, trader_addr, 3)?;
+    app.call_as(trader_addr).stake(Uint128::one())?;
+    let err: FriendTechAppError = app
+        .call_as(trader_addr)
+        .redeem()
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::MarketNotClosed {});
+
+    // Only the owner can close the market
+    let err: FriendTechAppError = app
+        .call_as(trader_addr)
+        .close_market()
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::Admin(AdminError::NotAdmin {}));
+
+    app.close_market()?;
+    assert!(app.market()?.closed);
+
+    let err: FriendTechAppError = app.close_market().unwrap_err().downcast().unwrap();
+    assert_eq!(err, FriendTechAppError::MarketClosed {});
+
+    // Trading and staking are halted
+    let err: FriendTechAppError = buy_keys(&mock_env, &app, trader_addr, 1)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::MarketClosed {});
+    let err: FriendTechAppError = sell_keys(&mock_env, &app, trader_addr, 1)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::MarketClosed {});
+    let err: FriendTechAppError = app
+        .call_as(trader_addr)
+        .stake(Uint128::one())
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::MarketClosed {});
+    let err: FriendTechAppError = app
+        .call_as(trader_addr)
+        .unstake(Uint128::one())
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::MarketClosed {});
+    mock_env.set_balance(trader_addr, coins(100, DENOM))?;
+    let err: FriendTechAppError = app
+        .call_as(trader_addr)
+        .deposit_rewards(&coins(100, DENOM))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::MarketClosed {});
+
+    Ok(())
+}
+
+#[test]
+fn successful_redeem() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let issuer_addr = &abs.sender();
+    let trader1_addr = &mock_env.addr_make(USER1);
+    let trader2_addr = &mock_env.addr_make(USER2);
+
+    buy_keys(&mock_env, &app, trader1_addr, 3)?;
+    buy_keys(&mock_env, &app, trader2_addr, 2)?;
+    // Staked and unbonding keys are redeemed too
+    app.call_as(trader1_addr).stake(Uint128::from(2u128))?;
+    app.call_as(trader1_addr).unstake(Uint128::one())?;
+    app.close_market()?;
+
+    let reserve = app.market()?.reserve;
+    assert_eq!(mock_env.query_balance(&app.address()?, DENOM)?, reserve);
+
+    app.call_as(trader1_addr).redeem()?;
+    let trader1_payout = reserve.multiply_ratio(3u128, 6u128);
+    assert_eq!(mock_env.query_balance(trader1_addr, DENOM)?, trader1_payout);
+    assert_eq!(
+        app.holding(trader1_addr.to_string())?.amount,
+        Uint128::zero()
+    );
+    let staked = app.staked(trader1_addr.to_string())?;
+    assert_eq!(staked.staked, Uint128::zero());
+    assert!(staked.unbonding.is_empty());
+    assert_eq!(app.staking_info()?.total_staked, Uint128::zero());
+
+    let err: FriendTechAppError = app
+        .call_as(trader1_addr)
+        .redeem()
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::NoKeysToRedeem {});
+
+    app.call_as(trader2_addr).redeem()?;
+    let trader2_payout = (reserve - trader1_payout).multiply_ratio(2u128, 3u128);
+    assert_eq!(mock_env.query_balance(trader2_addr, DENOM)?, trader2_payout);
+
+    // The issuer's last key is released and takes what is left of the reserve
+    let issuer_balance = mock_env.query_balance(issuer_addr, DENOM)?;
+    app.redeem()?;
+    assert_eq!(
+        mock_env.query_balance(issuer_addr, DENOM)?,
+        issuer_balance + reserve - trader1_payout - trader2_payout
+    );
+
+    let market = app.market()?;
+    assert_eq!(market.supply, Uint128::zero());
+    assert_eq!(market.reserve, Uint128::zero());
+    assert_eq!(market.holder_count, 0);
+    assert_eq!(
+        mock_env.query_balance(&app.address()?, DENOM)?,
+        Uint128::zero()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn successful_listings() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let seller_addr = &mock_env.addr_make(USER1);
+    let buyer_addr = &mock_env.addr_make(USER2);
+    let issuer = app.issuer()?;
+
+    buy_keys(&mock_env, &app, seller_addr, 4)?;
+    app.call_as(seller_addr)
+        .list_keys(Uint128::from(3u128), Uint128::from(1_000u128))?;
+    app.call_as(seller_addr)
+        .list_keys(Uint128::one(), Uint128::from(500u128))?;
+
+    // Listed keys are locked
+    let err: FriendTechAppError = sell_keys(&mock_env, &app, seller_addr, 1)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::InsufficientUnlockedKeys {
+            available: Uint128::zero(),
+            required: Uint128::one(),
+        }
+    );
+
+    let cheap_listing = ListingResponse {
+        listing_id: 1,
+        seller: seller_addr.clone(),
+        amount: Uint128::one(),
+        price_per_key: Uint128::from(500u128),
+    };
+    let listing = ListingResponse {
+        listing_id: 0,
+        seller: seller_addr.clone(),
+        amount: Uint128::from(3u128),
+        price_per_key: Uint128::from(1_000u128),
+    };
+    assert_eq!(
+        app.listings(None, None)?.listings,
+        vec![cheap_listing.clone(), listing.clone()]
+    );
+    assert_eq!(app.listings(None, Some(1))?.listings, vec![listing.clone()]);
+    assert_eq!(
+        app.listings_by_seller(seller_addr.to_string(), Some(1), None)?
+            .listings,
+        vec![cheap_listing.clone()]
+    );
+
+    let err: FriendTechAppError = app
+        .call_as(seller_addr)
+        .fill_listing(Uint128::one(), 0, &[])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::CannotFillOwnListing {});
+
+    // Fill part of the listing, the excess payment is refunded
+    mock_env.set_balance(buyer_addr, coins(5_000, DENOM))?;
+    let err: FriendTechAppError = app
+        .call_as(buyer_addr)
+        .fill_listing(Uint128::zero(), 0, &coins(5_000, DENOM))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::InvalidFillAmount {});
+    let err: FriendTechAppError = app
+        .call_as(buyer_addr)
+        .fill_listing(Uint128::from(4u128), 0, &coins(5_000, DENOM))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::CannotFillMoreThanListed {
+            listed: Uint128::from(3u128),
+            to_fill: Uint128::from(4u128),
+        }
+    );
+    let seller_balance = mock_env.query_balance(seller_addr, DENOM)?;
+    let collector_balance = mock_env.query_balance(&issuer.fee_recipients[0].address, DENOM)?;
+    app.call_as(buyer_addr)
+        .fill_listing(Uint128::from(2u128), 0, &coins(2_500, DENOM))?;
+    assert_eq!(
+        mock_env.query_balance(seller_addr, DENOM)?,
+        seller_balance + Uint128::from(1_800u128)
+    );
+    assert_eq!(
+        mock_env.query_balance(&issuer.fee_recipients[0].address, DENOM)?,
+        collector_balance + Uint128::from(200u128)
+    );
+    assert_eq!(
+        mock_env.query_balance(buyer_addr, DENOM)?,
+        Uint128::from(3_000u128)
+    );
+    assert_eq!(
+        app.holding(seller_addr.to_string())?.amount,
+        Uint128::from(2u128)
+    );
+    assert_eq!(
+        app.holding(buyer_addr.to_string())?.amount,
+        Uint128::from(2u128)
+    );
+    assert_eq!(app.listing(0)?.amount, Uint128::one());
+
+    // Only the seller can cancel, which unlocks the remaining keys
+    let err: FriendTechAppError = app
+        .call_as(buyer_addr)
+        .cancel_listing(0)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::NotListingSeller {});
+    app.call_as(seller_addr).cancel_listing(0)?;
+    assert_eq!(app.listings(None, None)?.listings, vec![cheap_listing]);
+    sell_keys(&mock_env, &app, seller_addr, 1)?;
+
+    // Filling the whole listing removes it
+    app.call_as(buyer_addr)
+        .fill_listing(Uint128::one(), 1, &coins(500, DENOM))?;
+    assert!(app.listings(None, None)?.listings.is_empty());
+    let err: FriendTechAppError = app
+        .call_as(buyer_addr)
+        .fill_listing(Uint128::one(), 1, &coins(500, DENOM))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::ListingNotFound { listing_id: 1 });
+
+    Ok(())
+}
+
+fn auction_params() -> AuctionParams {
+    AuctionParams {
+        amount: Uint128::from(10u128),
+        start_price: Uint128::from(100_000u128),
+        end_price: Uint128::from(30_000u128),
+        duration: 1_000,
+    }
+}
+
+#[test]
+fn failed_install_auction_end_price_too_low() -> anyhow::Result<()> {
+    let err = TestEnv::setup_with_auction(Some(AuctionParams {
+        end_price: Uint128::from(24_062u128),
+        ..auction_params()
+    }))
+    .err()
+    .unwrap();
+    // The curve price of the first 10 keys is 240_625
+    assert!(format!("{err:?}").contains(
+        &FriendTechAppError::AuctionEndPriceTooLow {
+            min_end_price: Uint128::from(24_063u128)
+        }
+        .to_string()
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn successful_auction() -> anyhow::Result<()> {
+    let env = TestEnv::setup_with_auction(Some(auction_params()))?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let bidder1_addr = &mock_env.addr_make(USER1);
+    let bidder2_addr = &mock_env.addr_make(USER2);
+    let issuer = app.issuer()?;
+
+    // Trading on the curve waits for the auction
+    let err: FriendTechAppError = buy_keys(&mock_env, &app, bidder1_addr, 1)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::AuctionInProgress {});
+    let err: FriendTechAppError = app.settle_auction().unwrap_err().downcast().unwrap();
+    assert_eq!(err, FriendTechAppError::AuctionInProgress {});
+
+    mock_env.set_balance(bidder1_addr, coins(400_000, DENOM))?;
+    app.call_as(bidder1_addr)
+        .bid(Uint128::from(4u128), &coins(400_000, DENOM))?;
+
+    // Halfway through the price has decayed to 65_000, the bid is cut down to the 6 keys left
+    mock_env.wait_seconds(500)?;
+    assert_eq!(
+        app.auction()?.unwrap().current_price,
+        Uint128::from(65_000u128)
+    );
+    mock_env.set_balance(bidder2_addr, coins(520_000, DENOM))?;
+    app.call_as(bidder2_addr)
+        .bid(Uint128::from(8u128), &coins(520_000, DENOM))?;
+    assert_eq!(
+        mock_env.query_balance(bidder2_addr, DENOM)?,
+        Uint128::from(130_000u128)
+    );
+    let auction = app.auction()?.unwrap();
+    assert_eq!(auction.sold, Uint128::from(10u128));
+    assert_eq!(auction.clearing_price, Some(Uint128::from(65_000u128)));
+    assert!(!auction.settled);
+
+    let err: FriendTechAppError = app
+        .call_as(bidder2_addr)
+        .bid(Uint128::one(), &coins(65_000, DENOM))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::AuctionEnded {});
+    let err: FriendTechAppError = app
+        .call_as(bidder1_addr)
+        .claim_auction()
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::AuctionInProgress {});
+
+    // The reserve gets the curve price of the keys sold, the issuer the rest
+    let collector_balance = mock_env.query_balance(&issuer.fee_recipients[0].address, DENOM)?;
+    app.call_as(bidder1_addr).settle_auction()?;
+    let market = app.market()?;
+    assert_eq!(market.supply, Uint128::from(11u128));
+    assert_eq!(market.reserve, Uint128::from(240_625u128));
+    assert_eq!(
+        mock_env.query_balance(&issuer.fee_recipients[0].address, DENOM)?,
+        collector_balance + Uint128::from(650_000u128 - 240_625u128)
+    );
+    let err: FriendTechAppError = app.settle_auction().unwrap_err().downcast().unwrap();
+    assert_eq!(err, FriendTechAppError::AuctionSettled {});
+
+    // Every bidder pays the clearing price
+    app.call_as(bidder1_addr).claim_auction()?;
+    assert_eq!(
+        mock_env.query_balance(bidder1_addr, DENOM)?,
+        Uint128::from(400_000u128 - 4 * 65_000u128)
+    );
+    assert_eq!(
+        app.holding(bidder1_addr.to_string())?.amount,
+        Uint128::from(4u128)
+    );
+    let err: FriendTechAppError = app
+        .call_as(bidder1_addr)
+        .claim_auction()
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::NoBidToClaim {});
+
+    // Keys not claimed yet still earn the rewards deposited after settlement
+    mock_env.set_balance(bidder1_addr, coins(1_100, DENOM))?;
+    app.call_as(bidder1_addr)
+        .deposit_rewards(&coins(1_100, DENOM))?;
+    app.call_as(bidder2_addr).claim_auction()?;
+    assert_eq!(
+        app.holding(bidder2_addr.to_string())?.amount,
+        Uint128::from(6u128)
+    );
+    assert_eq!(
+        app.pending_rewards(bidder2_addr.to_string())?.amount,
+        Uint128::from(600u128)
+    );
+
+    // Trading continues on the curve
+    buy_keys(&mock_env, &app, bidder1_addr, 1)?;
+    sell_keys(&mock_env, &app, bidder2_addr, 2)?;
+    assert_eq!(app.market()?.supply, Uint128::from(10u128));
+
+    Ok(())
+}
+
+#[test]
+fn successful_auction_settled_after_end() -> anyhow::Result<()> {
+    let env = TestEnv::setup_with_auction(Some(auction_params()))?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let bidder_addr = &mock_env.addr_make(USER1);
+
+    mock_env.set_balance(bidder_addr, coins(200_000, DENOM))?;
+    app.call_as(bidder_addr)
+        .bid(Uint128::from(2u128), &coins(200_000, DENOM))?;
+
+    // Keys left unsold are never issued and the bidders pay the end price
+    mock_env.wait_seconds(1_000)?;
+    app.settle_auction()?;
+    let auction = app.auction()?.unwrap();
+    assert_eq!(auction.clearing_price, Some(Uint128::from(30_000u128)));
+    assert!(auction.settled);
+    assert_eq!(app.market()?.supply, Uint128::from(3u128));
+
+    app.call_as(bidder_addr).claim_auction()?;
+    assert_eq!(
+        mock_env.query_balance(bidder_addr, DENOM)?,
+        Uint128::from(200_000u128 - 2 * 30_000u128)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn successful_update_fee_recipients() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let trader_addr = &mock_env.addr_make(USER1);
+    let recipient1_addr = mock_env.addr_make("recipient1");
+    let recipient2_addr = mock_env.addr_make("recipient2");
+    let fee_recipients = vec![
+        UncheckedFeeRecipient {
+            address: recipient1_addr.to_string(),
+            weight: 70,
+        },
+        UncheckedFeeRecipient {
+            address: recipient2_addr.to_string(),
+            weight: 30,
+        },
+    ];
+
+    // Only the owner can update the fee recipients
+    let err: FriendTechAppError = app
+        .call_as(trader_addr)
+        .update_fee_recipients(fee_recipients.clone())
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::Admin(AdminError::NotAdmin {}));
+
+    // The weights must add up to 100
+    let err: FriendTechAppError = app
+        .update_fee_recipients(vec![UncheckedFeeRecipient {
+            address: recipient1_addr.to_string(),
+            weight: 90,
+        }])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::InvalidFeeRecipientWeights {
+            total: Uint128::new(90)
+        }
+    );
+    // Weights that would wrap around to 100 in a u32 are rejected
+    let err: FriendTechAppError = app
+        .update_fee_recipients(vec![
+            UncheckedFeeRecipient {
+                address: recipient1_addr.to_string(),
+                weight: u32::MAX,
+            },
+            UncheckedFeeRecipient {
+                address: recipient2_addr.to_string(),
+                weight: 101,
+            },
+        ])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::InvalidFeeRecipientWeights {
+            total: Uint128::from(u32::MAX) + Uint128::new(101)
+        }
+    );
+
+    app.update_fee_recipients(fee_recipients)?;
+    assert_eq!(
+        app.issuer()?.fee_recipients,
+        vec![
+            FeeRecipient {
+                address: recipient1_addr.clone(),
+                weight: 70,
+            },
+            FeeRecipient {
+                address: recipient2_addr.clone(),
+                weight: 30,
+            },
+        ]
+    );
+
+    // The issuer fee is split by weight, the rounding dust goes to the first recipient
+    let buy_cost_resp = buy_keys(&mock_env, &app, trader_addr, 5)?;
+    let recipient2_share = buy_cost_resp.issuer_fee.multiply_ratio(30u128, 100u128);
+    assert_eq!(
+        mock_env.query_balance(&recipient1_addr, DENOM)?,
+        buy_cost_resp.issuer_fee - recipient2_share
+    );
+    assert_eq!(
+        mock_env.query_balance(&recipient2_addr, DENOM)?,
+        recipient2_share
+    );
+
+    Ok(())
+}
+
+/// Leaf of the airdrop merkle tree granting amount keys to the address
+fn airdrop_leaf(address: &Addr, amount: u128) -> [u8; 32] {
+    Sha256::digest(format!("friend_tech_airdrop:{address}:{amount}").as_bytes()).into()
+}
+
+/// Root of the merkle tree of two leaves
+fn airdrop_root(leaf1: [u8; 32], leaf2: [u8; 32]) -> String {
+    let (first, second) = if leaf1 <= leaf2 {
+        (leaf1, leaf2)
+    } else {
+        (leaf2, leaf1)
+    };
+    hex::encode(Sha256::digest([first, second].concat()))
+}
+
+#[test]
+fn successful_airdrop() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+    let fan1_addr = &mock.addr_make(USER1);
+    let fan2_addr = &mock.addr_make(USER2);
+    let fan1_leaf = airdrop_leaf(fan1_addr, 3);
+    let fan2_leaf = airdrop_leaf(fan2_addr, 2);
+    let merkle_root = airdrop_root(fan1_leaf, fan2_leaf);
+    let expires = Expiration::AtHeight(mock.block_info()?.height + 100);
+    let airdrop = AirdropParams {
+        merkle_root: merkle_root.clone(),
+        total_amount: Uint128::new(5),
+        expires,
+    };
+    let env = TestEnv::setup_with(|msg| msg.airdrop = Some(airdrop))?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let trader_addr = &mock_env.addr_make(USER3);
+    let airdrop = app.airdrop()?.unwrap();
+    assert_eq!(airdrop.merkle_root, merkle_root);
+    assert_eq!(airdrop.expires, expires);
+    assert!(!airdrop.funded);
+
+    // Trading and claims wait until the airdrop is funded
+    let err: FriendTechAppError = buy_keys(&mock_env, &app, trader_addr, 1)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::AirdropNotFunded {});
+    let err: FriendTechAppError = app
+        .call_as(fan1_addr)
+        .claim_airdrop(Uint128::new(3), vec![hex::encode(fan2_leaf)])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::AirdropNotFunded {});
+
+    // The owner pays the curve price of the airdropped keys into the reserve
+    let funding_cost = airdrop.funding_cost;
+    assert_eq!(funding_cost, app.buy_key_cost(Uint128::new(5), None)?.price);
+    mock_env.set_balance(&abs.sender(), coins(funding_cost.u128() + 100, DENOM))?;
+    let err: FriendTechAppError = app
+        .fund_airdrop(&coins(funding_cost.u128() - 1, DENOM))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::InsufficientFunds {
+            required: funding_cost,
+            paid: funding_cost - Uint128::one(),
+        }
+    );
+    app.fund_airdrop(&coins(funding_cost.u128() + 100, DENOM))?;
+    assert_eq!(
+        mock_env.query_balance(&abs.sender(), DENOM)?,
+        Uint128::new(100)
+    );
+    let market = app.market()?;
+    assert_eq!(market.supply, Uint128::new(6));
+    assert_eq!(market.reserve, funding_cost);
+    let err: FriendTechAppError = app.fund_airdrop(&[]).unwrap_err().downcast().unwrap();
+    assert_eq!(err, FriendTechAppError::AirdropFunded {});
+
+    // Fans claim their keys with a proof, once
+    app.call_as(fan1_addr)
+        .claim_airdrop(Uint128::new(3), vec![hex::encode(fan2_leaf)])?;
+    assert_eq!(app.holding(fan1_addr.to_string())?.amount, Uint128::new(3));
+    assert_eq!(
+        app.airdrop_claim(fan1_addr.to_string())?.claimed,
+        Uint128::new(3)
+    );
+    let err: FriendTechAppError = app
+        .call_as(fan1_addr)
+        .claim_airdrop(Uint128::new(3), vec![hex::encode(fan2_leaf)])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::AirdropAlreadyClaimed {});
+    let err: FriendTechAppError = app
+        .call_as(fan2_addr)
+        .claim_airdrop(Uint128::new(3), vec![hex::encode(fan1_leaf)])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::InvalidMerkleProof {});
+    // Leaves without the domain and the delimiters are not part of the tree
+    let untagged_root = airdrop_root(
+        Sha256::digest(format!("{fan1_addr}3").as_bytes()).into(),
+        fan2_leaf,
+    );
+    assert_ne!(untagged_root, merkle_root);
+    app.call_as(fan2_addr)
+        .claim_airdrop(Uint128::new(2), vec![hex::encode(fan1_leaf)])?;
+    assert_eq!(app.airdrop()?.unwrap().claimed, Uint128::new(5));
+
+    // Trading is open and the reserve covers the airdropped keys
+    buy_keys(&mock_env, &app, trader_addr, 2)?;
+    sell_keys(&mock_env, &app, fan1_addr, 3)?;
+    let err: FriendTechAppError = app
+        .set_airdrop(AirdropParams {
+            merkle_root,
+            total_amount: Uint128::one(),
+            expires,
+        })
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::TradingStarted {});
+
+    Ok(())
+}
+
+#[test]
+fn successful_reclaim_airdrop() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+    let fan1_addr = &mock.addr_make(USER1);
+    let fan2_addr = &mock.addr_make(USER2);
+    let fan1_leaf = airdrop_leaf(fan1_addr, 3);
+    let fan2_leaf = airdrop_leaf(fan2_addr, 2);
+    let expires = Expiration::AtHeight(mock.block_info()?.height + 100);
+    let airdrop = AirdropParams {
+        merkle_root: airdrop_root(fan1_leaf, fan2_leaf),
+        total_amount: Uint128::new(5),
+        expires,
+    };
+    let env = TestEnv::setup_with(|msg| msg.airdrop = Some(airdrop))?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let owner_addr = &abs.sender();
+    let trader_addr = &mock_env.addr_make(USER3);
+
+    let err: FriendTechAppError = app.reclaim_airdrop().unwrap_err().downcast().unwrap();
+    assert_eq!(err, FriendTechAppError::AirdropNotFunded {});
+    let funding_cost = app.airdrop()?.unwrap().funding_cost;
+    mock_env.set_balance(owner_addr, coins(funding_cost.u128(), DENOM))?;
+    app.fund_airdrop(&coins(funding_cost.u128(), DENOM))?;
+    app.call_as(fan1_addr)
+        .claim_airdrop(Uint128::new(3), vec![hex::encode(fan2_leaf)])?;
+    buy_keys(&mock_env, &app, trader_addr, 2)?;
+    // 100 per key of the supply of 8, the unclaimed keys included
+    mock_env.set_balance(trader_addr, coins(800, DENOM))?;
+    app.call_as(trader_addr)
+        .deposit_rewards(&coins(800, DENOM))?;
+
+    // The unclaimed keys stay claimable until the expiry
+    let err: FriendTechAppError = app.reclaim_airdrop().unwrap_err().downcast().unwrap();
+    assert_eq!(err, FriendTechAppError::AirdropNotExpired { expires });
+    mock_env.wait_blocks(100)?;
+    let err: FriendTechAppError = app
+        .call_as(fan2_addr)
+        .claim_airdrop(Uint128::new(2), vec![hex::encode(fan1_leaf)])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::AirdropExpired {});
+    let err: FriendTechAppError = app
+        .call_as(trader_addr)
+        .reclaim_airdrop()
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::Admin(AdminError::NotAdmin {}));
+
+    // The owner gets the top of the curve back for the burned keys, with their rewards
+    let market = app.market()?;
+    assert_eq!(market.supply, Uint128::new(8));
+    // Curve price of the keys 6 and 7
+    let price = Uint128::new((36 + 49) * 1_000_000 / 1_600);
+    let owner_balance = mock_env.query_balance(owner_addr, DENOM)?;
+    app.reclaim_airdrop()?;
+    assert_eq!(
+        mock_env.query_balance(owner_addr, DENOM)?,
+        owner_balance + price + Uint128::new(200)
+    );
+    let reclaimed_market = app.market()?;
+    assert_eq!(reclaimed_market.supply, market.supply - Uint128::new(2));
+    assert_eq!(reclaimed_market.reserve, market.reserve - price);
+    assert_eq!(reclaimed_market.holder_count, market.holder_count);
+    let airdrop = app.airdrop()?.unwrap();
+    assert_eq!(airdrop.claimed, Uint128::new(3));
+    assert_eq!(airdrop.reclaimed, Uint128::new(2));
+    let err: FriendTechAppError = app.reclaim_airdrop().unwrap_err().downcast().unwrap();
+    assert_eq!(err, FriendTechAppError::NothingToReclaim {});
+
+    // The holders can still sell every key left on the curve
+    sell_keys(&mock_env, &app, trader_addr, 2)?;
+    sell_keys(&mock_env, &app, fan1_addr, 3)?;
+    assert!(app.market()?.reserve.is_zero());
+
+    Ok(())
+}
+
+#[test]
+fn successful_reclaim_airdrop_after_close() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+    let fan1_addr = &mock.addr_make(USER1);
+    let fan2_addr = &mock.addr_make(USER2);
+    let fan1_leaf = airdrop_leaf(fan1_addr, 3);
+    let fan2_leaf = airdrop_leaf(fan2_addr, 2);
+    let airdrop = AirdropParams {
+        merkle_root: airdrop_root(fan1_leaf, fan2_leaf),
+        total_amount: Uint128::new(5),
+        expires: Expiration::AtHeight(mock.block_info()?.height + 100),
+    };
+    let env = TestEnv::setup_with(|msg| msg.airdrop = Some(airdrop))?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let owner_addr = &abs.sender();
+    let trader_addr = &mock_env.addr_make(USER3);
+
+    let funding_cost = app.airdrop()?.unwrap().funding_cost;
+    mock_env.set_balance(owner_addr, coins(funding_cost.u128(), DENOM))?;
+    app.fund_airdrop(&coins(funding_cost.u128(), DENOM))?;
+    app.call_as(fan1_addr)
+        .claim_airdrop(Uint128::new(3), vec![hex::encode(fan2_leaf)])?;
+    buy_keys(&mock_env, &app, trader_addr, 2)?;
+    mock_env.wait_blocks(100)?;
+    app.close_market()?;
+
+    // The unclaimed keys are redeemed for their share of the reserve, not priced on the curve
+    let market = app.market()?;
+    assert_eq!(market.supply, Uint128::new(8));
+    let price = market.reserve.multiply_ratio(2u128, 8u128);
+    let owner_balance = mock_env.query_balance(owner_addr, DENOM)?;
+    let resp = app.reclaim_airdrop()?;
+    let event = ReclaimAirdropEvent::find(&resp.events)?;
+    assert_eq!(event.price, price);
+    assert_eq!(event.supply, Uint128::new(6));
+    assert_eq!(
+        mock_env.query_balance(owner_addr, DENOM)?,
+        owner_balance + price
+    );
+    let reclaimed_market = app.market()?;
+    assert_eq!(reclaimed_market.reserve, market.reserve - price);
+
+    // The holders left redeem the same share per key
+    let reserve = reclaimed_market.reserve;
+    app.call_as(trader_addr).redeem()?;
+    assert_eq!(
+        mock_env.query_balance(trader_addr, DENOM)?,
+        reserve.multiply_ratio(2u128, 6u128)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn successful_claim_fees() -> anyhow::Result<()> {
+    let env = TestEnv::setup_with_fee_accrual()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let trader_addr = &mock_env.addr_make(USER1);
+    let fee_recipient = &abs.sender();
+    let claimer_addr = &mock_env.addr_make(USER2);
+    assert!(app.issuer()?.accrue_fees);
+
+    let err: FriendTechAppError = app.claim_fees(None).unwrap_err().downcast().unwrap();
+    assert_eq!(err, FriendTechAppError::NoFeesToClaim {});
+
+    // The issuer fee stays in the contract instead of being sent on every trade
+    let recipient_balance = mock_env.query_balance(fee_recipient, DENOM)?;
+    let buy_cost_resp = buy_keys(&mock_env, &app, trader_addr, 5)?;
+    let sell_cost_resp = sell_keys(&mock_env, &app, trader_addr, 2)?;
+    // Nobody staked so the stakers get no share of the issuer fee
+    let accrued = buy_cost_resp.issuer_fee + sell_cost_resp.issuer_fee;
+    assert_eq!(
+        mock_env.query_balance(fee_recipient, DENOM)?,
+        recipient_balance
+    );
+    assert_eq!(app.accrued_fees()?.amount, accrued);
+
+    // Only the owner can claim the fees
+    let err: FriendTechAppError = app
+        .call_as(trader_addr)
+        .claim_fees(None)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::Admin(AdminError::NotAdmin {}));
+
+    app.claim_fees(Some(claimer_addr.to_string()))?;
+    assert_eq!(mock_env.query_balance(claimer_addr, DENOM)?, accrued);
+    assert!(app.accrued_fees()?.amount.is_zero());
+
+    // Without a destination the fees are split among the fee recipients
+    let buy_cost_resp = buy_keys(&mock_env, &app, trader_addr, 1)?;
+    let accrued = buy_cost_resp.issuer_fee;
+    app.claim_fees(None)?;
+    assert_eq!(
+        mock_env.query_balance(fee_recipient, DENOM)?,
+        recipient_balance + accrued
+    );
+
+    Ok(())
+}
+
+#[test]
+fn successful_buyback_and_burn() -> anyhow::Result<()> {
+    let env = TestEnv::setup_with_fee_accrual()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let trader_addr = &mock_env.addr_make(USER1);
+    // Accrue the fees of a buy and of sales taking the supply back down the curve
+    let mut accrued = buy_keys(&mock_env, &app, trader_addr, 20)?.issuer_fee;
+    for amount in [10, 5, 2] {
+        accrued += sell_keys(&mock_env, &app, trader_addr, amount)?.issuer_fee;
+    }
+    assert_eq!(app.accrued_fees()?.amount, accrued);
+
+    let err: FriendTechAppError = app
+        .call_as(trader_addr)
+        .buyback_and_burn(accrued)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::Admin(AdminError::NotAdmin {}));
+    let err: FriendTechAppError = app
+        .buyback_and_burn(accrued + Uint128::one())
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::InsufficientAccruedFees {
+            accrued,
+            budget: accrued + Uint128::one(),
+        }
+    );
+    let err: FriendTechAppError = app
+        .buyback_and_burn(Uint128::one())
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::BudgetTooLow {
+            budget: Uint128::one()
+        }
+    );
+
+    // The buyback pays the curve price alone, the rest of the budget stays accrued
+    let mut amount = Uint128::zero();
+    while app.buy_key_cost(amount + Uint128::one(), None)?.price <= accrued {
+        amount += Uint128::one();
+    }
+    assert!(amount > Uint128::one());
+    let price = app.buy_key_cost(amount, None)?.price;
+    let market = app.market()?;
+    let pending = app.pending_rewards(trader_addr.to_string())?.amount;
+    let resp = app.buyback_and_burn(accrued)?;
+    let event = BuybackAndBurnEvent::find(&resp.events)?;
+    assert_eq!(event.amount, amount);
+    assert_eq!(event.price, price);
+    assert_eq!(app.accrued_fees()?.amount, accrued - price);
+
+    // The keys are burned as they are bought, the curve is untouched and the holders share the price
+    let new_market = app.market()?;
+    assert_eq!(new_market.supply, market.supply);
+    assert_eq!(new_market.spot_price, market.spot_price);
+    assert_eq!(new_market.reserve, market.reserve);
+    assert_eq!(new_market.volume_24h, market.volume_24h);
+    assert_eq!(new_market.burned, amount);
+    assert_eq!(new_market.holder_count, market.holder_count);
+    assert_eq!(
+        app.holding(trader_addr.to_string())?.amount,
+        Uint128::new(3)
+    );
+    assert_eq!(
+        app.pending_rewards(trader_addr.to_string())?.amount,
+        pending + price.multiply_ratio(3u128, market.supply)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn successful_accounts_only() -> anyhow::Result<()> {
+    let env = TestEnv::setup_with(|msg| msg.accounts_only = true)?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let user_addr = &mock_env.addr_make(USER1);
+    assert!(app.issuer()?.accounts_only);
+
+    // The issuer's key is held by the account of the app, holders are listed by account id
+    assert_eq!(
+        app.holders(None, None)?,
+        HoldersResponse {
+            holders: vec![],
+            accounts: vec![app.account().id()?],
+        }
+    );
+
+    let err: FriendTechAppError = buy_keys(&mock_env, &app, user_addr, 1)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::NotAbstractAccount {
+            address: user_addr.to_string()
+        }
+    );
+
+    let account = abs.account_builder().build()?;
+    let proxy = &account.proxy()?;
+    buy_keys(&mock_env, &app, proxy, 3)?;
+    assert_eq!(app.holding(proxy.to_string())?.amount, Uint128::new(3));
+
+    assert_eq!(app.market()?.holder_count, 2);
+    assert_eq!(
+        app.holders(None, None)?,
+        HoldersResponse {
+            holders: vec![],
+            accounts: vec![app.account().id()?, account.id()?],
+        }
+    );
+    assert_eq!(
+        app.holders(None, Some(app.account().id()?.to_string()))?
+            .accounts,
+        vec![account.id()?]
+    );
+
+    // Accounts cannot buy keys for plain addresses either
+    let buy_cost_resp = app.buy_key_cost(Uint128::one(), None)?;
+    mock_env.set_balance(proxy, coins(buy_cost_resp.total_cost.u128(), DENOM))?;
+    let err: FriendTechAppError = app
+        .call_as(proxy)
+        .buy_key(
+            Uint128::one(),
+            Some(user_addr.to_string()),
+            None,
+            &coins(buy_cost_resp.total_cost.u128(), DENOM),
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::NotAbstractAccount {
+            address: user_addr.to_string()
+        }
+    );
+
+    sell_keys(&mock_env, &app, proxy, 1)?;
+    assert_eq!(app.holding(proxy.to_string())?.amount, Uint128::new(2));
+
+    Ok(())
+}
+
+/// Hand the account the app is installed on over to owner
+fn transfer_account(
+    app: &Application<MockBech32, Friendtech<MockBech32>>,
+    owner: &Addr,
+) -> anyhow::Result<()> {
+    let manager = &app.account().as_ref().manager;
+    manager.execute(
+        &manager::ExecuteMsg::ProposeOwner {
+            owner: GovernanceDetails::Monarchy {
+                monarch: owner.to_string(),
+            },
+        },
+        None,
+    )?;
+    manager.call_as(owner).execute(
+        &from_json::<manager::ExecuteMsg>(r#"{"update_ownership":"accept_ownership"}"#)?,
+        None,
+    )?;
+    Ok(())
+}
+
+#[test]
+fn successful_sync_owner() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let previous_owner = &abs.sender();
+    let owner = &mock_env.addr_make(USER1);
+    let trader_addr = &mock_env.addr_make(USER2);
+
+    buy_keys(&mock_env, &app, trader_addr, 5)?;
+
+    let err: FriendTechAppError = app.sync_owner().unwrap_err().downcast().unwrap();
+    assert_eq!(err, FriendTechAppError::OwnerAlreadySynced {});
+
+    // The issuer's key can be neither staked nor listed
+    let err: FriendTechAppError = app.stake(Uint128::one()).unwrap_err().downcast().unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::InsufficientUnlockedKeys {
+            available: Uint128::zero(),
+            required: Uint128::one(),
+        }
+    );
+
+    transfer_account(&app, owner)?;
+
+    // The previous owner still cannot sell the issuer's key
+    let err: FriendTechAppError = sell_keys(&mock_env, &app, previous_owner, 1)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::IssuerCannotSellLastKey {});
+
+    let res = app.call_as(trader_addr).sync_owner()?;
+    assert_eq!(
+        SyncOwnerEvent::find(&res.events)?,
+        SyncOwnerEvent {
+            previous_owner: previous_owner.clone(),
+            owner: owner.clone(),
+            amount: Uint128::one(),
+        }
+    );
+    assert!(app.holding(previous_owner.to_string())?.amount.is_zero());
+    assert_eq!(app.holding(owner.to_string())?.amount, Uint128::one());
+    let issuer = app.issuer()?;
+    assert_eq!(&issuer.issuer_key_holder, owner);
+    assert_eq!(
+        issuer.fee_recipients,
+        vec![FeeRecipient {
+            address: owner.clone(),
+            weight: 100,
+        }]
+    );
+
+    let err: FriendTechAppError = app.sync_owner().unwrap_err().downcast().unwrap();
+    assert_eq!(err, FriendTechAppError::OwnerAlreadySynced {});
+
+    // The new owner cannot sell the issuer's key either
+    buy_keys(&mock_env, &app, owner, 2)?;
+    let err: FriendTechAppError = sell_keys(&mock_env, &app, owner, 3)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::IssuerCannotSellLastKey {});
+    sell_keys(&mock_env, &app, owner, 2)?;
+
+    Ok(())
+}
+
+#[test]
+fn successful_governance() -> anyhow::Result<()> {
+    let governance = GovernanceConfig {
+        quorum: Decimal::percent(50),
+        threshold: Decimal::percent(50),
+        voting_period: Duration::Height(10),
+    };
+    let env = TestEnv::setup_with(|msg| msg.governance = Some(governance.clone()))?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let owner = &abs.sender();
+    let proposer_addr = &mock_env.addr_make(USER1);
+    let late_buyer_addr = &mock_env.addr_make(USER2);
+    let payee_addr = &mock_env.addr_make(USER3);
+    assert_eq!(app.governance()?.governance, Some(governance));
+
+    let proxy = app.account().proxy()?;
+    mock_env.set_balance(&proxy, coins(1_000, DENOM))?;
+    let msgs = vec![CosmosMsg::Bank(BankMsg::Send {
+        to_address: payee_addr.to_string(),
+        amount: coins(1_000, DENOM),
+    })];
+
+    let err: FriendTechAppError = app
+        .call_as(proposer_addr)
+        .propose("Pay the payee".to_string(), msgs.clone(), "Pay".to_string())
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::NoVotingPower {});
+
+    buy_keys(&mock_env, &app, proposer_addr, 3)?;
+    mock_env.wait_blocks(1)?;
+
+    let res = app.call_as(proposer_addr).propose(
+        "Pay the payee".to_string(),
+        msgs.clone(),
+        "Pay".to_string(),
+    )?;
+    let proposal_id = ProposeEvent::find(&res.events)?.proposal_id;
+    // Keys bought once the proposal is created carry no vote on it
+    buy_keys(&mock_env, &app, late_buyer_addr, 5)?;
+
+    let proposal = app.proposal(proposal_id)?;
+    assert_eq!(proposal.total_power, Uint128::new(4));
+    assert_eq!(proposal.status, ProposalStatus::Open);
+
+    let err: FriendTechAppError = app
+        .call_as(late_buyer_addr)
+        .vote(proposal_id, VoteOption::Yes)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::NoVotingPower {});
+
+    app.call_as(proposer_addr)
+        .vote(proposal_id, VoteOption::Yes)?;
+    let err: FriendTechAppError = app
+        .call_as(proposer_addr)
+        .vote(proposal_id, VoteOption::No)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::AlreadyVoted { proposal_id });
+    let res = app.vote(proposal_id, VoteOption::No)?;
+    assert_eq!(
+        VoteEvent::find(&res.events)?,
+        VoteEvent {
+            proposal_id,
+            voter: owner.clone(),
+            vote: VoteOption::No,
+            weight: Uint128::one(),
+        }
+    );
+    assert_eq!(
+        app.ballot(proposal_id, proposer_addr.to_string())?.ballot,
+        Some(Ballot {
+            vote: VoteOption::Yes,
+            weight: Uint128::new(3),
+        })
+    );
+
+    let err: FriendTechAppError = app
+        .execute_proposal(proposal_id)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::ProposalNotPassed { proposal_id });
+
+    mock_env.wait_blocks(10)?;
+    let err: FriendTechAppError = app
+        .vote(proposal_id, VoteOption::Yes)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::VotingClosed { proposal_id });
+    assert_eq!(app.proposal(proposal_id)?.status, ProposalStatus::Passed);
+
+    // Paying out of the account needs the approval of the owner
+    let proposal = app.proposal(proposal_id)?;
+    assert!(proposal.needs_owner_approval);
+    assert!(!proposal.owner_approved);
+    let err: FriendTechAppError = app
+        .call_as(late_buyer_addr)
+        .execute_proposal(proposal_id)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::ProposalNotApproved { proposal_id });
+    let err: FriendTechAppError = app
+        .call_as(proposer_addr)
+        .approve_proposal(proposal_id)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::Admin(AdminError::NotAdmin {}));
+    let err: FriendTechAppError = app
+        .approve_proposal(proposal_id + 1)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::ProposalNotFound {
+            proposal_id: proposal_id + 1
+        }
+    );
+    app.approve_proposal(proposal_id)?;
+    assert!(app.proposal(proposal_id)?.owner_approved);
+
+    app.call_as(late_buyer_addr).execute_proposal(proposal_id)?;
+    assert_eq!(mock_env.query_balance(payee_addr, DENOM)?.u128(), 1_000);
+    assert_eq!(app.proposal(proposal_id)?.status, ProposalStatus::Executed);
+    let err: FriendTechAppError = app
+        .execute_proposal(proposal_id)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::ProposalNotPassed { proposal_id });
+
+    // Without enough votes the proposal is rejected
+    let res = app.call_as(proposer_addr).propose(
+        "Pay again".to_string(),
+        msgs.clone(),
+        "Pay".to_string(),
+    )?;
+    let proposal_id = ProposeEvent::find(&res.events)?.proposal_id;
+    app.vote(proposal_id, VoteOption::Yes)?;
+    mock_env.wait_blocks(10)?;
+    assert_eq!(app.proposal(proposal_id)?.status, ProposalStatus::Rejected);
+    assert_eq!(app.proposals(None, None)?.proposals.len(), 2);
+
+    let err: FriendTechAppError = app
+        .call_as(proposer_addr)
+        .update_governance(None)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::Admin(AdminError::NotAdmin {}));
+    app.update_governance(None)?;
+    let err: FriendTechAppError = app
+        .call_as(proposer_addr)
+        .propose("Pay once more".to_string(), msgs, "Pay".to_string())
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::GovernanceDisabled {});
+
+    Ok(())
+}
+
+#[test]
+fn successful_proposal_owner_approval() -> anyhow::Result<()> {
+    let env = TestEnv::setup_with(|msg| {
+        msg.governance = Some(GovernanceConfig {
+            quorum: Decimal::percent(50),
+            threshold: Decimal::percent(50),
+            voting_period: Duration::Height(10),
+        })
+    })?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let proposer_addr = &mock_env.addr_make(USER1);
+    let external_addr = &mock_env.addr_make("external");
+    buy_keys(&mock_env, &app, proposer_addr, 3)?;
+    mock_env.wait_blocks(1)?;
+
+    let execute = |contract_addr: &Addr, funds: Vec<Coin>| -> anyhow::Result<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_json_binary(&Empty {})?,
+            funds,
+        }))
+    };
+    let needs_owner_approval = |msg: CosmosMsg| -> anyhow::Result<bool> {
+        let res =
+            app.call_as(proposer_addr)
+                .propose("Run".to_string(), vec![msg], "Run".to_string())?;
+        let proposal_id = ProposeEvent::find(&res.events)?.proposal_id;
+        Ok(app.proposal(proposal_id)?.needs_owner_approval)
+    };
+
+    // Every call needs the owner until its contract is exempted
+    assert!(needs_owner_approval(execute(external_addr, vec![])?)?);
+    let err: FriendTechAppError = app
+        .call_as(proposer_addr)
+        .update_approval_exemptions(vec![external_addr.to_string()], vec![])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::Admin(AdminError::NotAdmin {}));
+    app.update_approval_exemptions(
+        vec![
+            external_addr.to_string(),
+            app.account().proxy()?.to_string(),
+        ],
+        vec![],
+    )?;
+    assert_eq!(app.approval_exemptions(None, None)?.addresses.len(), 2);
+    assert!(!needs_owner_approval(execute(external_addr, vec![])?)?);
+    // Moving the funds of the account or calling the account and its modules needs the owner anyway
+    assert!(needs_owner_approval(execute(
+        external_addr,
+        coins(1, DENOM)
+    )?)?);
+    assert!(needs_owner_approval(execute(&app.address()?, vec![])?)?);
+    assert!(needs_owner_approval(execute(
+        &app.account().manager()?,
+        vec![]
+    )?)?);
+    assert!(needs_owner_approval(execute(
+        &app.account().proxy()?,
+        vec![]
+    )?)?);
+    assert!(needs_owner_approval(CosmosMsg::Bank(BankMsg::Burn {
+        amount: coins(1, DENOM),
+    }))?);
+
+    app.update_approval_exemptions(vec![], vec![external_addr.to_string()])?;
+    assert!(needs_owner_approval(execute(external_addr, vec![])?)?);
+
+    Ok(())
+}
+
+#[test]
+fn failed_execute_proposal_token_transfer() -> anyhow::Result<()> {
+    let env = TestEnv::setup_with(|msg| {
+        msg.governance = Some(GovernanceConfig {
+            quorum: Decimal::percent(50),
+            threshold: Decimal::percent(50),
+            voting_period: Duration::Height(10),
+        })
+    })?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let holder_addr = &mock_env.addr_make(USER1);
+    let token_addr = &mock_env.addr_make("cw20");
+    buy_keys(&mock_env, &app, holder_addr, 3)?;
+    mock_env.wait_blocks(1)?;
+
+    // A cw20 transfer sends no funds, yet it moves the tokens of the account
+    let transfer = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: token_addr.to_string(),
+        msg: Binary::from(
+            format!(r#"{{"transfer":{{"recipient":"{holder_addr}","amount":"1000"}}}}"#)
+                .into_bytes(),
+        ),
+        funds: vec![],
+    });
+    let res = app.call_as(holder_addr).propose(
+        "Take the tokens".to_string(),
+        vec![transfer],
+        "Drain".to_string(),
+    )?;
+    let proposal_id = ProposeEvent::find(&res.events)?.proposal_id;
+    app.call_as(holder_addr)
+        .vote(proposal_id, VoteOption::Yes)?;
+    mock_env.wait_blocks(10)?;
+
+    // The holders pass it alone, it still waits for the owner
+    let proposal = app.proposal(proposal_id)?;
+    assert_eq!(proposal.status, ProposalStatus::Passed);
+    assert!(proposal.needs_owner_approval);
+    let err: FriendTechAppError = app
+        .call_as(holder_addr)
+        .execute_proposal(proposal_id)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::ProposalNotApproved { proposal_id });
+
+    Ok(())
+}
+
+#[test]
+fn successful_dynamic_fee() -> anyhow::Result<()> {
+    let dynamic_fee = DynamicFeeConfig {
+        volume_step: Uint128::new(5_000),
+        round_trip_fee_percentage: 10,
+        round_trip_window: 3_600,
+        max_fee_percentage: 20,
+    };
+    let env = TestEnv::setup_with(|msg| msg.dynamic_fee = Some(dynamic_fee.clone()))?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let trader_addr = &mock_env.addr_make(USER1);
+    let other_addr = &mock_env.addr_make(USER2);
+    assert_eq!(app.dynamic_fee()?.dynamic_fee, Some(dynamic_fee));
+    assert_eq!(
+        app.buy_key_cost(Uint128::one(), None)?
+            .issuer_fee_percentage,
+        5
+    );
+
+    // 8_750 of volume adds one point for everyone
+    let buy_cost_resp = buy_keys(&mock_env, &app, trader_addr, 3)?;
+    assert_eq!(buy_cost_resp.price, Uint128::new(8_750));
+    assert_eq!(
+        app.sell_key_cost(Uint128::one(), Some(other_addr.to_string()))?
+            .issuer_fee_percentage,
+        6
+    );
+
+    // Selling right after buying adds the whole round trip surcharge
+    let sell_cost_resp = app.sell_key_cost(Uint128::one(), Some(trader_addr.to_string()))?;
+    assert_eq!(sell_cost_resp.issuer_fee_percentage, 16);
+    assert_eq!(
+        app.keys_for_budget(Uint128::new(1_000_000), Some(other_addr.to_string()))?
+            .issuer_fee_percentage,
+        6
+    );
+
+    // The surcharge decays over the window
+    mock_env.wait_seconds(1_800)?;
+    let sell_cost_resp = app.sell_key_cost(Uint128::one(), Some(trader_addr.to_string()))?;
+    assert_eq!(sell_cost_resp.issuer_fee_percentage, 11);
+
+    // Paying the fee of a new trader is not enough
+    let new_trader_cost = app.sell_key_cost(Uint128::one(), None)?;
+    mock_env.set_balance(trader_addr, coins(sell_cost_resp.total_cost.u128(), DENOM))?;
+    let err: FriendTechAppError = app
+        .call_as(trader_addr)
+        .sell_key(
+            Uint128::one(),
+            None,
+            &coins(new_trader_cost.total_cost.u128(), DENOM),
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::InsufficientFunds {
+            required: sell_cost_resp.total_cost,
+            paid: new_trader_cost.total_cost,
+        }
+    );
+    app.call_as(trader_addr).sell_key(
+        Uint128::one(),
+        None,
+        &coins(sell_cost_resp.total_cost.u128(), DENOM),
+    )?;
+
+    // Buying back is a round trip too, and the sell brought the volume to 14_375
+    // The round trip made by the sell still counts half, the fee is capped at 5 + 2 + 10 + 5
+    assert_eq!(
+        app.buy_key_cost(Uint128::one(), Some(trader_addr.to_string()))?
+            .issuer_fee_percentage,
+        20
+    );
+
+    // Once the trades leave the window and the volume leaves the last 24 hours, the fee is back to base
+    mock_env.wait_seconds(24 * 3_600)?;
+    assert_eq!(
+        app.buy_key_cost(Uint128::one(), Some(trader_addr.to_string()))?
+            .issuer_fee_percentage,
+        5
+    );
+
+    // Buying for someone else is a trade of the recipient, 5_625 of volume adds one point
+    let buy_cost_resp = app.buy_key_cost(Uint128::one(), Some(other_addr.to_string()))?;
+    mock_env.set_balance(trader_addr, coins(buy_cost_resp.total_cost.u128(), DENOM))?;
+    app.call_as(trader_addr).buy_key(
+        Uint128::one(),
+        Some(other_addr.to_string()),
+        None,
+        &coins(buy_cost_resp.total_cost.u128(), DENOM),
+    )?;
+    assert_eq!(
+        app.sell_key_cost(Uint128::one(), Some(other_addr.to_string()))?
+            .issuer_fee_percentage,
+        16
+    );
+    assert_eq!(
+        app.sell_key_cost(Uint128::one(), Some(trader_addr.to_string()))?
+            .issuer_fee_percentage,
+        6
+    );
+
+    // Transferring the keys is a round trip of the sender, and the receiver selling them is one too
+    app.call_as(other_addr)
+        .transfer_keys(Uint128::one(), trader_addr.to_string(), &[])?;
+    assert_eq!(
+        app.sell_key_cost(Uint128::one(), Some(trader_addr.to_string()))?
+            .issuer_fee_percentage,
+        16
+    );
+    assert_eq!(
+        app.buy_key_cost(Uint128::one(), Some(other_addr.to_string()))?
+            .issuer_fee_percentage,
+        20
+    );
+
+    let err: FriendTechAppError = app
+        .update_dynamic_fee(Some(DynamicFeeConfig {
+            volume_step: Uint128::new(5_000),
+            round_trip_fee_percentage: 10,
+            round_trip_window: 3_600,
+            max_fee_percentage: 4,
+        }))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::InvalidDynamicFee {});
+    let err: FriendTechAppError = app
+        .call_as(trader_addr)
+        .update_dynamic_fee(None)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::Admin(AdminError::NotAdmin {}));
+
+    app.update_dynamic_fee(None)?;
+    buy_keys(&mock_env, &app, trader_addr, 3)?;
+    assert_eq!(
+        app.sell_key_cost(Uint128::one(), Some(trader_addr.to_string()))?
+            .issuer_fee_percentage,
+        5
+    );
+
+    Ok(())
+}
+
+#[test]
+fn successful_rate_limits() -> anyhow::Result<()> {
+    let rate_limits = RateLimits {
+        max_trades: Some(2),
+        window_blocks: 5,
+        max_block_volume: Some(Uint128::new(10_000)),
+    };
+    let env = TestEnv::setup_with(|msg| msg.rate_limits = Some(rate_limits.clone()))?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let trader_addr = &mock_env.addr_make(USER1);
+    let other_addr = &mock_env.addr_make(USER2);
+    assert_eq!(app.rate_limits()?.rate_limits, Some(rate_limits));
+    let start_height = mock_env.block_info()?.height;
+
+    // Two trades fill the window of the trader
+    buy_keys(&mock_env, &app, trader_addr, 1)?;
+    buy_keys(&mock_env, &app, trader_addr, 1)?;
+    let err: FriendTechAppError = buy_keys(&mock_env, &app, trader_addr, 1)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::TradeRateLimited {
+            retry_at_height: start_height + 5
+        }
+    );
+
+    // 15_625 could never fit in a block, no retry height helps
+    let err: FriendTechAppError = buy_keys(&mock_env, &app, other_addr, 2)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::TradeExceedsBlockLimit {
+            price: Uint128::new(15_625),
+            max_block_volume: Uint128::new(10_000),
+        }
+    );
+
+    // 3_125 were traded in this block, 10_000 more is above the block volume
+    buy_keys(&mock_env, &app, other_addr, 1)?;
+    let err: FriendTechAppError = buy_keys(&mock_env, &app, other_addr, 1)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::BlockVolumeLimited {
+            retry_at_height: start_height + 1
+        }
+    );
+
+    // The volume starts over on the next block, the trade window does not
+    mock_env.wait_blocks(1)?;
+    buy_keys(&mock_env, &app, other_addr, 1)?;
+    let err: FriendTechAppError = sell_keys(&mock_env, &app, trader_addr, 1)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::TradeRateLimited {
+            retry_at_height: start_height + 5
+        }
+    );
+    mock_env.wait_blocks(4)?;
+    sell_keys(&mock_env, &app, trader_addr, 1)?;
+
+    let err: FriendTechAppError = app
+        .update_rate_limits(Some(RateLimits {
+            max_trades: Some(2),
+            window_blocks: 0,
+            max_block_volume: None,
+        }))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::InvalidRateLimits {});
+    let err: FriendTechAppError = app
+        .call_as(trader_addr)
+        .update_rate_limits(None)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::Admin(AdminError::NotAdmin {}));
+
+    app.update_rate_limits(None)?;
+    assert_eq!(app.rate_limits()?.rate_limits, None);
+    buy_keys(&mock_env, &app, trader_addr, 1)?;
+    buy_keys(&mock_env, &app, trader_addr, 1)?;
+    buy_keys(&mock_env, &app, trader_addr, 1)?;
+
+    Ok(())
+}
+
+#[test]
+fn successful_key_locks() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let holder_addr = &mock_env.addr_make(USER1);
+    let other_addr = &mock_env.addr_make(USER2);
+    // The app itself stands in for the beneficiary app installed on the account
+    let beneficiary_addr = &app.address()?;
+    let until = Expiration::AtHeight(mock_env.block_info()?.height + 10);
+
+    buy_keys(&mock_env, &app, holder_addr, 6)?;
+    app.call_as(holder_addr)
+        .lock(Uint128::new(5), FRIEND_TECH_APP_ID.to_string(), until)?;
+
+    // Locked keys count toward the holding, the unlocked ones can still be sold
+    assert_eq!(
+        app.holding(holder_addr.to_string())?.amount,
+        Uint128::new(6)
+    );
+    app.call_as(holder_addr)
+        .lock(Uint128::one(), FRIEND_TECH_APP_ID.to_string(), until)?;
+
+    // The beneficiary releases the keys back to the holder
+    app.call_as(beneficiary_addr).release_lock(1)?;
+    let lock = LockResponse {
+        lock_id: 0,
+        holder: holder_addr.clone(),
+        amount: Uint128::new(5),
+        until,
+        beneficiary_app: FRIEND_TECH_APP_ID.to_string(),
+    };
+    assert_eq!(
+        app.locks_by_holder(holder_addr.to_string(), None, None)?
+            .locks,
+        vec![lock.clone()]
+    );
+
+    // Or slashes them, to a recipient or burning them
+    app.call_as(beneficiary_addr).slash_lock(
+        Uint128::one(),
+        0,
+        Some(other_addr.to_string()),
+        &[],
+    )?;
+    assert_eq!(
+        app.holding(holder_addr.to_string())?.amount,
+        Uint128::new(5)
+    );
+    assert_eq!(app.holding(other_addr.to_string())?.amount, Uint128::one());
+    assert_eq!(
+        app.key_lock(0)?,
+        LockResponse {
+            amount: Uint128::new(4),
+            ..lock.clone()
+        }
+    );
+
+    // Slashing to a recipient pays the transfer royalty like a gift
+    app.update_transfer_royalty(Some(TransferRoyalty::Fixed {
+        amount_per_key: Uint128::new(1_000),
+    }))?;
+    // The beneficiary pays out of its balance, which is the reserve too in this setup
+    let beneficiary_balance = mock_env.query_balance(beneficiary_addr, DENOM)?;
+    let fee_recipient = &app.issuer()?.fee_recipients[0].address;
+    let recipient_balance = mock_env.query_balance(fee_recipient, DENOM)?;
+    mock_env.set_balance(
+        beneficiary_addr,
+        coins(beneficiary_balance.u128() + 1_500, DENOM),
+    )?;
+    let err: FriendTechAppError = app
+        .call_as(beneficiary_addr)
+        .slash_lock(
+            Uint128::one(),
+            0,
+            Some(other_addr.to_string()),
+            &coins(999, DENOM),
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::InsufficientFunds {
+            required: Uint128::new(1_000),
+            paid: Uint128::new(999),
+        }
+    );
+    let res = app.call_as(beneficiary_addr).slash_lock(
+        Uint128::one(),
+        0,
+        Some(other_addr.to_string()),
+        &coins(1_500, DENOM),
+    )?;
+    assert_eq!(
+        SlashLockEvent::find(&res.events)?.royalty,
+        Uint128::new(1_000)
+    );
+    assert_eq!(
+        mock_env.query_balance(fee_recipient, DENOM)?,
+        recipient_balance + Uint128::new(1_000)
+    );
+    assert_eq!(
+        mock_env.query_balance(beneficiary_addr, DENOM)?,
+        beneficiary_balance + Uint128::new(500)
+    );
+    assert_eq!(app.holding(other_addr.to_string())?.amount, Uint128::new(2));
+
+    // Burned keys leave the supply, they are no buyback
+    let market = app.market()?;
+    let res = app
+        .call_as(beneficiary_addr)
+        .slash_lock(Uint128::new(3), 0, None, &[])?;
+    assert_eq!(SlashLockEvent::find(&res.events)?.royalty, Uint128::zero());
+    assert_eq!(app.holding(holder_addr.to_string())?.amount, Uint128::one());
+    let burned_market = app.market()?;
+    assert_eq!(burned_market.supply, market.supply - Uint128::new(3));
+    assert_eq!(burned_market.reserve, market.reserve);
+    assert!(burned_market.burned.is_zero());
+    assert_eq!(burned_market.holder_count, market.holder_count);
+    assert!(app
+        .locks_by_holder(holder_addr.to_string(), None, None)?
+        .locks
+        .is_empty());
+
+    // Once expired, the holder unlocks the keys
+    app.call_as(holder_addr)
+        .lock(Uint128::one(), FRIEND_TECH_APP_ID.to_string(), until)?;
+    mock_env.wait_blocks(10)?;
+    app.call_as(holder_addr).unlock(2)?;
+    sell_keys(&mock_env, &app, holder_addr, 1)?;
+
+    // Slashing every key of a holder removes them from the holders
+    let until = Expiration::AtHeight(mock_env.block_info()?.height + 10);
+    app.call_as(other_addr)
+        .lock(Uint128::new(2), FRIEND_TECH_APP_ID.to_string(), until)?;
+    let holder_count = app.market()?.holder_count;
+    app.call_as(beneficiary_addr)
+        .slash_lock(Uint128::new(2), 3, None, &[])?;
+    assert!(app.holding(other_addr.to_string())?.amount.is_zero());
+    assert_eq!(app.market()?.holder_count, holder_count - 1);
+
+    Ok(())
+}
+
+#[test]
+fn failed_key_locks() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let holder_addr = &mock_env.addr_make(USER1);
+    let other_addr = &mock_env.addr_make(USER2);
+    let beneficiary_addr = &app.address()?;
+    // Any other contract, installed on the account or not, is not the beneficiary
+    let other_app_addr = &app.account().proxy()?;
+    let until = Expiration::AtHeight(mock_env.block_info()?.height + 10);
+
+    buy_keys(&mock_env, &app, holder_addr, 6)?;
+    let err: FriendTechAppError = app
+        .call_as(holder_addr)
+        .lock(Uint128::one(), "abstract:unknown".to_string(), until)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::AppNotInstalled {
+            module_id: "abstract:unknown".to_string()
+        }
+    );
+    let err: FriendTechAppError = app
+        .call_as(holder_addr)
+        .lock(
+            Uint128::one(),
+            FRIEND_TECH_APP_ID.to_string(),
+            Expiration::Never {},
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::InvalidLock {});
+    app.call_as(holder_addr)
+        .lock(Uint128::new(5), FRIEND_TECH_APP_ID.to_string(), until)?;
+
+    // Locked keys cannot be sold or transferred
+    let err: FriendTechAppError = sell_keys(&mock_env, &app, holder_addr, 2)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::InsufficientUnlockedKeys {
+            available: Uint128::one(),
+            required: Uint128::new(2),
+        }
+    );
+    let err: FriendTechAppError = app
+        .call_as(holder_addr)
+        .transfer_keys(Uint128::new(2), other_addr.to_string(), &[])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::InsufficientUnlockedKeys {
+            available: Uint128::one(),
+            required: Uint128::new(2),
+        }
+    );
+
+    // Nor unlocked before the expiry, or by anyone but the holder
+    let err: FriendTechAppError = app
+        .call_as(holder_addr)
+        .unlock(0)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::LockNotExpired {});
+    let err: FriendTechAppError = app
+        .call_as(other_addr)
+        .unlock(0)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::NotLockHolder {});
+
+    // Only the beneficiary app releases or slashes the keys
+    for sender in [other_addr, other_app_addr, holder_addr] {
+        let err: FriendTechAppError = app
+            .call_as(sender)
+            .release_lock(0)
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err, FriendTechAppError::NotLockBeneficiary {});
+        let err: FriendTechAppError = app
+            .call_as(sender)
+            .slash_lock(Uint128::one(), 0, Some(other_addr.to_string()), &[])
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err, FriendTechAppError::NotLockBeneficiary {});
+    }
+    let err: FriendTechAppError = app
+        .call_as(beneficiary_addr)
+        .slash_lock(Uint128::new(6), 0, None, &[])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::CannotSlashMoreThanLocked {
+            locked: Uint128::new(5),
+            to_slash: Uint128::new(6),
+        }
+    );
+    let err: FriendTechAppError = app
+        .call_as(beneficiary_addr)
+        .slash_lock(Uint128::one(), 0, Some(holder_addr.to_string()), &[])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::InvalidTransfer {});
+
+    // A released lock is gone, it cannot be released or slashed again
+    app.call_as(beneficiary_addr).release_lock(0)?;
+    let err: FriendTechAppError = app
+        .call_as(beneficiary_addr)
+        .release_lock(0)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::LockNotFound { lock_id: 0 });
+    let err: FriendTechAppError = app
+        .call_as(beneficiary_addr)
+        .slash_lock(Uint128::one(), 0, None, &[])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::LockNotFound { lock_id: 0 });
+
+    // An expired lock can no longer be slashed
+    app.call_as(holder_addr)
+        .lock(Uint128::one(), FRIEND_TECH_APP_ID.to_string(), until)?;
+    mock_env.wait_blocks(10)?;
+    let err: FriendTechAppError = app
+        .call_as(beneficiary_addr)
+        .slash_lock(Uint128::one(), 1, None, &[])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::LockExpired {});
+
+    Ok(())
+}
+
+#[test]
+fn successful_transfer_royalty() -> anyhow::Result<()> {
+    let fixed_royalty = TransferRoyalty::Fixed {
+        amount_per_key: Uint128::new(100),
+    };
+    let env = TestEnv::setup_with(|msg| msg.transfer_royalty = Some(fixed_royalty.clone()))?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let sender_addr = &mock_env.addr_make(USER1);
+    let recipient_addr = &mock_env.addr_make(USER2);
+    let friend_addr = &mock_env.addr_make(USER3);
+    let fee_recipient = &app.issuer()?.fee_recipients[0].address;
+    assert_eq!(app.transfer_royalty()?.royalty, Some(fixed_royalty));
+    buy_keys(&mock_env, &app, sender_addr, 5)?;
+
+    // The royalty is charged per key moved and the excess payment is refunded
+    let royalty = app
+        .transfer_royalty_cost(Uint128::new(2), Some(recipient_addr.to_string()))?
+        .royalty;
+    assert_eq!(royalty, Uint128::new(200));
+    mock_env.set_balance(sender_addr, coins(250, DENOM))?;
+    let err: FriendTechAppError = app
+        .call_as(sender_addr)
+        .transfer_keys(
+            Uint128::new(2),
+            recipient_addr.to_string(),
+            &coins(100, DENOM),
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::InsufficientFunds {
+            required: royalty,
+            paid: Uint128::new(100),
+        }
+    );
+    let fee_recipient_balance = mock_env.query_balance(fee_recipient, DENOM)?;
+    app.call_as(sender_addr).transfer_keys(
+        Uint128::new(2),
+        recipient_addr.to_string(),
+        &coins(250, DENOM),
+    )?;
+    assert_eq!(
+        mock_env.query_balance(fee_recipient, DENOM)?,
+        fee_recipient_balance + royalty
+    );
+    assert_eq!(
+        mock_env.query_balance(sender_addr, DENOM)?,
+        Uint128::new(50)
+    );
+    assert_eq!(
+        app.holding(sender_addr.to_string())?.amount,
+        Uint128::new(3)
+    );
+    assert_eq!(
+        app.holding(recipient_addr.to_string())?.amount,
+        Uint128::new(2)
+    );
+
+    let err: FriendTechAppError = app
+        .call_as(sender_addr)
+        .transfer_keys(Uint128::one(), sender_addr.to_string(), &[])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::InvalidTransfer {});
+
+    // Gifts to exempt addresses are free
+    let err: FriendTechAppError = app
+        .call_as(sender_addr)
+        .update_royalty_exemptions(vec![friend_addr.to_string()], vec![])
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::Admin(AdminError::NotAdmin {}));
+    app.update_royalty_exemptions(vec![friend_addr.to_string()], vec![])?;
+    assert_eq!(
+        app.royalty_exemptions(None, None)?.addresses,
+        vec![friend_addr.clone()]
+    );
+    assert!(app
+        .transfer_royalty_cost(Uint128::one(), Some(friend_addr.to_string()))?
+        .royalty
+        .is_zero());
+    app.call_as(sender_addr)
+        .transfer_keys(Uint128::one(), friend_addr.to_string(), &[])?;
+    assert_eq!(app.holding(friend_addr.to_string())?.amount, Uint128::one());
+
+    // A percentage royalty follows the spot price
+    let err: FriendTechAppError = app
+        .update_transfer_royalty(Some(TransferRoyalty::SpotPercentage { percentage: 110 }))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::InvalidPercentage { percentage: 110 }
+    );
+    app.update_transfer_royalty(Some(TransferRoyalty::SpotPercentage { percentage: 10 }))?;
+    let spot_price = app.buy_key_cost(Uint128::one(), None)?.price;
+    let royalty = app.transfer_royalty_cost(Uint128::one(), None)?.royalty;
+    assert_eq!(royalty, spot_price * Decimal::percent(10));
+
+    // Listing fills pay a single royalty, the greater of the listing and the transfer royalty
+    // The buyer tops up the part of the transfer royalty the listing royalty leaves, exempt buyers included
+    let listing_royalty = Uint128::new(100);
+    assert!(royalty > listing_royalty);
+    let top_up = royalty - listing_royalty;
+    app.call_as(recipient_addr)
+        .list_keys(Uint128::one(), Uint128::new(1_000))?;
+    mock_env.set_balance(friend_addr, coins(1_000 + top_up.u128(), DENOM))?;
+    let err: FriendTechAppError = app
+        .call_as(friend_addr)
+        .fill_listing(Uint128::one(), 0, &coins(1_000, DENOM))
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::InsufficientFunds {
+            required: Uint128::new(1_000) + top_up,
+            paid: Uint128::new(1_000),
+        }
+    );
+    let fee_recipient_balance = mock_env.query_balance(fee_recipient, DENOM)?;
+    let seller_balance = mock_env.query_balance(recipient_addr, DENOM)?;
+    let fill_resp = app.call_as(friend_addr).fill_listing(
+        Uint128::one(),
+        0,
+        &coins(1_000 + top_up.u128(), DENOM),
+    )?;
+    let event = FillListingEvent::find(&fill_resp.events)?;
+    assert_eq!(event.royalty, listing_royalty);
+    assert_eq!(event.transfer_royalty, top_up);
+    assert_eq!(
+        mock_env.query_balance(fee_recipient, DENOM)?,
+        fee_recipient_balance + royalty
+    );
+    assert_eq!(
+        mock_env.query_balance(recipient_addr, DENOM)?,
+        seller_balance + Uint128::new(1_000) - listing_royalty
+    );
+    assert!(mock_env.query_balance(friend_addr, DENOM)?.is_zero());
+
+    // A listing royalty above the transfer royalty covers it whole
+    let price_per_key = royalty * Uint128::new(100);
+    app.call_as(recipient_addr)
+        .list_keys(Uint128::one(), price_per_key)?;
+    mock_env.set_balance(friend_addr, coins(price_per_key.u128(), DENOM))?;
+    let fee_recipient_balance = mock_env.query_balance(fee_recipient, DENOM)?;
+    let fill_resp = app.call_as(friend_addr).fill_listing(
+        Uint128::one(),
+        1,
+        &coins(price_per_key.u128(), DENOM),
+    )?;
+    let event = FillListingEvent::find(&fill_resp.events)?;
+    assert_eq!(event.royalty, royalty * Uint128::new(10));
+    assert!(event.transfer_royalty.is_zero());
+    assert_eq!(
+        mock_env.query_balance(fee_recipient, DENOM)?,
+        fee_recipient_balance + royalty * Uint128::new(10)
+    );
+
+    // Without a royalty transfers take no funds
+    app.update_transfer_royalty(None)?;
+    assert!(app
+        .transfer_royalty_cost(Uint128::one(), None)?
+        .royalty
+        .is_zero());
+    app.call_as(sender_addr)
+        .transfer_keys(Uint128::one(), recipient_addr.to_string(), &[])?;
+    app.update_royalty_exemptions(vec![], vec![friend_addr.to_string()])?;
+    assert!(app.royalty_exemptions(None, None)?.addresses.is_empty());
+
+    Ok(())
+}
+
+/// Messages received by the test hook contract
+const HOOK_CALLS: Item<Vec<FriendTechHookMsg>> = Item::new("hook_calls");
+/// Whether the test hook contract fails on every message
+const HOOK_FAILS: Item<bool> = Item::new("hook_fails");
+
+fn hook_instantiate(
+    deps: DepsMut,
+    _env: cosmwasm_std::Env,
+    _info: MessageInfo,
+    fails: bool,
+) -> StdResult<Response> {
+    HOOK_FAILS.save(deps.storage, &fails)?;
+    HOOK_CALLS.save(deps.storage, &vec![])?;
+    Ok(Response::new())
+}
+
+fn hook_execute(
+    deps: DepsMut,
+    _env: cosmwasm_std::Env,
+    _info: MessageInfo,
+    msg: FriendTechHookMsg,
+) -> StdResult<Response> {
+    if HOOK_FAILS.load(deps.storage)? {
+        return Err(StdError::generic_err("hook failed"));
+    }
+    HOOK_CALLS.update(deps.storage, |mut calls| -> StdResult<_> {
+        calls.push(msg);
+        Ok(calls)
+    })?;
+    Ok(Response::new())
+}
+
+fn hook_query(deps: Deps, _env: cosmwasm_std::Env, _msg: Empty) -> StdResult<Binary> {
+    to_json_binary(&HOOK_CALLS.load(deps.storage)?)
+}
+
+/// Instantiate a hook contract recording the messages it receives, or failing on all of them
+fn instantiate_hook(mock_env: &MockBech32, fails: bool) -> anyhow::Result<Addr> {
+    let mut mock_app = mock_env.app.borrow_mut();
+    let code_id = mock_app.store_code(Box::new(ContractWrapper::new(
+        hook_execute,
+        hook_instantiate,
+        hook_query,
+    )));
+    mock_app.instantiate_contract(code_id, mock_env.sender_addr(), &fails, &[], "hook", None)
+}
+
+fn hook_calls(mock_env: &MockBech32, hook: &Addr) -> StdResult<Vec<FriendTechHookMsg>> {
+    mock_env
+        .app
+        .borrow()
+        .wrap()
+        .query_wasm_smart(hook, &Empty {})
+}
+
+#[test]
+fn successful_hooks() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let trader_addr = &mock_env.addr_make(USER1);
+    let recorder = instantiate_hook(&mock_env, false)?;
+    let failing = instantiate_hook(&mock_env, true)?;
+
+    // Only the owner can register hooks
+    let err: FriendTechAppError = app
+        .call_as(trader_addr)
+        .register_hook(recorder.to_string(), HookFailure::Revert)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, FriendTechAppError::Admin(AdminError::NotAdmin {}));
+
+    app.register_hook(recorder.to_string(), HookFailure::Revert)?;
+    let err: FriendTechAppError = app
+        .register_hook(recorder.to_string(), HookFailure::Ignore)
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::HookAlreadyRegistered {
+            contract: recorder.to_string()
+        }
+    );
+    assert_eq!(
+        app.hooks()?,
+        HooksResponse {
+            hooks: vec![HookResponse {
+                contract: recorder.clone(),
+                on_failure: HookFailure::Revert,
+            }]
+        }
+    );
+
+    // Every balance change is reported with the supply after the trade
+    buy_keys(&mock_env, &app, trader_addr, 3)?;
+    sell_keys(&mock_env, &app, trader_addr, 1)?;
+    assert_eq!(
+        hook_calls(&mock_env, &recorder)?,
+        vec![
+            FriendTechHookMsg::KeyBalanceChanged {
+                holder: trader_addr.clone(),
+                old: Uint128::zero(),
+                new: Uint128::from(3u128),
+                supply: Uint128::from(4u128),
+            },
+            FriendTechHookMsg::KeyBalanceChanged {
+                holder: trader_addr.clone(),
+                old: Uint128::from(3u128),
+                new: Uint128::from(2u128),
+                supply: Uint128::from(3u128),
+            },
+        ]
+    );
+
+    // A failing hook reverts the trade
+    app.register_hook(failing.to_string(), HookFailure::Revert)?;
+    assert!(buy_keys(&mock_env, &app, trader_addr, 1).is_err());
+    assert_eq!(
+        app.holding(trader_addr.to_string())?.amount,
+        Uint128::from(2u128)
+    );
+
+    // Unless its failures are ignored
+    app.unregister_hook(failing.to_string())?;
+    app.register_hook(failing.to_string(), HookFailure::Ignore)?;
+    let buy_cost_resp = app.buy_key_cost(Uint128::one(), None)?;
+    mock_env.set_balance(trader_addr, coins(buy_cost_resp.total_cost.u128(), DENOM))?;
+    let tx_resp = app.call_as(trader_addr).buy_key(
+        Uint128::one(),
+        None,
+        None,
+        &coins(buy_cost_resp.total_cost.u128(), DENOM),
+    )?;
+    assert!(!HookFailedEvent::find(&tx_resp.events)?.error.is_empty());
+    assert_eq!(
+        app.holding(trader_addr.to_string())?.amount,
+        Uint128::from(3u128)
+    );
+    assert_eq!(hook_calls(&mock_env, &recorder)?.len(), 3);
+
+    app.unregister_hook(recorder.to_string())?;
+    let err: FriendTechAppError = app
+        .unregister_hook(recorder.to_string())
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        FriendTechAppError::HookNotRegistered {
+            contract: recorder.to_string()
+        }
+    );
+
+    Ok(())
+}
@@ -43,7 +43,7 @@ fn main() -> anyhow::Result<()> {
     let publisher: Publisher<_> = abstract_client.publisher_builder(app_namespace).build()?;
 
     // Ensure the current sender owns the namespace
-    if publisher.account().owner()? != daemon.sender() {
+    if publisher.account().owner()? != daemon.sender_addr() {
         panic!("The current sender can not publish to this namespace. Please use the wallet that owns the Account that owns the Namespace.")
     }
 
@@ -54,7 +54,12 @@ fn main() -> anyhow::Result<()> {
 
     let account = abstract_client.account_builder().build()?;
     // Installs the app on the Account
-    let app = account.install_app::<Qa<_>>(&QAAppInstantiateMsg {}, &[])?;
+    let app = account.install_app::<Qa<_>>(
+        &QAAppInstantiateMsg {
+            holder_reward_percentage: 0,
+        },
+        &[],
+    )?;
 
     // Import app's endpoint function traits for easy interactions.
     use qa_app::msg::QAAppQueryMsgFns;
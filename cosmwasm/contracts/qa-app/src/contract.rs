@@ -2,7 +2,7 @@ use crate::{
     error::QAAppError,
     handlers,
     msg::{QAAppExecuteMsg, QAAppInstantiateMsg, QAAppMigrateMsg, QAAppQueryMsg},
-    replies::{self, INSTANTIATE_REPLY_ID},
+    replies::{self, DEPOSIT_REWARDS_REPLY_ID, INSTANTIATE_REPLY_ID},
     APP_VERSION, QA_APP_ID,
 };
 
@@ -22,7 +22,10 @@ const QA_APP: QAApp = QAApp::new(QA_APP_ID, APP_VERSION, None)
     .with_execute(handlers::execute_handler)
     .with_query(handlers::query_handler)
     .with_migrate(handlers::migrate_handler)
-    .with_replies(&[(INSTANTIATE_REPLY_ID, replies::instantiate_reply)])
+    .with_replies(&[
+        (INSTANTIATE_REPLY_ID, replies::instantiate_reply),
+        (DEPOSIT_REWARDS_REPLY_ID, replies::deposit_rewards_reply),
+    ])
     .with_dependencies(&[
         // This module application is dependent on another modules: the Friend Tech module.
         StaticDependency::new(FRIEND_TECH_APP_ID, &[FRIEND_TECH_APP_VERSION]),
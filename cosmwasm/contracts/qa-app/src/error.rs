@@ -41,4 +41,7 @@ pub enum QAAppError {
 
     #[error("Insufficient funds, required: {required}, paid: {paid}")]
     InsufficientFunds { required: Uint128, paid: Uint128 },
+
+    #[error("Percentage must be between 0 and 100, got: {percentage}")]
+    InvalidPercentage { percentage: u32 },
 }
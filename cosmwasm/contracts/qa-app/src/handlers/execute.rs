@@ -2,14 +2,19 @@ use super::query::query_ask_cost;
 use crate::{
     contract::{QAApp, QAAppResult},
     msg::QAAppExecuteMsg,
-    state::{Question, ANSWERED_QUESTIONS, NEXT_QUESTION_ID, UNANSWERED_QUESTIONS},
+    replies::DEPOSIT_REWARDS_REPLY_ID,
+    state::{
+        Question, ANSWERED_QUESTIONS, CONFIG, NEXT_QUESTION_ID, PENDING_HOLDER_REWARD,
+        UNANSWERED_QUESTIONS,
+    },
     utils::get_account_owner_addr,
     QAAppError,
 };
 
 use abstract_app::traits::AbstractResponse;
-use cosmwasm_std::{coins, BankMsg, DepsMut, Env, MessageInfo};
+use cosmwasm_std::{coin, coins, BankMsg, DepsMut, Env, MessageInfo, SubMsg, Uint128};
 use cw_utils::{must_pay, nonpayable};
+use friend_tech_app::{split_fee, FriendTechInterface};
 
 pub fn execute_handler(
     deps: DepsMut,
@@ -55,18 +60,38 @@ fn ask(deps: DepsMut, msg_info: MessageInfo, content: String, app: QAApp) -> QAA
 
     NEXT_QUESTION_ID.save(deps.storage, &(next_question_id + 1))?;
 
-    Ok(app
-        .response("ask")
-        .add_message(BankMsg::Send {
-            to_address: cost_resp.ask_fee_collector.to_string(),
-            amount: coins(cost_resp.cost.u128(), cost_resp.fee_denom.clone()),
-        })
+    let config = CONFIG.load(deps.storage)?;
+    let holder_reward =
+        (cost_resp.cost * Uint128::from(config.holder_reward_percentage)) / Uint128::from(100_u64);
+    let ask_fee = cost_resp.cost - holder_reward;
+
+    let mut response = app.response("ask");
+    for (recipient, share) in split_fee(ask_fee, &cost_resp.ask_fee_recipients) {
+        if !share.is_zero() {
+            response = response.add_message(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: coins(share.u128(), cost_resp.fee_denom.clone()),
+            });
+        }
+    }
+    if !holder_reward.is_zero() {
+        let holder_reward = coin(holder_reward.u128(), cost_resp.fee_denom.clone());
+        PENDING_HOLDER_REWARD.save(deps.storage, &holder_reward)?;
+        response = response.add_submessage(SubMsg::reply_always(
+            app.friend_tech(deps.as_ref())
+                .deposit_rewards(vec![holder_reward])?,
+            DEPOSIT_REWARDS_REPLY_ID,
+        ));
+    }
+
+    Ok(response
         .add_attribute("asker", asker)
         .add_attribute("question_id", next_question_id.to_string())
         .add_attribute("question_content", content)
         .add_attribute("cost", cost_resp.cost)
         .add_attribute("fee_denom", cost_resp.fee_denom)
-        .add_attribute("ask_fee_collector", cost_resp.ask_fee_collector))
+        .add_attribute("ask_fee", ask_fee)
+        .add_attribute("holder_reward", holder_reward))
 }
 
 /// Only module owner can call, answer a question
@@ -1,8 +1,9 @@
 use crate::{
     contract::{QAApp, QAAppResult},
     msg::QAAppInstantiateMsg,
-    state::NEXT_QUESTION_ID,
+    state::{Config, CONFIG, NEXT_QUESTION_ID},
     utils::get_account_owner_addr,
+    QAAppError,
 };
 
 use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
@@ -12,13 +13,29 @@ pub fn instantiate_handler(
     _env: Env,
     _info: MessageInfo,
     app: QAApp,
-    _msg: QAAppInstantiateMsg,
+    msg: QAAppInstantiateMsg,
 ) -> QAAppResult {
     let account_owner_addr = &get_account_owner_addr(deps.as_ref(), &app)?;
 
+    if msg.holder_reward_percentage > 100 {
+        return Err(QAAppError::InvalidPercentage {
+            percentage: msg.holder_reward_percentage,
+        });
+    }
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            holder_reward_percentage: msg.holder_reward_percentage,
+        },
+    )?;
     NEXT_QUESTION_ID.save(deps.storage, &0)?;
 
     Ok(Response::new()
         .add_attribute("action", "instantiate")
-        .add_attribute("account_owner", account_owner_addr))
+        .add_attribute("account_owner", account_owner_addr)
+        .add_attribute(
+            "holder_reward_percentage",
+            msg.holder_reward_percentage.to_string(),
+        ))
 }
@@ -1,6 +1,7 @@
 use crate::{
     contract::{QAApp, QAAppResult},
     msg::QAAppMigrateMsg,
+    state::{Config, CONFIG},
 };
 
 use abstract_app::traits::AbstractResponse;
@@ -8,11 +9,15 @@ use cosmwasm_std::{DepsMut, Env};
 
 /// Handle the app migrate msg
 /// The top-level Abstract app does version checking and dispatches to this handler
-pub fn migrate_handler(
-    _deps: DepsMut,
-    _env: Env,
-    app: QAApp,
-    _msg: QAAppMigrateMsg,
-) -> QAAppResult {
+pub fn migrate_handler(deps: DepsMut, _env: Env, app: QAApp, _msg: QAAppMigrateMsg) -> QAAppResult {
+    // Installs made before 0.0.2 have no config, they keep paying the whole ask cost to the fee recipients
+    if !CONFIG.exists(deps.storage) {
+        CONFIG.save(
+            deps.storage,
+            &Config {
+                holder_reward_percentage: 0,
+            },
+        )?;
+    }
     Ok(app.response("migrate"))
 }
@@ -45,7 +45,7 @@ pub fn query_ask_cost(deps: Deps, app: &QAApp) -> StdResult<AskCostResponse> {
     Ok(AskCostResponse {
         fee_denom: answerer.fee_denom,
         cost: cost.total_cost,
-        ask_fee_collector: answerer.issuer_fee_collector,
+        ask_fee_recipients: answerer.fee_recipients,
     })
 }
 
@@ -1,13 +1,17 @@
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::Uint128;
 
 use crate::{contract::QAApp, state::Question};
+use friend_tech_app::state::FeeRecipient;
 
 // This is used for type safety and re-exporting the contract endpoint structs.
 abstract_app::app_msg_types!(QAApp, QAAppExecuteMsg, QAAppQueryMsg);
 
 /// App instantiate message
 #[cosmwasm_schema::cw_serde]
-pub struct QAAppInstantiateMsg {}
+pub struct QAAppInstantiateMsg {
+    /// Percentage of the ask cost deposited as rewards for the answerer's key holders
+    pub holder_reward_percentage: u32,
+}
 
 /// App execute messages
 #[cosmwasm_schema::cw_serde]
@@ -56,7 +60,7 @@ pub struct StatsResponse {
 pub struct AskCostResponse {
     pub fee_denom: String,
     pub cost: Uint128,
-    pub ask_fee_collector: Addr,
+    pub ask_fee_recipients: Vec<FeeRecipient>,
 }
 
 #[cosmwasm_schema::cw_serde]
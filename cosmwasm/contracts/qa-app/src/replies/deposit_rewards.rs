@@ -0,0 +1,30 @@
+use crate::{
+    contract::{QAApp, QAAppResult},
+    state::PENDING_HOLDER_REWARD,
+    utils::get_account_owner_addr,
+};
+
+use abstract_app::traits::AbstractResponse;
+use cosmwasm_std::{BankMsg, DepsMut, Env, Reply, SubMsgResult};
+
+/// Pay the holder reward to the account owner when friend tech refuses the deposit,
+/// e.g. while its market is closed, so asking never depends on the state of the market
+pub fn deposit_rewards_reply(deps: DepsMut, _env: Env, app: QAApp, reply: Reply) -> QAAppResult {
+    let holder_reward = PENDING_HOLDER_REWARD.load(deps.storage)?;
+    PENDING_HOLDER_REWARD.remove(deps.storage);
+
+    let response = app.response("deposit_rewards_reply");
+    match reply.result {
+        SubMsgResult::Ok(_) => Ok(response),
+        SubMsgResult::Err(error) => {
+            let owner = get_account_owner_addr(deps.as_ref(), &app)?;
+            Ok(response
+                .add_attribute("error", error)
+                .add_attribute("holder_reward_paid_to", &owner)
+                .add_message(BankMsg::Send {
+                    to_address: owner.to_string(),
+                    amount: vec![holder_reward],
+                }))
+        }
+    }
+}
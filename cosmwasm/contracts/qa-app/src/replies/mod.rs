@@ -1,5 +1,8 @@
+mod deposit_rewards;
 mod instantiate;
 
+pub use deposit_rewards::deposit_rewards_reply;
 pub use instantiate::instantiate_reply;
 
 pub const INSTANTIATE_REPLY_ID: u64 = 1u64;
+pub const DEPOSIT_REWARDS_REPLY_ID: u64 = 2u64;
@@ -1,6 +1,17 @@
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Coin};
 use cw_storage_plus::{Item, Map};
 
+#[cosmwasm_schema::cw_serde]
+pub struct Config {
+    /// Percentage of the ask cost deposited as rewards for the answerer's key holders
+    pub holder_reward_percentage: u32,
+}
+
+pub const CONFIG: Item<Config> = Item::new("CONFIG");
+
+/// Holder reward of the ask being deposited to friend tech, until the deposit replies
+pub const PENDING_HOLDER_REWARD: Item<Coin> = Item::new("PENDING_HOLDER_REWARD");
+
 /// An incrementing number to assign unique IDs to questions
 pub const NEXT_QUESTION_ID: Item<u64> = Item::new("NEXT_QUESTION_ID");
 
@@ -1,19 +1,30 @@
-use friend_tech_app::{msg::FriendTechAppInstantiateMsg, Friendtech, FRIEND_TECH_APP_ID};
+use friend_tech_app::{
+    msg::{
+        FriendTechAppExecuteMsgFns, FriendTechAppInstantiateMsg, FriendTechAppQueryMsgFns,
+        UncheckedFeeRecipient,
+    },
+    state::FeeRecipient,
+    Friendtech, FRIEND_TECH_APP_ID,
+};
 use qa_app::{
     contract::interface::Qa,
     msg::{
-        QAAppExecuteMsgFns, QAAppInstantiateMsg, QAAppQueryMsgFns, QuestionResponse,
-        QuestionsResponse, StatsResponse,
+        QAAppExecuteMsgFns, QAAppInstantiateMsg, QAAppMigrateMsg, QAAppQueryMsgFns,
+        QuestionResponse, QuestionsResponse, StatsResponse,
     },
     state::Question,
-    QAAppError, MY_NAMESPACE,
+    QAAppError, MY_NAMESPACE, QA_APP_ID,
 };
 
-use abstract_app::objects::namespace::Namespace;
+use abstract_app::{
+    objects::namespace::Namespace,
+    std::app::{BaseMigrateMsg, MigrateMsg},
+};
 use abstract_client::{AbstractClient, Application, Environment};
-use cosmwasm_std::{coins, Uint128};
+use cosmwasm_std::{coins, Storage, Uint128};
 // Use prelude to get all the necessary imports
 use cw_orch::{anyhow, prelude::*};
+use cw_utils::Duration;
 
 const DENOM: &str = "ucosm";
 
@@ -27,9 +38,16 @@ struct TestEnv<Env: CwEnv> {
 impl TestEnv<MockBech32> {
     /// Set up the test environment with an Account that has the App installed
     fn setup() -> anyhow::Result<TestEnv<MockBech32>> {
+        Self::setup_with_holder_reward(0)
+    }
+
+    /// Set up the test environment with part of the ask cost going to the key holders
+    fn setup_with_holder_reward(
+        holder_reward_percentage: u32,
+    ) -> anyhow::Result<TestEnv<MockBech32>> {
         // Create a sender and mock env
         let mock = MockBech32::new("mock");
-        let sender = mock.sender();
+        let sender = mock.sender_addr();
         let namespace = Namespace::new(MY_NAMESPACE)?;
 
         // You can set up Abstract with a builder.
@@ -47,11 +65,28 @@ impl TestEnv<MockBech32> {
         let app = qa_publisher
             .account()
             .install_app_with_dependencies::<Qa<_>>(
-                &QAAppInstantiateMsg {},
+                &QAAppInstantiateMsg {
+                    holder_reward_percentage,
+                },
                 FriendTechAppInstantiateMsg {
                     username: "test".to_string(),
                     fee_denom: DENOM.to_string(),
-                    issuer_fee_collector: sender.to_string(),
+                    fee_recipients: vec![UncheckedFeeRecipient {
+                        address: sender.to_string(),
+                        weight: 100,
+                    }],
+                    referral_fee_percentage: 0,
+                    staking_fee_percentage: 0,
+                    unbonding_period: Duration::Time(0),
+                    listing_royalty_percentage: 0,
+                    accrue_fees: false,
+                    accounts_only: false,
+                    opening_auction: None,
+                    governance: None,
+                    dynamic_fee: None,
+                    transfer_royalty: None,
+                    airdrop: None,
+                    rate_limits: None,
                 },
                 &[],
             )?;
@@ -139,10 +174,16 @@ fn successful_ask() -> anyhow::Result<()> {
         mock_env.query_balance(asker_addr, ask_cost_resp.fee_denom.as_str())?,
         Uint128::zero()
     );
-    assert_eq!(ask_cost_resp.ask_fee_collector, app.account().owner()?);
+    assert_eq!(
+        ask_cost_resp.ask_fee_recipients,
+        vec![FeeRecipient {
+            address: app.account().owner()?,
+            weight: 100
+        }]
+    );
     assert_eq!(
         mock_env.query_balance(
-            &ask_cost_resp.ask_fee_collector,
+            &ask_cost_resp.ask_fee_recipients[0].address,
             ask_cost_resp.fee_denom.as_str()
         )?,
         ask_cost_resp.cost
@@ -281,3 +322,145 @@ fn successful_answer() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn successful_ask_with_holder_reward() -> anyhow::Result<()> {
+    let env = TestEnv::setup_with_holder_reward(40)?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let ask_cost_resp = app.ask_cost()?;
+    let holder_reward = ask_cost_resp.cost * Uint128::from(40u128) / Uint128::from(100u128);
+
+    let asker_addr = &mock_env.addr_make(USER1);
+
+    mock_env.set_balance(
+        asker_addr,
+        coins(ask_cost_resp.cost.u128(), ask_cost_resp.fee_denom.clone()),
+    )?;
+
+    app.call_as(asker_addr).ask(
+        "this is a question".to_string(),
+        &coins(ask_cost_resp.cost.u128(), ask_cost_resp.fee_denom.clone()),
+    )?;
+
+    assert_eq!(
+        mock_env.query_balance(
+            &ask_cost_resp.ask_fee_recipients[0].address,
+            ask_cost_resp.fee_denom.as_str()
+        )?,
+        ask_cost_resp.cost - holder_reward
+    );
+
+    // The account owner holds the only key so they get the whole reward
+    let friend_tech = app.account().application::<Friendtech<_>>()?;
+    let pending_rewards = friend_tech.pending_rewards(app.account().owner()?.to_string())?;
+    assert_eq!(pending_rewards.amount, holder_reward);
+
+    Ok(())
+}
+
+#[test]
+fn successful_ask_with_market_closed() -> anyhow::Result<()> {
+    let env = TestEnv::setup_with_holder_reward(40)?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    let friend_tech = app.account().application::<Friendtech<_>>()?;
+    friend_tech.close_market()?;
+
+    let ask_cost_resp = app.ask_cost()?;
+    let asker_addr = &mock_env.addr_make(USER1);
+    mock_env.set_balance(
+        asker_addr,
+        coins(ask_cost_resp.cost.u128(), ask_cost_resp.fee_denom.clone()),
+    )?;
+    app.call_as(asker_addr).ask(
+        "this is a question".to_string(),
+        &coins(ask_cost_resp.cost.u128(), ask_cost_resp.fee_denom.clone()),
+    )?;
+
+    // Friend tech refuses the deposit, so the account owner, also the fee recipient, gets the whole cost
+    let owner = app.account().owner()?;
+    assert_eq!(ask_cost_resp.ask_fee_recipients[0].address, owner);
+    assert_eq!(
+        mock_env.query_balance(&owner, ask_cost_resp.fee_denom.as_str())?,
+        ask_cost_resp.cost
+    );
+    assert_eq!(app.stats()?.total_question_count, 1);
+
+    Ok(())
+}
+
+/// Key of `key` in the storage of the contract at `contract_addr`, as namespaced by the mock wasm module
+fn contract_storage_key(contract_addr: &Addr, key: &[u8]) -> Vec<u8> {
+    let contract_namespace = [b"contract_data/", contract_addr.as_bytes()].concat();
+    let mut storage_key = vec![];
+    for namespace in [b"wasm".as_slice(), &contract_namespace] {
+        storage_key.extend_from_slice(&(namespace.len() as u16).to_be_bytes());
+        storage_key.extend_from_slice(namespace);
+    }
+    storage_key.extend_from_slice(key);
+    storage_key
+}
+
+#[test]
+fn successful_migrate_from_v0_0_1() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let app = env.app;
+    let abs = env.abs;
+
+    let mock_env = abs.environment();
+
+    // Installs of 0.0.1 had no config
+    let app_addr = app.address()?;
+    {
+        let mut mock_app = mock_env.app.borrow_mut();
+        let storage = mock_app.storage_mut();
+        storage.set(
+            &contract_storage_key(&app_addr, b"contract_info"),
+            format!(r#"{{"contract":"{QA_APP_ID}","version":"0.0.1"}}"#).as_bytes(),
+        );
+        storage.remove(&contract_storage_key(&app_addr, b"CONFIG"));
+    }
+    let admin = mock_env
+        .app
+        .borrow()
+        .contract_data(&app_addr)
+        .unwrap()
+        .admin
+        .unwrap();
+    app.call_as(&admin).migrate(
+        &MigrateMsg {
+            base: BaseMigrateMsg {},
+            module: QAAppMigrateMsg {},
+        },
+        app.code_id()?,
+    )?;
+
+    let ask_cost_resp = app.ask_cost()?;
+    let asker_addr = &mock_env.addr_make(USER1);
+    mock_env.set_balance(
+        asker_addr,
+        coins(ask_cost_resp.cost.u128(), ask_cost_resp.fee_denom.clone()),
+    )?;
+    app.call_as(asker_addr).ask(
+        "this is a question".to_string(),
+        &coins(ask_cost_resp.cost.u128(), ask_cost_resp.fee_denom.clone()),
+    )?;
+
+    // The migrated config deposits no holder reward
+    assert_eq!(
+        mock_env.query_balance(
+            &ask_cost_resp.ask_fee_recipients[0].address,
+            ask_cost_resp.fee_denom.as_str()
+        )?,
+        ask_cost_resp.cost
+    );
+
+    Ok(())
+}